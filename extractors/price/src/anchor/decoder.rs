@@ -0,0 +1,385 @@
+use super::{
+    errors::AnchorIdlError,
+    hash::{hash_anchor_discriminator, hash_anchor_event_discriminator},
+    idl::{
+        to_snake_case, Idl, IdlAccountItem, IdlDefinedFields, IdlField, IdlType, IdlTypeDefBody,
+    },
+    value::IdlValue,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, fs};
+
+// emit_cpi! calls back into the program with this tag ahead of the event
+pub const EVENT_IX_TAG: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+#[derive(Debug)]
+pub struct DecodedInstruction {
+    pub name: String,
+    // IDL account names with the addresses passed for them, remaining accounts are dropped
+    pub accounts: Vec<(String, String)>,
+}
+
+impl DecodedInstruction {
+    pub fn account(&self, name: &str) -> Option<&str> {
+        self.accounts
+            .iter()
+            .find(|(account_name, _)| account_name == name)
+            .map(|(_, address)| address.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct DecodedEvent {
+    pub name: String,
+    pub fields: IdlValue,
+}
+
+#[derive(Debug)]
+struct InstructionLayout {
+    name: String,
+    accounts: Vec<String>,
+    args: Vec<IdlField>,
+}
+
+#[derive(Debug)]
+struct EventLayout {
+    name: String,
+    body: IdlTypeDefBody,
+}
+
+// Decodes instructions and events of one Anchor program from its IDL, keyed by discriminator.
+#[derive(Debug)]
+pub struct AnchorDecoder {
+    instructions: HashMap<[u8; 8], InstructionLayout>,
+    events: HashMap<[u8; 8], EventLayout>,
+    types: HashMap<String, IdlTypeDefBody>,
+}
+
+fn to_discriminator(bytes: &[u8], name: &str) -> Result<[u8; 8], AnchorIdlError> {
+    bytes
+        .try_into()
+        .map_err(|_| AnchorIdlError::InvalidDiscriminator(name.to_string()))
+}
+
+fn flatten_accounts(items: &[IdlAccountItem], accounts: &mut Vec<String>) {
+    for item in items {
+        match item {
+            IdlAccountItem::Composite { accounts: nested } => flatten_accounts(nested, accounts),
+            IdlAccountItem::Single { name } => accounts.push(to_snake_case(name)),
+        }
+    }
+}
+
+impl AnchorDecoder {
+    pub fn from_file(path: &str) -> Result<Self, AnchorIdlError> {
+        let json = fs::read_to_string(path).map_err(AnchorIdlError::Read)?;
+        Self::from_json(&json)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, AnchorIdlError> {
+        let idl: Idl = serde_json::from_str(json).map_err(AnchorIdlError::Parse)?;
+        Self::from_idl(idl)
+    }
+
+    pub fn from_idl(idl: Idl) -> Result<Self, AnchorIdlError> {
+        let types: HashMap<String, IdlTypeDefBody> = idl
+            .types
+            .into_iter()
+            .map(|type_def| (type_def.name, type_def.ty))
+            .collect();
+
+        let mut instructions = HashMap::with_capacity(idl.instructions.len());
+        for instruction in idl.instructions {
+            let discriminator = match &instruction.discriminator {
+                Some(bytes) => to_discriminator(bytes, &instruction.name)?,
+                None => hash_anchor_discriminator(&to_snake_case(&instruction.name)),
+            };
+
+            let mut accounts = Vec::with_capacity(instruction.accounts.len());
+            flatten_accounts(&instruction.accounts, &mut accounts);
+
+            instructions.insert(
+                discriminator,
+                InstructionLayout {
+                    name: to_snake_case(&instruction.name),
+                    accounts,
+                    args: instruction.args,
+                },
+            );
+        }
+
+        let mut events = HashMap::with_capacity(idl.events.len());
+        for event in idl.events {
+            let discriminator = match &event.discriminator {
+                Some(bytes) => to_discriminator(bytes, &event.name)?,
+                None => hash_anchor_event_discriminator(&event.name),
+            };
+
+            // legacy IDLs list event fields inline, newer ones describe the event in `types`
+            let body = match event.fields {
+                Some(fields) => IdlTypeDefBody::Struct {
+                    fields: Some(IdlDefinedFields::Named(fields)),
+                },
+                None => types
+                    .get(&event.name)
+                    .cloned()
+                    .ok_or_else(|| AnchorIdlError::UnknownType(event.name.clone()))?,
+            };
+
+            events.insert(
+                discriminator,
+                EventLayout {
+                    name: event.name,
+                    body,
+                },
+            );
+        }
+
+        Ok(Self {
+            instructions,
+            events,
+            types,
+        })
+    }
+
+    // `accounts` are the addresses passed to the instruction, in order.
+    // Ok(None) when the data does not start with a known instruction discriminator.
+    pub fn decode_instruction(
+        &self,
+        data: &[u8],
+        accounts: &[String],
+    ) -> Result<Option<DecodedInstruction>, AnchorIdlError> {
+        let layout = match data.get(..8).and_then(|prefix| {
+            let discriminator: [u8; 8] = prefix.try_into().ok()?;
+            self.instructions.get(&discriminator)
+        }) {
+            Some(layout) => layout,
+            None => return Ok(None),
+        };
+
+        // the args are only decoded to reject data that does not fit the instruction
+        let mut reader = BorshReader::new(&data[8..]);
+        self.decode_named_fields(&layout.args, &mut reader)?;
+
+        let accounts = layout
+            .accounts
+            .iter()
+            .zip(accounts)
+            .map(|(name, address)| (name.clone(), address.clone()))
+            .collect();
+
+        Ok(Some(DecodedInstruction {
+            name: layout.name.clone(),
+            accounts,
+        }))
+    }
+
+    // `data` is the event as emitted, discriminator first.
+    // Ok(None) when the data does not start with a known event discriminator.
+    pub fn decode_event(&self, data: &[u8]) -> Result<Option<DecodedEvent>, AnchorIdlError> {
+        let layout = match data.get(..8).and_then(|prefix| {
+            let discriminator: [u8; 8] = prefix.try_into().ok()?;
+            self.events.get(&discriminator)
+        }) {
+            Some(layout) => layout,
+            None => return Ok(None),
+        };
+
+        let mut reader = BorshReader::new(&data[8..]);
+        let fields = self.decode_body(&layout.body, &mut reader)?;
+
+        Ok(Some(DecodedEvent {
+            name: layout.name.clone(),
+            fields,
+        }))
+    }
+
+    // `data` is the instruction data of a self invocation made by emit_cpi!.
+    // Ok(None) when it is not an event instruction or the event is unknown.
+    pub fn decode_cpi_event(&self, data: &[u8]) -> Result<Option<DecodedEvent>, AnchorIdlError> {
+        match data.get(..8) {
+            Some(tag) if tag == EVENT_IX_TAG => self.decode_event(&data[8..]),
+            _ => Ok(None),
+        }
+    }
+
+    fn decode_named_fields(
+        &self,
+        fields: &[IdlField],
+        reader: &mut BorshReader,
+    ) -> Result<IdlValue, AnchorIdlError> {
+        let mut values = Vec::with_capacity(fields.len());
+        for field in fields {
+            values.push((
+                to_snake_case(&field.name),
+                self.decode_type(&field.ty, reader)?,
+            ));
+        }
+        Ok(IdlValue::Struct(values))
+    }
+
+    fn decode_fields(
+        &self,
+        fields: Option<&IdlDefinedFields>,
+        reader: &mut BorshReader,
+    ) -> Result<IdlValue, AnchorIdlError> {
+        match fields {
+            None => Ok(IdlValue::Struct(Vec::new())),
+            Some(IdlDefinedFields::Named(fields)) => self.decode_named_fields(fields, reader),
+            Some(IdlDefinedFields::Tuple(types)) => {
+                let mut values = Vec::with_capacity(types.len());
+                for ty in types {
+                    values.push(self.decode_type(ty, reader)?);
+                }
+                Ok(IdlValue::Tuple(values))
+            }
+        }
+    }
+
+    fn decode_body(
+        &self,
+        body: &IdlTypeDefBody,
+        reader: &mut BorshReader,
+    ) -> Result<IdlValue, AnchorIdlError> {
+        match body {
+            IdlTypeDefBody::Struct { fields } => self.decode_fields(fields.as_ref(), reader),
+            IdlTypeDefBody::Enum { variants } => {
+                let tag = reader.read_unsigned(1, "enum variant")? as u32;
+                let variant = variants
+                    .get(tag as usize)
+                    .ok_or_else(|| AnchorIdlError::InvalidTag("enum variant".to_string(), tag))?;
+                let fields = match &variant.fields {
+                    None => None,
+                    Some(fields) => Some(Box::new(self.decode_fields(Some(fields), reader)?)),
+                };
+                Ok(IdlValue::Enum(variant.name.clone(), fields))
+            }
+        }
+    }
+
+    fn decode_type(
+        &self,
+        ty: &IdlType,
+        reader: &mut BorshReader,
+    ) -> Result<IdlValue, AnchorIdlError> {
+        match ty {
+            IdlType::Primitive(name) => match name.as_str() {
+                "bool" => match reader.read_unsigned(1, name)? {
+                    0 => Ok(IdlValue::Bool(false)),
+                    1 => Ok(IdlValue::Bool(true)),
+                    tag => Err(AnchorIdlError::InvalidTag(name.clone(), tag as u32)),
+                },
+                "u8" => Ok(IdlValue::Unsigned(reader.read_unsigned(1, name)?)),
+                "u16" => Ok(IdlValue::Unsigned(reader.read_unsigned(2, name)?)),
+                "u32" => Ok(IdlValue::Unsigned(reader.read_unsigned(4, name)?)),
+                "u64" => Ok(IdlValue::Unsigned(reader.read_unsigned(8, name)?)),
+                "u128" => Ok(IdlValue::Unsigned(reader.read_unsigned(16, name)?)),
+                "i8" => Ok(IdlValue::Signed(reader.read_signed(1, name)?)),
+                "i16" => Ok(IdlValue::Signed(reader.read_signed(2, name)?)),
+                "i32" => Ok(IdlValue::Signed(reader.read_signed(4, name)?)),
+                "i64" => Ok(IdlValue::Signed(reader.read_signed(8, name)?)),
+                "i128" => Ok(IdlValue::Signed(reader.read_signed(16, name)?)),
+                "f32" => {
+                    let bits = reader.read_unsigned(4, name)? as u32;
+                    Ok(IdlValue::Float(f32::from_bits(bits) as f64))
+                }
+                "f64" => {
+                    let bits = reader.read_unsigned(8, name)? as u64;
+                    Ok(IdlValue::Float(f64::from_bits(bits)))
+                }
+                "string" => {
+                    let len = reader.read_unsigned(4, name)? as usize;
+                    let bytes = reader.take(len, name)?.to_vec();
+                    let string = String::from_utf8(bytes).map_err(AnchorIdlError::InvalidUtf8)?;
+                    Ok(IdlValue::String(string))
+                }
+                "bytes" => {
+                    let len = reader.read_unsigned(4, name)? as usize;
+                    Ok(IdlValue::Bytes(reader.take(len, name)?.to_vec()))
+                }
+                "publicKey" | "pubkey" => {
+                    let bytes: [u8; 32] = reader.take(32, name)?.try_into().unwrap();
+                    Ok(IdlValue::Pubkey(Pubkey::new_from_array(bytes)))
+                }
+                _ => Err(AnchorIdlError::UnknownType(name.clone())),
+            },
+            IdlType::Option { option } => match reader.read_unsigned(1, "option")? {
+                0 => Ok(IdlValue::Option(None)),
+                1 => Ok(IdlValue::Option(Some(Box::new(
+                    self.decode_type(option, reader)?,
+                )))),
+                tag => Err(AnchorIdlError::InvalidTag("option".to_string(), tag as u32)),
+            },
+            IdlType::COption { coption } => match reader.read_unsigned(4, "coption")? {
+                0 => Ok(IdlValue::Option(None)),
+                1 => Ok(IdlValue::Option(Some(Box::new(
+                    self.decode_type(coption, reader)?,
+                )))),
+                tag => Err(AnchorIdlError::InvalidTag(
+                    "coption".to_string(),
+                    tag as u32,
+                )),
+            },
+            IdlType::Vec { vec } => {
+                let len = reader.read_unsigned(4, "vec")? as usize;
+                let mut values = Vec::with_capacity(len.min(reader.remaining()));
+                for _ in 0..len {
+                    values.push(self.decode_type(vec, reader)?);
+                }
+                Ok(IdlValue::List(values))
+            }
+            IdlType::Array { array: (ty, len) } => {
+                let mut values = Vec::with_capacity(*len);
+                for _ in 0..*len {
+                    values.push(self.decode_type(ty, reader)?);
+                }
+                Ok(IdlValue::List(values))
+            }
+            IdlType::Defined { defined } => {
+                let body = self
+                    .types
+                    .get(defined.name())
+                    .ok_or_else(|| AnchorIdlError::UnknownType(defined.name().to_string()))?;
+                self.decode_body(body, reader)
+            }
+        }
+    }
+}
+
+// Little endian borsh reads. Trailing bytes are left alone, programs append fields to events
+// and instructions without breaking older layouts.
+struct BorshReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BorshReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.offset
+    }
+
+    fn take(&mut self, len: usize, name: &str) -> Result<&'a [u8], AnchorIdlError> {
+        if self.remaining() < len {
+            return Err(AnchorIdlError::UnexpectedEnd(name.to_string()));
+        }
+        let bytes = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_unsigned(&mut self, size: usize, name: &str) -> Result<u128, AnchorIdlError> {
+        let mut bytes = [0u8; 16];
+        bytes[..size].copy_from_slice(self.take(size, name)?);
+        Ok(u128::from_le_bytes(bytes))
+    }
+
+    fn read_signed(&mut self, size: usize, name: &str) -> Result<i128, AnchorIdlError> {
+        let shift = 128 - size as u32 * 8;
+        let unsigned = self.read_unsigned(size, name)?;
+        Ok(((unsigned << shift) as i128) >> shift)
+    }
+}
@@ -0,0 +1,37 @@
+#[derive(Debug)]
+pub enum AnchorIdlError {
+    Read(std::io::Error),
+    Parse(serde_json::Error),
+    UnknownType(String),
+    UnexpectedEnd(String),
+    InvalidUtf8(std::string::FromUtf8Error),
+    InvalidTag(String, u32),
+    InvalidDiscriminator(String),
+}
+
+impl std::fmt::Display for AnchorIdlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Read(err) => write!(f, "Failed to read IDL: {}", err),
+            Self::Parse(err) => write!(f, "Failed to parse IDL: {}", err),
+            Self::UnknownType(name) => write!(f, "Unknown IDL type: {}", name),
+            Self::UnexpectedEnd(name) => write!(f, "Data ended while reading {}", name),
+            Self::InvalidUtf8(err) => write!(f, "Invalid utf8 string: {}", err),
+            Self::InvalidTag(name, tag) => write!(f, "Invalid tag {} for {}", tag, name),
+            Self::InvalidDiscriminator(name) => {
+                write!(f, "Discriminator of {} is not 8 bytes", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnchorIdlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read(err) => Some(err),
+            Self::Parse(err) => Some(err),
+            Self::InvalidUtf8(err) => Some(err),
+            _ => None,
+        }
+    }
+}
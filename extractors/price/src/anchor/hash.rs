@@ -1,10 +1,22 @@
 use sha2::{Digest, Sha256};
 
-pub fn hash_anchor_discriminator(name: &str) -> [u8; 8] {
+fn hash_discriminator(preimage: &str) -> [u8; 8] {
     let mut hasher = Sha256::new();
-    hasher.update(format!("global:{}", name));
+    hasher.update(preimage);
     let hash = hasher.finalize();
     let mut discriminator = [0u8; 8];
     discriminator.copy_from_slice(&hash[..8]);
     discriminator
 }
+
+pub fn hash_anchor_discriminator(name: &str) -> [u8; 8] {
+    hash_discriminator(&format!("global:{}", name))
+}
+
+pub fn hash_anchor_event_discriminator(name: &str) -> [u8; 8] {
+    hash_discriminator(&format!("event:{}", name))
+}
+
+pub fn hash_anchor_account_discriminator(name: &str) -> [u8; 8] {
+    hash_discriminator(&format!("account:{}", name))
+}
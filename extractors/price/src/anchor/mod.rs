@@ -1 +1,5 @@
+pub mod decoder;
+pub mod errors;
 pub mod hash;
+pub mod idl;
+pub mod value;
@@ -0,0 +1,66 @@
+use solana_sdk::pubkey::Pubkey;
+
+// A decoded borsh value, shaped by the IDL type it was read with. Struct field names are
+// normalized to snake case so lookups don't depend on the IDL layout.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdlValue {
+    Bool(bool),
+    Unsigned(u128),
+    Signed(i128),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Pubkey(Pubkey),
+    Option(Option<Box<IdlValue>>),
+    List(Vec<IdlValue>),
+    Struct(Vec<(String, IdlValue)>),
+    Tuple(Vec<IdlValue>),
+    Enum(String, Option<Box<IdlValue>>),
+}
+
+impl IdlValue {
+    pub fn get(&self, name: &str) -> Option<&IdlValue> {
+        match self {
+            Self::Struct(fields) => fields
+                .iter()
+                .find(|(field_name, _)| field_name == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Unsigned(value) => u64::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Signed(value) => i64::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_pubkey(&self) -> Option<Pubkey> {
+        match self {
+            Self::Pubkey(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{
+    jupiter::program::JUPITER_ADDRESS,
+    meteora_dlmm::program::METEORA_DLMM_ADDRESS,
+    orca_whirlpool::program::ORCA_WHIRLPOOL_ADDRESS,
+    pump_fun::tokens::{pump_fun_tokens_from_instructions, PumpFunToken},
+    pump_swap::program::PUMP_SWAP_ADDRESS,
+    raydium_clmm::program::RAYDIUM_CLMM_ADDRESS,
+    raydium_cpmm::program::RAYDIUM_CPMM_ADDRESS,
+    trades::trades::{rows_from_instructions, TransactionRows},
+    transactions::{
+        costs::transaction_costs,
+        parse::{full_account_keys, parse_transaction_with_logs, raw_message},
+        status::is_failed_transaction,
+    },
+    utils::log::log_time,
+};
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiConfirmedBlock};
+
+#[derive(Debug, Default)]
+pub struct BlockContents {
+    pub transaction_count: u64,
+    pub rows: TransactionRows,
+    pub tokens: Vec<PumpFunToken>,
+}
+
+pub fn trades_and_tokens_from_block(
+    block: UiConfirmedBlock,
+    slot: u64,
+    pump_fun_program_address: &Pubkey,
+    raydium_amm_program_address: &Pubkey,
+) -> BlockContents {
+    let transactions = block.transactions.unwrap_or_default();
+    let mut contents = BlockContents {
+        transaction_count: transactions.len() as u64,
+        ..Default::default()
+    };
+
+    let pump_fun_program_address_string = pump_fun_program_address.to_string();
+    let raydium_amm_program_address_string = raydium_amm_program_address.to_string();
+
+    for transaction in transactions {
+        let tx = EncodedConfirmedTransactionWithStatusMeta {
+            slot,
+            transaction,
+            block_time: block.block_time,
+        };
+
+        if is_failed_transaction(&tx) {
+            continue;
+        }
+
+        let (signature, raw_message) = match raw_message(&tx.transaction.transaction) {
+            Some(message) => message,
+            None => continue,
+        };
+
+        // most of a block is unrelated. Programs invoked through another program, like a
+        // pool swapped on by a route, can be loaded from a lookup table
+        if !full_account_keys(raw_message, &tx.transaction.meta)
+            .iter()
+            .any(|account_key| {
+                *account_key == pump_fun_program_address_string
+                    || account_key == PUMP_SWAP_ADDRESS
+                    || *account_key == raydium_amm_program_address_string
+                    || account_key == RAYDIUM_CPMM_ADDRESS
+                    || account_key == RAYDIUM_CLMM_ADDRESS
+                    || account_key == ORCA_WHIRLPOOL_ADDRESS
+                    || account_key == METEORA_DLMM_ADDRESS
+                    || account_key == JUPITER_ADDRESS
+            })
+        {
+            continue;
+        }
+
+        let instructions_with_logs = parse_transaction_with_logs(
+            &tx.transaction.meta,
+            raw_message,
+            pump_fun_program_address,
+            raydium_amm_program_address,
+        );
+
+        contents
+            .tokens
+            .extend(pump_fun_tokens_from_instructions(&instructions_with_logs));
+
+        let block_time = match block.block_time {
+            Some(block_time) => block_time as u64,
+            None => continue,
+        };
+
+        let costs = transaction_costs(raw_message, &tx.transaction.meta);
+        match rows_from_instructions(&instructions_with_logs, &costs, slot, block_time, signature) {
+            Ok(rows) => contents.rows.extend(rows),
+            Err(error) => println!(
+                "{} Could not extract trades from {} in slot {}: {}",
+                log_time(),
+                signature,
+                slot,
+                error
+            ),
+        }
+    }
+
+    contents
+}
@@ -0,0 +1,17 @@
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+
+pub const BLOCK_CONFIG: RpcBlockConfig = RpcBlockConfig {
+    encoding: Some(UiTransactionEncoding::Json),
+    transaction_details: Some(TransactionDetails::Full),
+    rewards: Some(false),
+    commitment: Some(CommitmentConfig::confirmed()),
+    max_supported_transaction_version: Some(0),
+};
+
+// slots listed per getBlocks request
+pub const BLOCK_SLOTS_PAGE_SIZE: u64 = 1_000;
+
+// a claimed slot that never gets a status (crashed worker) is free again after this
+pub const SLOT_CLAIM_TTL_MS: u64 = 300_000;
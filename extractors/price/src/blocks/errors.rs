@@ -0,0 +1,32 @@
+use crate::{crawl_status::errors::CrawlStatusQueryError, rpc::errors::RpcError};
+use redis::RedisError;
+
+#[derive(Debug)]
+pub enum BlockCrawlError {
+    CrawledSlotsQueryFailed(CrawlStatusQueryError),
+    SlotsFetchFailed(RpcError),
+    SlotClaimFailed(RedisError),
+    BlockFetchFailed(RpcError),
+}
+
+impl std::fmt::Display for BlockCrawlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CrawledSlotsQueryFailed(err) => write!(f, "Failed to get crawled slots: {}", err),
+            Self::SlotsFetchFailed(err) => write!(f, "Failed to get confirmed slots: {}", err),
+            Self::SlotClaimFailed(err) => write!(f, "Failed to claim slot: {}", err),
+            Self::BlockFetchFailed(err) => write!(f, "Failed to fetch block: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for BlockCrawlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::CrawledSlotsQueryFailed(err) => Some(err),
+            Self::SlotsFetchFailed(err) => Some(err),
+            Self::SlotClaimFailed(err) => Some(err),
+            Self::BlockFetchFailed(err) => Some(err),
+        }
+    }
+}
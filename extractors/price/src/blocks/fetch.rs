@@ -0,0 +1,43 @@
+use super::config::BLOCK_CONFIG;
+use crate::rpc::{errors::RpcError, pool::RpcPoolManager};
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_transaction_status::UiConfirmedBlock;
+
+// Slots in the inclusive range that produced a block, everything else was skipped.
+pub async fn get_confirmed_slots(
+    rpc_pool_manager: &RpcPoolManager,
+    from_slot: u64,
+    to_slot: u64,
+    pin: Option<u64>,
+) -> Result<Vec<u64>, RpcError> {
+    rpc_pool_manager
+        .execute(
+            RpcRequest::GetBlocks,
+            |client| async move {
+                client
+                    .get_blocks_with_commitment(
+                        from_slot,
+                        Some(to_slot),
+                        CommitmentConfig::confirmed(),
+                    )
+                    .await
+            },
+            pin,
+        )
+        .await
+}
+
+pub async fn get_block(
+    rpc_pool_manager: &RpcPoolManager,
+    slot: u64,
+    pin: Option<u64>,
+) -> Result<UiConfirmedBlock, RpcError> {
+    rpc_pool_manager
+        .execute(
+            RpcRequest::GetBlock,
+            |client| async move { client.get_block_with_config(slot, BLOCK_CONFIG).await },
+            pin,
+        )
+        .await
+}
@@ -0,0 +1,5 @@
+pub mod block;
+pub mod config;
+pub mod errors;
+pub mod fetch;
+pub mod threads;
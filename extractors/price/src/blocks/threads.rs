@@ -0,0 +1,336 @@
+use super::{
+    block::trades_and_tokens_from_block,
+    config::{BLOCK_SLOTS_PAGE_SIZE, SLOT_CLAIM_TTL_MS},
+    errors::BlockCrawlError,
+    fetch::{get_block, get_confirmed_slots},
+};
+use crate::{
+    crawl_status::{
+        channels::create_slot_crawl_status,
+        inserts::claim_slot,
+        queries::get_crawled_slots,
+        table::{CrawlStatus, CrawlStatusOperation, SlotCrawlStatusRow},
+    },
+    dragonfly::client::dragonfly_client,
+    jupiter::table::JupiterRouteRow,
+    pools::table::PoolRow,
+    pump_fun::{
+        params::table::CurveParamsRow,
+        program::program::get_pump_fun_program_address,
+        tokens::{MintAddress, PumpFunToken},
+    },
+    raydium::amm::get_raydium_amm_program_address,
+    rpc::pool::RpcPoolManager,
+    termination::{is_terminated, terminate, terminate_on_error, TerminationFlag},
+    token::events::table::TokenEventRow,
+    trades::db::table::TradeRow,
+    utils::{blocking::blocking_call, channels::recv_batch, log::log_time},
+};
+use crossbeam::channel::Sender;
+use dashmap::DashSet;
+use futures_util::{stream, StreamExt};
+use std::{collections::HashSet, sync::Arc, thread};
+
+fn slot_crawl_status(
+    slot: u64,
+    transaction_count: u64,
+    error: Option<String>,
+) -> SlotCrawlStatusRow {
+    SlotCrawlStatusRow {
+        slot,
+        status: if error.is_some() {
+            CrawlStatus::Failed
+        } else {
+            CrawlStatus::Succeeded
+        },
+        transaction_count,
+        error,
+    }
+}
+
+// Lists the range a page at a time and queues every slot that has a block and has not
+// been crawled yet. Skipped slots are recorded as crawled with no transactions.
+fn block_slots_thread(
+    from_slot: u64,
+    to_slot: u64,
+    slots_tx: Sender<u64>,
+    crawl_status_tx: &Sender<CrawlStatusOperation>,
+    rpc_pool_manager: &RpcPoolManager,
+    termination_flag: &TerminationFlag,
+) -> thread::JoinHandle<()> {
+    let log_tag = format!("     {} block slots | ", log_time());
+
+    let crawl_status_tx = crawl_status_tx.clone();
+    let rpc_pool_manager = rpc_pool_manager.clone();
+    let termination_flag = termination_flag.clone();
+
+    thread::spawn(move || {
+        let dragonfly_client = dragonfly_client();
+        let mut page_start = from_slot;
+
+        blocking_call(async {
+            while page_start <= to_slot {
+                if is_terminated(&termination_flag) {
+                    println!("{} Termination flag set. Exiting", log_tag);
+                    break;
+                }
+
+                let page_end = page_start
+                    .saturating_add(BLOCK_SLOTS_PAGE_SIZE - 1)
+                    .min(to_slot);
+
+                let crawled_slots = terminate_on_error(
+                    &termination_flag,
+                    get_crawled_slots(&dragonfly_client, page_start, page_end)
+                        .map_err(BlockCrawlError::CrawledSlotsQueryFailed),
+                );
+
+                let confirmed_slots =
+                    get_confirmed_slots(&rpc_pool_manager, page_start, page_end, None).await;
+
+                match confirmed_slots {
+                    Ok(confirmed_slots) => {
+                        let confirmed_slots = confirmed_slots.into_iter().collect::<HashSet<_>>();
+                        let mut queued = 0;
+
+                        for slot in page_start..=page_end {
+                            if crawled_slots.contains(&slot) {
+                                continue;
+                            }
+
+                            if !confirmed_slots.contains(&slot) {
+                                terminate_on_error(
+                                    &termination_flag,
+                                    create_slot_crawl_status(
+                                        &crawl_status_tx,
+                                        slot_crawl_status(slot, 0, None),
+                                    ),
+                                );
+                                continue;
+                            }
+
+                            terminate_on_error(&termination_flag, slots_tx.send(slot));
+                            queued += 1;
+                        }
+
+                        println!(
+                            "{} Queued {} of slots {}..={} ({} already crawled)",
+                            log_tag,
+                            queued,
+                            page_start,
+                            page_end,
+                            crawled_slots.len()
+                        );
+                    }
+                    Err(error) if !error.is_fatal() => {
+                        // left without a status, so the next run picks these slots up again
+                        println!(
+                            "{} {}. Skipping slots {}..={}",
+                            log_tag,
+                            BlockCrawlError::SlotsFetchFailed(error),
+                            page_start,
+                            page_end
+                        );
+                    }
+                    Err(error) => {
+                        println!("{} {}", log_tag, BlockCrawlError::SlotsFetchFailed(error));
+                        terminate(&termination_flag);
+                    }
+                }
+
+                page_start = page_end + 1;
+            }
+        });
+
+        println!("{} Queued all slots up to {}", log_tag, to_slot);
+    })
+}
+
+pub fn block_range_threads(
+    from_slot: u64,
+    to_slot: u64,
+    pump_fun_tokens_tx: &Sender<PumpFunToken>,
+    trades_tx: &Sender<TradeRow>,
+    unresolved_trades_tx: &Sender<TradeRow>,
+    token_events_tx: &Sender<TokenEventRow>,
+    pools_tx: &Sender<PoolRow>,
+    jupiter_routes_tx: &Sender<JupiterRouteRow>,
+    curve_params_tx: &Sender<CurveParamsRow>,
+    crawl_status_tx: &Sender<CrawlStatusOperation>,
+    rpc_pool_manager: &RpcPoolManager,
+    termination_flag: &TerminationFlag,
+) -> Vec<thread::JoinHandle<()>> {
+    let concurrency = rpc_pool_manager.get_rpc_nodes_count();
+    let max_in_flight = rpc_pool_manager.get_max_in_flight();
+    let mut handles = Vec::with_capacity(concurrency + 1);
+
+    let (slots_tx, slots_rx) =
+        crossbeam::channel::bounded::<u64>(concurrency.max(1) * max_in_flight * 2);
+
+    handles.push(block_slots_thread(
+        from_slot,
+        to_slot,
+        slots_tx,
+        crawl_status_tx,
+        rpc_pool_manager,
+        termination_flag,
+    ));
+
+    let pump_fun_program_address = get_pump_fun_program_address();
+    let raydium_amm_program_address = get_raydium_amm_program_address();
+
+    // tokens are only sent on first sight, a busy token trades in most blocks
+    let seen_tokens: Arc<DashSet<MintAddress>> = Arc::new(DashSet::new());
+
+    for thread_index in 0..concurrency {
+        let log_tag = format!("         {} blocks #{} | ", log_time(), thread_index);
+
+        let slots_rx = slots_rx.clone();
+        let pump_fun_tokens_tx = pump_fun_tokens_tx.clone();
+        let trades_tx = trades_tx.clone();
+        let unresolved_trades_tx = unresolved_trades_tx.clone();
+        let token_events_tx = token_events_tx.clone();
+        let pools_tx = pools_tx.clone();
+        let jupiter_routes_tx = jupiter_routes_tx.clone();
+        let curve_params_tx = curve_params_tx.clone();
+        let crawl_status_tx = crawl_status_tx.clone();
+        let rpc_pool_manager = rpc_pool_manager.clone();
+        let termination_flag = termination_flag.clone();
+        let seen_tokens = seen_tokens.clone();
+
+        let handle = thread::spawn(move || {
+            let dragonfly_client = dragonfly_client();
+
+            blocking_call(async {
+                while let Some(slots) = recv_batch(&slots_rx, max_in_flight) {
+                    if is_terminated(&termination_flag) {
+                        println!("{} Termination flag set. Exiting", log_tag);
+                        break;
+                    }
+
+                    let mut claimed_slots = Vec::with_capacity(slots.len());
+                    for slot in slots {
+                        let claimed = terminate_on_error(
+                            &termination_flag,
+                            claim_slot(&dragonfly_client, slot, SLOT_CLAIM_TTL_MS)
+                                .map_err(BlockCrawlError::SlotClaimFailed),
+                        );
+                        if !claimed {
+                            println!(
+                                "{} Slot {} claimed by another worker. Skipping",
+                                log_tag, slot
+                            );
+                            continue;
+                        }
+
+                        claimed_slots.push(slot);
+                    }
+
+                    // the claimed blocks are fetched together and handled in slot order
+                    let blocks = stream::iter(claimed_slots.iter().map(|slot| {
+                        get_block(&rpc_pool_manager, *slot, Some(thread_index as u64))
+                    }))
+                    .buffered(max_in_flight)
+                    .collect::<Vec<_>>()
+                    .await;
+
+                    for (slot, block) in claimed_slots.into_iter().zip(blocks) {
+                        match block {
+                            Ok(block) => {
+                                let contents = trades_and_tokens_from_block(
+                                    block,
+                                    slot,
+                                    &pump_fun_program_address,
+                                    &raydium_amm_program_address,
+                                );
+
+                                println!(
+                                    "{} Slot {}: {} transactions, {} trades",
+                                    log_tag,
+                                    slot,
+                                    contents.transaction_count,
+                                    contents.rows.trades.len()
+                                );
+
+                                for token in contents.tokens {
+                                    let (mint_address, _bonding_curve_address) = token;
+                                    if seen_tokens.insert(mint_address) {
+                                        terminate_on_error(
+                                            &termination_flag,
+                                            pump_fun_tokens_tx.send(token),
+                                        );
+                                    }
+                                }
+
+                                for trade in contents.rows.trades {
+                                    terminate_on_error(&termination_flag, trades_tx.send(trade));
+                                }
+
+                                for trade in contents.rows.unresolved_trades {
+                                    terminate_on_error(
+                                        &termination_flag,
+                                        unresolved_trades_tx.send(trade),
+                                    );
+                                }
+
+                                for token_event in contents.rows.token_events {
+                                    terminate_on_error(
+                                        &termination_flag,
+                                        token_events_tx.send(token_event),
+                                    );
+                                }
+
+                                for pool in contents.rows.pools {
+                                    terminate_on_error(&termination_flag, pools_tx.send(pool));
+                                }
+
+                                for jupiter_route in contents.rows.jupiter_routes {
+                                    terminate_on_error(
+                                        &termination_flag,
+                                        jupiter_routes_tx.send(jupiter_route),
+                                    );
+                                }
+
+                                for curve_params in contents.rows.curve_params {
+                                    terminate_on_error(
+                                        &termination_flag,
+                                        curve_params_tx.send(curve_params),
+                                    );
+                                }
+
+                                terminate_on_error(
+                                    &termination_flag,
+                                    create_slot_crawl_status(
+                                        &crawl_status_tx,
+                                        slot_crawl_status(slot, contents.transaction_count, None),
+                                    ),
+                                );
+                            }
+                            Err(error) => {
+                                let is_fatal = error.is_fatal();
+                                let error = BlockCrawlError::BlockFetchFailed(error);
+                                println!("{} Slot {}: {}", log_tag, slot, error);
+
+                                terminate_on_error(
+                                    &termination_flag,
+                                    create_slot_crawl_status(
+                                        &crawl_status_tx,
+                                        slot_crawl_status(slot, 0, Some(error.to_string())),
+                                    ),
+                                );
+
+                                if is_fatal {
+                                    terminate(&termination_flag);
+                                }
+                            }
+                        }
+                    }
+                }
+            })
+        });
+
+        handles.push(handle);
+    }
+
+    handles
+}
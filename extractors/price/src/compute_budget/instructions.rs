@@ -0,0 +1,26 @@
+use crate::instructions::parse::ParsableInstruction;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComputeBudgetInstruction {
+    SetComputeUnitLimit(u64),
+    // micro lamports per compute unit
+    SetComputeUnitPrice(u64),
+}
+
+pub fn parse_compute_budget_instruction(
+    parsable: &ParsableInstruction,
+) -> Option<ComputeBudgetInstruction> {
+    let data = bs58::decode(&parsable.data).into_vec().ok()?;
+
+    match *data.first()? {
+        2 => {
+            let units = u32::from_le_bytes(data.get(1..5)?.try_into().ok()?);
+            Some(ComputeBudgetInstruction::SetComputeUnitLimit(units as u64))
+        }
+        3 => {
+            let price = u64::from_le_bytes(data.get(1..9)?.try_into().ok()?);
+            Some(ComputeBudgetInstruction::SetComputeUnitPrice(price))
+        }
+        _ => None,
+    }
+}
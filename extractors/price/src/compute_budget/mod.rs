@@ -0,0 +1,2 @@
+pub mod instructions;
+pub mod program;
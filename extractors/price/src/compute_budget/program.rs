@@ -0,0 +1,28 @@
+pub const COMPUTE_BUDGET_PROGRAM_ADDRESS: &str = "ComputeBudget111111111111111111111111111111";
+
+// Limit given to every instruction of a transaction that doesn't set one, builtin programs
+// only get a small allocation, and the most a transaction can ask for
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+pub const MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT: u64 = 3_000;
+pub const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+
+pub const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
+// programs the runtime counts as builtins when it works out the default limit
+pub const BUILTIN_PROGRAM_ADDRESSES: [&str; 15] = [
+    "11111111111111111111111111111111",
+    COMPUTE_BUDGET_PROGRAM_ADDRESS,
+    "AddressLookupTab1e1111111111111111111111111",
+    "BPFLoader1111111111111111111111111111111111",
+    "BPFLoader2111111111111111111111111111111111",
+    "BPFLoaderUpgradeab1e11111111111111111111111",
+    "LoaderV411111111111111111111111111111111111",
+    "Config1111111111111111111111111111111111111",
+    "Stake11111111111111111111111111111111111111",
+    "Vote111111111111111111111111111111111111111",
+    "KeccakSecp256k11111111111111111111111111111",
+    "Ed25519SigVerify111111111111111111111111111",
+    "Secp256r1SigVerify1111111111111111111111111",
+    "ZkTokenProof1111111111111111111111111111111",
+    "ZkE1Gama1Proof11111111111111111111111111111",
+];
@@ -1,4 +1,2 @@
-pub const IS_MAINNET: bool = true;
-
 // TODO: should be dynamic based on number of rpc nodes, like RPC_CONCURRENCY is now
 pub const STORE_CONCURRENCY: usize = 2;
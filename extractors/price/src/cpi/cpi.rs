@@ -1,6 +1,24 @@
-use crate::pump_fun::cpi::PumpFunCpiLog;
+use crate::{
+    jupiter::cpi::JupiterSwapEvent,
+    meteora_dlmm::cpi::MeteoraDlmmSwapEvent,
+    pump_fun::cpi::{
+        PumpFunCompleteEvent, PumpFunCreateEvent, PumpFunSetParamsEvent, PumpFunTradeEvent,
+    },
+    pump_swap::cpi::{PumpSwapBuyEvent, PumpSwapSellEvent},
+    raydium_clmm::cpi::RaydiumClmmSwapEvent,
+    raydium_cpmm::cpi::RaydiumCpmmSwapEvent,
+};
 
 #[derive(Debug, Clone)]
 pub enum CpiLog {
-    PumpFun(PumpFunCpiLog),
+    PumpFunTrade(PumpFunTradeEvent),
+    PumpFunCreate(PumpFunCreateEvent),
+    PumpFunComplete(PumpFunCompleteEvent),
+    PumpFunSetParams(PumpFunSetParamsEvent),
+    PumpSwapBuy(PumpSwapBuyEvent),
+    PumpSwapSell(PumpSwapSellEvent),
+    RaydiumCpmmSwap(RaydiumCpmmSwapEvent),
+    RaydiumClmmSwap(RaydiumClmmSwapEvent),
+    MeteoraDlmmSwap(MeteoraDlmmSwapEvent),
+    JupiterSwap(JupiterSwapEvent),
 }
@@ -1,4 +1,4 @@
-use super::table::{CrawlStatusOperation, CrawlStatusRow};
+use super::table::{CrawlStatusOperation, CrawlStatusRow, SlotCrawlStatusRow};
 use crate::trades::errors::TradeCrawlError;
 use crossbeam::channel::Sender;
 
@@ -34,3 +34,12 @@ pub fn create_crawl_status(
         .send(CrawlStatusOperation::Create(crawl_status))
         .map_err(TradeCrawlError::CrawlStatusSend)
 }
+
+pub fn create_slot_crawl_status(
+    crawl_status_tx: &Sender<CrawlStatusOperation>,
+    slot_crawl_status: SlotCrawlStatusRow,
+) -> Result<(), TradeCrawlError> {
+    crawl_status_tx
+        .send(CrawlStatusOperation::CreateSlot(slot_crawl_status))
+        .map_err(TradeCrawlError::CrawlStatusSend)
+}
@@ -1,4 +1,7 @@
-use crate::crawl_status::table::{CrawlStatus, CrawlStatusRow, REDIS_TRANSACTION_STATUS_PREFIX};
+use crate::crawl_status::table::{
+    CrawlStatus, CrawlStatusRow, SlotCrawlStatusRow, REDIS_CRAWLED_SLOTS_KEY,
+    REDIS_SLOT_CLAIM_PREFIX, REDIS_TRANSACTION_STATUS_PREFIX,
+};
 use redis::{Client, RedisError};
 
 pub fn add_account_signature(
@@ -20,6 +23,14 @@ pub fn add_account_signature(
     Ok(())
 }
 
+fn status_str(status: CrawlStatus) -> &'static str {
+    match status {
+        CrawlStatus::Pending => "pending",
+        CrawlStatus::Failed => "failed",
+        CrawlStatus::Succeeded => "succeeded",
+    }
+}
+
 pub fn insert_crawl_status(
     client: &Client,
     crawl_status: &CrawlStatusRow,
@@ -27,11 +38,7 @@ pub fn insert_crawl_status(
 ) -> Result<(), RedisError> {
     let mut conn = client.get_connection()?;
 
-    let status_str = match crawl_status.status {
-        CrawlStatus::Pending => "pending",
-        CrawlStatus::Failed => "failed",
-        CrawlStatus::Succeeded => "succeeded",
-    };
+    let status_str = status_str(crawl_status.status);
 
     add_account_signature(client, crawl_status, batch_size)?;
 
@@ -120,3 +127,69 @@ pub fn mark_first_account_signature(
 
     Ok(())
 }
+
+pub fn insert_slot_crawl_status(
+    client: &Client,
+    slot_crawl_status: &SlotCrawlStatusRow,
+) -> Result<(), RedisError> {
+    let mut conn = client.get_connection()?;
+
+    // written once per slot with its final status, so there is no pending state to race
+    let mut cmd = redis::cmd("HSET");
+    cmd.arg(slot_crawl_status.slot_status_key())
+        .arg("slot")
+        .arg(slot_crawl_status.slot.to_string())
+        .arg("status")
+        .arg(status_str(slot_crawl_status.status))
+        .arg("transaction_count")
+        .arg(slot_crawl_status.transaction_count.to_string());
+
+    match &slot_crawl_status.error {
+        Some(error) => {
+            cmd.arg("error").arg(error);
+        }
+        None => {
+            // a retried slot that succeeds drops the error from the earlier attempt
+            let _: () = redis::cmd("HDEL")
+                .arg(slot_crawl_status.slot_status_key())
+                .arg("error")
+                .query(&mut conn)?;
+        }
+    }
+
+    let _: () = cmd.query(&mut conn)?;
+
+    if slot_crawl_status.status == CrawlStatus::Succeeded {
+        add_crawled_slot(client, slot_crawl_status.slot)?;
+    }
+
+    Ok(())
+}
+
+fn add_crawled_slot(client: &Client, slot: u64) -> Result<(), RedisError> {
+    let mut conn = client.get_connection()?;
+
+    let _: () = redis::cmd("ZADD")
+        .arg(REDIS_CRAWLED_SLOTS_KEY)
+        .arg(slot)
+        .arg(slot)
+        .query(&mut conn)?;
+
+    Ok(())
+}
+
+// Only one worker, in this process or another, crawls a slot at a time. The claim
+// expires so a crashed worker's slots are picked up again on the next run.
+pub fn claim_slot(client: &Client, slot: u64, ttl_ms: u64) -> Result<bool, RedisError> {
+    let mut conn = client.get_connection()?;
+
+    let claimed: Option<String> = redis::cmd("SET")
+        .arg(format!("{}:{}", REDIS_SLOT_CLAIM_PREFIX, slot))
+        .arg(1)
+        .arg("NX")
+        .arg("PX")
+        .arg(ttl_ms)
+        .query(&mut conn)?;
+
+    Ok(claimed.is_some())
+}
@@ -1,7 +1,8 @@
 use crate::crawl_status::table::{
-    REDIS_ACCOUNT_TRANSACTIONS_PREFIX, REDIS_TRANSACTION_STATUS_PREFIX,
+    REDIS_ACCOUNT_TRANSACTIONS_PREFIX, REDIS_CRAWLED_SLOTS_KEY, REDIS_TRANSACTION_STATUS_PREFIX,
 };
 use redis::Client;
+use std::collections::HashSet;
 
 use super::errors::CrawlStatusQueryError;
 
@@ -78,3 +79,85 @@ pub fn get_oldest_seen_signature(
 
     Ok(signatures.first().cloned())
 }
+
+// Scores are `slot + relative index / batch size` and a slot's signatures are listed newest
+// first, so the newest signature is the lowest index of the highest slot. Signatures that
+// are pending or failed were not crawled and are passed over.
+pub fn newest_succeeded_signature<F>(
+    mut signatures_with_scores: Vec<(String, f64)>,
+    mut is_succeeded: F,
+) -> Result<Option<String>, CrawlStatusQueryError>
+where
+    F: FnMut(&str) -> Result<bool, CrawlStatusQueryError>,
+{
+    signatures_with_scores
+        .sort_by(|(_, a), (_, b)| b.floor().total_cmp(&a.floor()).then_with(|| a.total_cmp(b)));
+
+    for (signature, _score) in signatures_with_scores {
+        if is_succeeded(&signature)? {
+            return Ok(Some(signature));
+        }
+    }
+
+    Ok(None)
+}
+
+pub fn get_newest_seen_signature(
+    client: &Client,
+    account_address: &str,
+) -> Result<Option<String>, CrawlStatusQueryError> {
+    let mut conn = client
+        .get_connection()
+        .map_err(|e| CrawlStatusQueryError::Redis(e))?;
+
+    let account_key = format!("{}:{}", REDIS_ACCOUNT_TRANSACTIONS_PREFIX, account_address);
+
+    let signatures_with_scores: Vec<(String, f64)> = redis::cmd("ZRANGE")
+        .arg(&account_key)
+        .arg(0)
+        .arg(-1)
+        .arg("WITHSCORES")
+        .query::<Vec<(String, f64)>>(&mut conn)
+        .map_err(|e| CrawlStatusQueryError::Redis(e))?;
+
+    newest_succeeded_signature(signatures_with_scores, |signature| {
+        has_signature_status(client, signature, vec!["succeeded"])
+    })
+}
+
+pub fn get_account_signatures(
+    client: &Client,
+    account_address: &str,
+) -> Result<Vec<String>, CrawlStatusQueryError> {
+    let mut conn = client
+        .get_connection()
+        .map_err(|e| CrawlStatusQueryError::Redis(e))?;
+
+    let account_key = format!("{}:{}", REDIS_ACCOUNT_TRANSACTIONS_PREFIX, account_address);
+
+    redis::cmd("ZRANGE")
+        .arg(&account_key)
+        .arg(0)
+        .arg(-1)
+        .query::<Vec<String>>(&mut conn)
+        .map_err(|e| CrawlStatusQueryError::Redis(e))
+}
+
+pub fn get_crawled_slots(
+    client: &Client,
+    from_slot: u64,
+    to_slot: u64,
+) -> Result<HashSet<u64>, CrawlStatusQueryError> {
+    let mut conn = client
+        .get_connection()
+        .map_err(|e| CrawlStatusQueryError::Redis(e))?;
+
+    let slots: Vec<u64> = redis::cmd("ZRANGEBYSCORE")
+        .arg(REDIS_CRAWLED_SLOTS_KEY)
+        .arg(from_slot)
+        .arg(to_slot)
+        .query::<Vec<u64>>(&mut conn)
+        .map_err(|e| CrawlStatusQueryError::Redis(e))?;
+
+    Ok(slots.into_iter().collect())
+}
@@ -1,7 +1,10 @@
 use crate::{
     constants::STORE_CONCURRENCY,
     crawl_status::{
-        inserts::{mark_crawl_failed, mark_crawl_success, mark_first_account_signature},
+        inserts::{
+            insert_slot_crawl_status, mark_crawl_failed, mark_crawl_success,
+            mark_first_account_signature,
+        },
         table::CrawlStatusOperation,
     },
     dragonfly::client::dragonfly_client,
@@ -42,6 +45,9 @@ pub fn store_crawl_statuses(
                     CrawlStatusOperation::MarkAsFirstAccountSignature(transaction_signature) => {
                         mark_first_account_signature(&client, &transaction_signature)
                     }
+                    CrawlStatusOperation::CreateSlot(slot_crawl_status) => {
+                        insert_slot_crawl_status(&client, &slot_crawl_status)
+                    }
                 };
 
                 match result {
@@ -59,3 +65,15 @@ pub fn store_crawl_statuses(
 
     handles
 }
+
+// Offline reparses replay signatures that were already crawled, their outcome says nothing
+// about the real crawl so it is dropped instead of overwriting the stored status.
+pub fn discard_crawl_statuses(
+    crawl_status_rx: &Receiver<CrawlStatusOperation>,
+) -> Vec<thread::JoinHandle<()>> {
+    let crawl_status_rx = crawl_status_rx.clone();
+
+    let handle = thread::spawn(move || while let Ok(_crawl_status) = crawl_status_rx.recv() {});
+
+    vec![handle]
+}
@@ -5,6 +5,9 @@ use crate::pump_fun::program::signatures::TransactionSignature;
 
 pub const REDIS_ACCOUNT_TRANSACTIONS_PREFIX: &str = "account";
 pub const REDIS_TRANSACTION_STATUS_PREFIX: &str = "tx";
+pub const REDIS_SLOT_STATUS_PREFIX: &str = "slot";
+pub const REDIS_SLOT_CLAIM_PREFIX: &str = "slot_claim";
+pub const REDIS_CRAWLED_SLOTS_KEY: &str = "slots:crawled";
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize_repr, Serialize_repr)]
 #[repr(u8)]
@@ -25,12 +28,21 @@ pub struct CrawlStatusRow {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SlotCrawlStatusRow {
+    pub slot: u64,
+    pub status: CrawlStatus,
+    pub transaction_count: u64,
+    pub error: Option<String>,
+}
+
 #[derive(Debug)]
 pub enum CrawlStatusOperation {
     Create(CrawlStatusRow),
     MarkAsSucceeded(TransactionSignature),
     MarkAsFailed(TransactionSignature, String),
     MarkAsFirstAccountSignature(TransactionSignature),
+    CreateSlot(SlotCrawlStatusRow),
 }
 
 impl CrawlStatusRow {
@@ -48,3 +60,9 @@ impl CrawlStatusRow {
         )
     }
 }
+
+impl SlotCrawlStatusRow {
+    pub fn slot_status_key(&self) -> String {
+        format!("{}:{}", REDIS_SLOT_STATUS_PREFIX, self.slot)
+    }
+}
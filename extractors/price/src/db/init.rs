@@ -3,8 +3,14 @@ use super::{
     db::create_db,
 };
 use crate::{
-    raydium::table::create_raydium_pools_table, token::table::create_tokens_table,
-    trades::db::table::create_trades_table,
+    jupiter::table::create_jupiter_routes_table,
+    pools::table::create_pools_table,
+    pump_fun::params::table::create_pump_fun_curve_params_table,
+    token::{
+        events::table::{create_token_events_table, create_token_lifecycles_view},
+        table::create_tokens_table,
+    },
+    trades::db::table::{create_pump_fun_curve_prices_view, create_trades_table},
 };
 use clickhouse::Client;
 
@@ -20,13 +26,30 @@ pub async fn init_db() -> Client {
 }
 
 async fn init_tables(client: &Client) {
-    let (trades_result, tokens_result, raydium_pools_result) = tokio::join!(
+    let (
+        trades_result,
+        tokens_result,
+        token_events_result,
+        pools_result,
+        jupiter_routes_result,
+        curve_params_result,
+    ) = tokio::join!(
         create_trades_table(&client),
         create_tokens_table(&client),
-        create_raydium_pools_table(&client),
+        create_token_events_table(&client),
+        create_pools_table(&client),
+        create_jupiter_routes_table(&client),
+        create_pump_fun_curve_params_table(&client),
     );
 
     trades_result.unwrap();
     tokens_result.unwrap();
-    raydium_pools_result.unwrap();
+    token_events_result.unwrap();
+    pools_result.unwrap();
+    jupiter_routes_result.unwrap();
+    curve_params_result.unwrap();
+
+    // the views read from the tables above, so those have to exist first
+    create_token_lifecycles_view(&client).await.unwrap();
+    create_pump_fun_curve_prices_view(&client).await.unwrap();
 }
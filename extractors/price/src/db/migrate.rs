@@ -0,0 +1,24 @@
+use clickhouse::Client;
+
+// Tables created by an earlier version keep their old columns, `CREATE TABLE IF NOT EXISTS`
+// leaves them be. Columns added since are added here, each is a no-op once it exists.
+pub async fn add_columns(
+    client: &Client,
+    table_name: &str,
+    columns: &[&str],
+) -> Result<(), clickhouse::error::Error> {
+    for column in columns {
+        client
+            .query(
+                format!(
+                    "ALTER TABLE {} ADD COLUMN IF NOT EXISTS {}",
+                    table_name, column
+                )
+                .as_str(),
+            )
+            .execute()
+            .await?;
+    }
+
+    Ok(())
+}
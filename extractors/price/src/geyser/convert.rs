@@ -0,0 +1,64 @@
+use super::errors::GeyserError;
+use crate::{
+    instructions::parse::InstructionWithLogs,
+    transactions::{
+        costs::{transaction_costs, TransactionCosts},
+        parse::{parse_transaction_with_logs, raw_message},
+        status::is_failed_transaction,
+    },
+};
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+use yellowstone_grpc_proto::{
+    convert_from::create_tx_with_meta, prelude::SubscribeUpdateTransaction,
+};
+
+#[derive(Debug)]
+pub struct GeyserTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub instructions_with_logs: Vec<InstructionWithLogs>,
+    pub costs: TransactionCosts,
+}
+
+// Geyser sends the node's own protobuf form. It goes through the same json encoding as
+// rpc responses so the instruction and log parsing is shared. Failed transactions give
+// `None`.
+pub fn geyser_transaction_from_update(
+    update: SubscribeUpdateTransaction,
+    pump_fun_program_address: &Pubkey,
+    raydium_amm_program_address: &Pubkey,
+) -> Result<Option<GeyserTransaction>, GeyserError> {
+    let transaction = update.transaction.ok_or(GeyserError::MissingTransaction)?;
+    let transaction = create_tx_with_meta(transaction)
+        .map_err(|err| GeyserError::Convert(err.to_string()))?
+        .encode(UiTransactionEncoding::Json, Some(0), false)
+        .map_err(GeyserError::Encode)?;
+
+    let tx = EncodedConfirmedTransactionWithStatusMeta {
+        slot: update.slot,
+        transaction,
+        block_time: None,
+    };
+
+    if is_failed_transaction(&tx) {
+        return Ok(None);
+    }
+
+    let (signature, raw_message) = raw_message(&tx.transaction.transaction)
+        .ok_or(GeyserError::TransactionMessageParseFailed)?;
+
+    let instructions_with_logs = parse_transaction_with_logs(
+        &tx.transaction.meta,
+        raw_message,
+        pump_fun_program_address,
+        raydium_amm_program_address,
+    );
+
+    Ok(Some(GeyserTransaction {
+        signature: signature.clone(),
+        slot: tx.slot,
+        instructions_with_logs,
+        costs: transaction_costs(raw_message, &tx.transaction.meta),
+    }))
+}
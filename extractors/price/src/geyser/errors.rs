@@ -0,0 +1,44 @@
+use solana_transaction_status::EncodeError;
+use tonic::Status;
+use yellowstone_grpc_client::{GeyserGrpcBuilderError, GeyserGrpcClientError};
+
+#[derive(Debug)]
+pub enum GeyserError {
+    Connect(GeyserGrpcBuilderError),
+    Subscribe(GeyserGrpcClientError),
+    Stream(Status),
+    Disconnected,
+    MissingTransaction,
+    Convert(String),
+    Encode(EncodeError),
+    TransactionMessageParseFailed,
+}
+
+impl std::fmt::Display for GeyserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Connect(err) => write!(f, "Failed to connect to geyser: {}", err),
+            Self::Subscribe(err) => write!(f, "Failed to subscribe to geyser: {}", err),
+            Self::Stream(err) => write!(f, "Geyser stream error: {}", err),
+            Self::Disconnected => write!(f, "Geyser stream closed"),
+            Self::MissingTransaction => write!(f, "Geyser update has no transaction"),
+            Self::Convert(err) => write!(f, "Failed to convert geyser transaction: {}", err),
+            Self::Encode(err) => write!(f, "Failed to encode geyser transaction: {}", err),
+            Self::TransactionMessageParseFailed => {
+                write!(f, "Failed to parse geyser transaction message")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GeyserError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Connect(err) => Some(err),
+            Self::Subscribe(err) => Some(err),
+            Self::Stream(err) => Some(err),
+            Self::Encode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod convert;
+pub mod errors;
+pub mod stream;
+pub mod threads;
@@ -0,0 +1,72 @@
+use super::errors::GeyserError;
+use futures_util::StreamExt;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use tonic::transport::ClientTlsConfig;
+use yellowstone_grpc_client::GeyserGrpcClient;
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest,
+    SubscribeRequestFilterTransactions, SubscribeUpdateTransaction,
+};
+
+const GEYSER_TRANSACTIONS_FILTER: &str = "programs";
+
+pub fn subscribe_request(program_addresses: &[Pubkey]) -> SubscribeRequest {
+    SubscribeRequest {
+        transactions: HashMap::from([(
+            GEYSER_TRANSACTIONS_FILTER.to_string(),
+            SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: Some(false),
+                account_include: program_addresses
+                    .iter()
+                    .map(|program_address| program_address.to_string())
+                    .collect(),
+                ..Default::default()
+            },
+        )]),
+        commitment: Some(CommitmentLevel::Confirmed as i32),
+        ..Default::default()
+    }
+}
+
+// Streams confirmed, successful transactions mentioning any of the programs until the
+// stream ends or errors.
+pub async fn stream_geyser_transactions<F>(
+    endpoint: &str,
+    x_token: Option<String>,
+    program_addresses: &[Pubkey],
+    mut on_transaction: F,
+) -> Result<(), GeyserError>
+where
+    F: FnMut(SubscribeUpdateTransaction),
+{
+    let mut builder = GeyserGrpcClient::build_from_shared(endpoint.to_string())
+        .map_err(GeyserError::Connect)?
+        .x_token(x_token)
+        .map_err(GeyserError::Connect)?;
+
+    if endpoint.starts_with("https") {
+        builder = builder
+            .tls_config(ClientTlsConfig::new().with_native_roots())
+            .map_err(GeyserError::Connect)?;
+    }
+
+    let mut client = builder.connect().await.map_err(GeyserError::Connect)?;
+
+    let mut updates = client
+        .subscribe_once(subscribe_request(program_addresses))
+        .await
+        .map_err(GeyserError::Subscribe)?;
+
+    while let Some(update) = updates.next().await {
+        let update = update.map_err(GeyserError::Stream)?;
+
+        // pings and anything else the filter lets through are not ours to handle
+        if let Some(UpdateOneof::Transaction(transaction)) = update.update_oneof {
+            on_transaction(transaction);
+        }
+    }
+
+    Err(GeyserError::Disconnected)
+}
@@ -0,0 +1,297 @@
+use super::{
+    convert::{geyser_transaction_from_update, GeyserTransaction},
+    stream::stream_geyser_transactions,
+};
+use crate::{
+    crawl_status::{channels::mark_as_succeeded, table::CrawlStatusOperation},
+    jupiter::{program::get_jupiter_program_address, table::JupiterRouteRow},
+    meteora_dlmm::program::get_meteora_dlmm_program_address,
+    orca_whirlpool::program::get_orca_whirlpool_program_address,
+    pools::table::PoolRow,
+    pump_fun::{
+        params::table::CurveParamsRow,
+        program::program::get_pump_fun_program_address,
+        tokens::{pump_fun_tokens_from_instructions, MintAddress, PumpFunToken},
+    },
+    pump_swap::program::get_pump_swap_program_address,
+    raydium::amm::get_raydium_amm_program_address,
+    raydium_clmm::program::get_raydium_clmm_program_address,
+    raydium_cpmm::program::get_raydium_cpmm_program_address,
+    rpc::pool::RpcPoolManager,
+    termination::{is_terminated, terminate_on_error, TerminationFlag},
+    token::events::table::TokenEventRow,
+    trades::{db::table::TradeRow, trades::rows_from_instructions},
+    utils::{blocking::blocking_call, log::log_time},
+};
+use crossbeam::channel::{Receiver, Sender};
+use solana_client::rpc_request::RpcRequest;
+use std::{
+    collections::{BTreeMap, HashSet},
+    thread,
+    time::Duration,
+};
+use yellowstone_grpc_proto::prelude::SubscribeUpdateTransaction;
+
+const GEYSER_RECONNECT_BASE_DELAY_MS: u64 = 500;
+const GEYSER_RECONNECT_MAX_DELAY_MS: u64 = 30_000;
+const GEYSER_BLOCK_TIME_CACHE_SIZE: usize = 1024;
+const GEYSER_UPDATES_BUFFER_SIZE: usize = 1024;
+
+pub type BlockTimeCache = BTreeMap<u64, u64>;
+
+// Transaction updates carry their slot but not its block time, it is looked up once per slot.
+// Only the newest slots are kept, updates arrive in roughly slot order.
+fn slot_block_time(
+    slot: u64,
+    block_time_cache: &mut BlockTimeCache,
+    rpc_pool_manager: &RpcPoolManager,
+) -> Result<u64, String> {
+    if let Some(block_time) = block_time_cache.get(&slot) {
+        return Ok(*block_time);
+    }
+
+    let block_time = blocking_call(rpc_pool_manager.execute(
+        RpcRequest::GetBlockTime,
+        |client| async move { client.get_block_time(slot).await },
+        None,
+    ))
+    .map_err(|err| err.to_string())? as u64;
+
+    block_time_cache.insert(slot, block_time);
+    while block_time_cache.len() > GEYSER_BLOCK_TIME_CACHE_SIZE {
+        block_time_cache.pop_first();
+    }
+
+    Ok(block_time)
+}
+
+pub fn forward_geyser_transaction(
+    geyser_transaction: GeyserTransaction,
+    pump_fun_tokens_tx: &Sender<PumpFunToken>,
+    trades_tx: &Sender<TradeRow>,
+    unresolved_trades_tx: &Sender<TradeRow>,
+    token_events_tx: &Sender<TokenEventRow>,
+    pools_tx: &Sender<PoolRow>,
+    jupiter_routes_tx: &Sender<JupiterRouteRow>,
+    curve_params_tx: &Sender<CurveParamsRow>,
+    crawl_status_tx: &Sender<CrawlStatusOperation>,
+    seen_tokens: &mut HashSet<MintAddress>,
+    block_time_cache: &mut BlockTimeCache,
+    rpc_pool_manager: &RpcPoolManager,
+    termination_flag: &TerminationFlag,
+) -> Result<(), String> {
+    for token in pump_fun_tokens_from_instructions(&geyser_transaction.instructions_with_logs) {
+        let (mint_address, _bonding_curve_address) = token;
+        if seen_tokens.insert(mint_address) {
+            terminate_on_error(termination_flag, pump_fun_tokens_tx.send(token));
+        }
+    }
+
+    // without a block time the transaction is left uncrawled, the signature crawlers pick it up
+    let block_time = slot_block_time(geyser_transaction.slot, block_time_cache, rpc_pool_manager)?;
+
+    let rows = rows_from_instructions(
+        &geyser_transaction.instructions_with_logs,
+        &geyser_transaction.costs,
+        geyser_transaction.slot,
+        block_time,
+        &geyser_transaction.signature,
+    )
+    .map_err(|err| err.to_string())?;
+
+    for trade in rows.trades {
+        terminate_on_error(termination_flag, trades_tx.send(trade));
+    }
+
+    for trade in rows.unresolved_trades {
+        terminate_on_error(termination_flag, unresolved_trades_tx.send(trade));
+    }
+
+    for token_event in rows.token_events {
+        terminate_on_error(termination_flag, token_events_tx.send(token_event));
+    }
+
+    for pool in rows.pools {
+        terminate_on_error(termination_flag, pools_tx.send(pool));
+    }
+
+    for jupiter_route in rows.jupiter_routes {
+        terminate_on_error(termination_flag, jupiter_routes_tx.send(jupiter_route));
+    }
+
+    for curve_params in rows.curve_params {
+        terminate_on_error(termination_flag, curve_params_tx.send(curve_params));
+    }
+
+    // recorded as crawled so the signature crawlers skip what was already streamed. It is
+    // kept out of the per account signatures, those are ordered by the crawlers' own indexes
+    terminate_on_error(
+        termination_flag,
+        mark_as_succeeded(crawl_status_tx, &geyser_transaction.signature),
+    );
+
+    Ok(())
+}
+
+pub fn geyser_transactions_threads(
+    endpoint: &str,
+    x_token: Option<String>,
+    pump_fun_tokens_tx: &Sender<PumpFunToken>,
+    trades_tx: &Sender<TradeRow>,
+    unresolved_trades_tx: &Sender<TradeRow>,
+    token_events_tx: &Sender<TokenEventRow>,
+    pools_tx: &Sender<PoolRow>,
+    jupiter_routes_tx: &Sender<JupiterRouteRow>,
+    curve_params_tx: &Sender<CurveParamsRow>,
+    crawl_status_tx: &Sender<CrawlStatusOperation>,
+    rpc_pool_manager: &RpcPoolManager,
+    termination_flag: &TerminationFlag,
+) -> Vec<thread::JoinHandle<()>> {
+    // The stream runs on the shared runtime, so updates are forwarded from a thread of their
+    // own where the block time lookups can block on rpc calls.
+    let (updates_tx, updates_rx) =
+        crossbeam::channel::bounded::<SubscribeUpdateTransaction>(GEYSER_UPDATES_BUFFER_SIZE);
+
+    vec![
+        stream_geyser_transactions_thread(endpoint, x_token, updates_tx, termination_flag),
+        forward_geyser_transactions_thread(
+            updates_rx,
+            pump_fun_tokens_tx,
+            trades_tx,
+            unresolved_trades_tx,
+            token_events_tx,
+            pools_tx,
+            jupiter_routes_tx,
+            curve_params_tx,
+            crawl_status_tx,
+            rpc_pool_manager,
+            termination_flag,
+        ),
+    ]
+}
+
+fn stream_geyser_transactions_thread(
+    endpoint: &str,
+    x_token: Option<String>,
+    updates_tx: Sender<SubscribeUpdateTransaction>,
+    termination_flag: &TerminationFlag,
+) -> thread::JoinHandle<()> {
+    let log_tag = format!("     {} geyser transactions | ", log_time());
+
+    let endpoint = endpoint.to_string();
+    let termination_flag = termination_flag.clone();
+
+    thread::spawn(move || {
+        let mut reconnects: u32 = 0;
+
+        loop {
+            if is_terminated(&termination_flag) {
+                println!("{} Termination flag set. Exiting", log_tag);
+                break;
+            }
+
+            println!("{} Subscribing to transactions at {}", log_tag, endpoint);
+            let result = blocking_call(stream_geyser_transactions(
+                &endpoint,
+                x_token.clone(),
+                &[
+                    get_pump_fun_program_address(),
+                    get_pump_swap_program_address(),
+                    get_raydium_amm_program_address(),
+                    get_raydium_cpmm_program_address(),
+                    get_raydium_clmm_program_address(),
+                    get_orca_whirlpool_program_address(),
+                    get_meteora_dlmm_program_address(),
+                    get_jupiter_program_address(),
+                ],
+                |update| {
+                    reconnects = 0;
+                    terminate_on_error(&termination_flag, updates_tx.send(update));
+                },
+            ));
+
+            if let Err(error) = result {
+                println!("{} Geyser stream stopped: {}", log_tag, error);
+            }
+
+            let delay = (GEYSER_RECONNECT_BASE_DELAY_MS << reconnects.min(16))
+                .min(GEYSER_RECONNECT_MAX_DELAY_MS);
+            reconnects += 1;
+
+            println!("{} Reconnecting in {}ms", log_tag, delay);
+            thread::sleep(Duration::from_millis(delay));
+        }
+    })
+}
+
+fn forward_geyser_transactions_thread(
+    updates_rx: Receiver<SubscribeUpdateTransaction>,
+    pump_fun_tokens_tx: &Sender<PumpFunToken>,
+    trades_tx: &Sender<TradeRow>,
+    unresolved_trades_tx: &Sender<TradeRow>,
+    token_events_tx: &Sender<TokenEventRow>,
+    pools_tx: &Sender<PoolRow>,
+    jupiter_routes_tx: &Sender<JupiterRouteRow>,
+    curve_params_tx: &Sender<CurveParamsRow>,
+    crawl_status_tx: &Sender<CrawlStatusOperation>,
+    rpc_pool_manager: &RpcPoolManager,
+    termination_flag: &TerminationFlag,
+) -> thread::JoinHandle<()> {
+    let log_tag = format!("     {} geyser trades | ", log_time());
+
+    let pump_fun_tokens_tx = pump_fun_tokens_tx.clone();
+    let trades_tx = trades_tx.clone();
+    let unresolved_trades_tx = unresolved_trades_tx.clone();
+    let token_events_tx = token_events_tx.clone();
+    let pools_tx = pools_tx.clone();
+    let jupiter_routes_tx = jupiter_routes_tx.clone();
+    let curve_params_tx = curve_params_tx.clone();
+    let crawl_status_tx = crawl_status_tx.clone();
+    let rpc_pool_manager = rpc_pool_manager.clone();
+    let termination_flag = termination_flag.clone();
+
+    let pump_fun_program_address = get_pump_fun_program_address();
+    let raydium_amm_program_address = get_raydium_amm_program_address();
+
+    thread::spawn(move || {
+        let mut seen_tokens = HashSet::new();
+        let mut block_time_cache = BlockTimeCache::new();
+
+        while let Ok(update) = updates_rx.recv() {
+            let geyser_transaction = match geyser_transaction_from_update(
+                update,
+                &pump_fun_program_address,
+                &raydium_amm_program_address,
+            ) {
+                Ok(Some(geyser_transaction)) => geyser_transaction,
+                Ok(None) => continue,
+                Err(error) => {
+                    println!("{} Skipping update: {}", log_tag, error);
+                    continue;
+                }
+            };
+
+            let signature = geyser_transaction.signature.clone();
+            if let Err(error) = forward_geyser_transaction(
+                geyser_transaction,
+                &pump_fun_tokens_tx,
+                &trades_tx,
+                &unresolved_trades_tx,
+                &token_events_tx,
+                &pools_tx,
+                &jupiter_routes_tx,
+                &curve_params_tx,
+                &crawl_status_tx,
+                &mut seen_tokens,
+                &mut block_time_cache,
+                &rpc_pool_manager,
+                &termination_flag,
+            ) {
+                println!(
+                    "{} Could not extract trades from {}: {}",
+                    log_tag, signature, error
+                );
+            }
+        }
+    })
+}
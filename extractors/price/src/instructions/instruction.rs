@@ -1,5 +1,9 @@
 use crate::{
-    pump_fun::instructions::PumpFunInstruction, raydium::instructions::RaydiumInstruction,
+    jupiter::instructions::JupiterInstruction, meteora_dlmm::instructions::MeteoraDlmmInstruction,
+    orca_whirlpool::instructions::OrcaWhirlpoolInstruction,
+    pump_fun::instructions::PumpFunInstruction, pump_swap::instructions::PumpSwapInstruction,
+    raydium::instructions::RaydiumInstruction, raydium_clmm::instructions::RaydiumClmmInstruction,
+    raydium_cpmm::instructions::RaydiumCpmmInstruction,
 };
 
 pub type InstructionIndex = u64;
@@ -7,5 +11,11 @@ pub type InstructionIndex = u64;
 #[derive(Debug)]
 pub enum Instruction {
     PumpFun(InstructionIndex, PumpFunInstruction),
+    PumpSwap(InstructionIndex, PumpSwapInstruction),
     Raydium(InstructionIndex, RaydiumInstruction),
+    RaydiumCpmm(InstructionIndex, RaydiumCpmmInstruction),
+    RaydiumClmm(InstructionIndex, RaydiumClmmInstruction),
+    OrcaWhirlpool(InstructionIndex, OrcaWhirlpoolInstruction),
+    MeteoraDlmm(InstructionIndex, MeteoraDlmmInstruction),
+    Jupiter(InstructionIndex, JupiterInstruction),
 }
@@ -1,7 +1,22 @@
 use crate::{
-    cpi::cpi::CpiLog, instructions::instruction::Instruction,
+    cpi::cpi::CpiLog,
+    instructions::instruction::Instruction,
+    jupiter::{instructions::parse_single_jupiter_instruction, program::JUPITER_ADDRESS},
+    meteora_dlmm::{
+        instructions::parse_single_meteora_dlmm_instruction, program::METEORA_DLMM_ADDRESS,
+    },
+    orca_whirlpool::{
+        instructions::parse_single_orca_whirlpool_instruction, program::ORCA_WHIRLPOOL_ADDRESS,
+    },
     pump_fun::instructions::parse_single_pump_fun_instruction,
+    pump_swap::{instructions::parse_single_pump_swap_instruction, program::PUMP_SWAP_ADDRESS},
     raydium::instructions::parse_single_raydium_instruction,
+    raydium_clmm::{
+        instructions::parse_single_raydium_clmm_instruction, program::RAYDIUM_CLMM_ADDRESS,
+    },
+    raydium_cpmm::{
+        instructions::parse_single_raydium_cpmm_instruction, program::RAYDIUM_CPMM_ADDRESS,
+    },
 };
 use solana_sdk::pubkey::Pubkey;
 
@@ -19,6 +34,7 @@ pub struct ParsableInstruction {
     pub inner_instructions: Option<Vec<ParsableInstruction>>,
     pub instruction_index: usize,
     pub inner_instruction_index: Option<usize>,
+    pub stack_height: Option<u32>,
 }
 
 pub fn parse_single_instruction(
@@ -43,7 +59,51 @@ pub fn parse_single_instruction(
             instruction_index,
             inner_instructions,
         )
+    } else if executing_program_id_str == PUMP_SWAP_ADDRESS {
+        parse_single_pump_swap_instruction(&parsable, account_keys, instruction_index)
+    } else if executing_program_id_str == RAYDIUM_CPMM_ADDRESS {
+        parse_single_raydium_cpmm_instruction(&parsable, account_keys, instruction_index)
+    } else if executing_program_id_str == RAYDIUM_CLMM_ADDRESS {
+        parse_single_raydium_clmm_instruction(&parsable, account_keys, instruction_index)
+    } else if executing_program_id_str == ORCA_WHIRLPOOL_ADDRESS {
+        parse_single_orca_whirlpool_instruction(
+            &parsable,
+            account_keys,
+            instruction_index,
+            inner_instructions,
+        )
+    } else if executing_program_id_str == METEORA_DLMM_ADDRESS {
+        parse_single_meteora_dlmm_instruction(&parsable, account_keys, instruction_index)
+    } else if executing_program_id_str == JUPITER_ADDRESS {
+        parse_single_jupiter_instruction(&parsable, account_keys, instruction_index)
     } else {
         None
     }
 }
+
+// Top level instructions carry their own inner instructions. Inner instructions are followed
+// by theirs in the parent's inner instructions, up to the next instruction at the same depth,
+// or without stack heights, up to the next call into the same program.
+pub fn invoked_instructions(
+    parsable: &ParsableInstruction,
+    inner_instructions: &Vec<ParsableInstruction>,
+) -> Vec<ParsableInstruction> {
+    if let Some(inner_instructions) = &parsable.inner_instructions {
+        return inner_instructions.clone();
+    }
+
+    let position = match parsable.inner_instruction_index {
+        Some(position) => position,
+        None => return vec![],
+    };
+
+    inner_instructions
+        .iter()
+        .skip(position + 1)
+        .take_while(|ix| match (parsable.stack_height, ix.stack_height) {
+            (Some(parent_height), Some(height)) => height > parent_height,
+            _ => ix.program_id_index != parsable.program_id_index,
+        })
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,39 @@
+use super::idl::jupiter_decoder;
+use crate::{anchor::value::IdlValue, cpi::cpi::CpiLog, utils::log::log_time};
+use solana_sdk::pubkey::Pubkey;
+
+// One per hop of a route, the amounts the user's side of the hop moved
+#[derive(Debug, Clone)]
+pub struct JupiterSwapEvent {
+    pub input_mint: Pubkey,
+    pub input_amount: u64,
+    pub output_mint: Pubkey,
+    pub output_amount: u64,
+}
+
+impl JupiterSwapEvent {
+    fn from_fields(fields: &IdlValue) -> Option<Self> {
+        Some(Self {
+            input_mint: fields.get("input_mint")?.as_pubkey()?,
+            input_amount: fields.get("input_amount")?.as_u64()?,
+            output_mint: fields.get("output_mint")?.as_pubkey()?,
+            output_amount: fields.get("output_amount")?.as_u64()?,
+        })
+    }
+}
+
+// `data` is the instruction data of a jupiter emit_cpi! self invocation
+pub fn jupiter_cpi_log_from_event_instruction(data: &[u8]) -> Option<CpiLog> {
+    let event = match jupiter_decoder().decode_cpi_event(data) {
+        Ok(event) => event?,
+        Err(error) => {
+            println!("{} Failed to decode jupiter event: {}", log_time(), error);
+            return None;
+        }
+    };
+
+    match event.name.as_str() {
+        "SwapEvent" => JupiterSwapEvent::from_fields(&event.fields).map(CpiLog::JupiterSwap),
+        _ => None,
+    }
+}
@@ -0,0 +1,12 @@
+use crate::anchor::decoder::AnchorDecoder;
+use std::sync::OnceLock;
+
+const JUPITER_IDL: &str = include_str!("idl.json");
+
+static JUPITER_DECODER: OnceLock<AnchorDecoder> = OnceLock::new();
+
+pub fn jupiter_decoder() -> &'static AnchorDecoder {
+    JUPITER_DECODER.get_or_init(|| {
+        AnchorDecoder::from_json(JUPITER_IDL).expect("Bundled jupiter IDL is invalid")
+    })
+}
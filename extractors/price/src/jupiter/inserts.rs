@@ -0,0 +1,43 @@
+use super::table::{JupiterRouteRow, CLICKHOUSE_JUPITER_ROUTES_TABLE_NAME};
+use clickhouse::Client;
+
+pub async fn insert_jupiter_route(
+    client: &Client,
+    jupiter_route: &JupiterRouteRow,
+) -> Result<(), clickhouse::error::Error> {
+    client
+        .query(
+            format!(
+                "
+            INSERT INTO {} (
+                transaction_signature,
+                route_id,
+                slot,
+                block_time,
+                user_address,
+                input_mint_address,
+                input_amount,
+                output_mint_address,
+                output_amount,
+                hop_count
+            ) VALUES ('{}', {}, {}, {}, '{}', '{}', {}, '{}', {}, {})
+            ",
+                CLICKHOUSE_JUPITER_ROUTES_TABLE_NAME,
+                jupiter_route.transaction_signature,
+                jupiter_route.route_id,
+                jupiter_route.slot,
+                jupiter_route.block_time.unix_timestamp(),
+                jupiter_route.user_address,
+                jupiter_route.input_mint_address,
+                jupiter_route.input_amount,
+                jupiter_route.output_mint_address,
+                jupiter_route.output_amount,
+                jupiter_route.hop_count,
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use super::idl::jupiter_decoder;
+use crate::{
+    anchor::decoder::DecodedInstruction,
+    instructions::{instruction::Instruction, parse::ParsableInstruction},
+    utils::log::log_time,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub type UserAddress = Pubkey;
+
+// Route plans are left undecoded, the hops are read from the swap events each route emits
+#[derive(Debug)]
+pub enum JupiterInstruction {
+    Route((DecodedInstruction, UserAddress)),
+    SharedAccountsRoute((DecodedInstruction, UserAddress)),
+    ExactOutRoute((DecodedInstruction, UserAddress)),
+    SharedAccountsExactOutRoute((DecodedInstruction, UserAddress)),
+}
+
+impl JupiterInstruction {
+    pub fn user(&self) -> &UserAddress {
+        match self {
+            Self::Route((_, user))
+            | Self::SharedAccountsRoute((_, user))
+            | Self::ExactOutRoute((_, user))
+            | Self::SharedAccountsExactOutRoute((_, user)) => user,
+        }
+    }
+}
+
+pub fn parse_single_jupiter_instruction(
+    parsable: &ParsableInstruction,
+    account_keys: &[String],
+    instruction_index: u64,
+) -> Option<Instruction> {
+    let data = bs58::decode(&parsable.data).into_vec().ok()?;
+    let accounts = parsable
+        .accounts
+        .iter()
+        .map(|account| account_keys.get(*account as usize).cloned())
+        .collect::<Option<Vec<String>>>()?;
+
+    let decoded = match jupiter_decoder().decode_instruction(&data, &accounts) {
+        Ok(decoded) => decoded?,
+        Err(error) => {
+            println!(
+                "{} Failed to decode jupiter instruction: {}",
+                log_time(),
+                error
+            );
+            return None;
+        }
+    };
+
+    let user = Pubkey::from_str(decoded.account("user_transfer_authority")?).ok()?;
+
+    let instruction = match decoded.name.as_str() {
+        "route" => JupiterInstruction::Route((decoded, user)),
+        "shared_accounts_route" => JupiterInstruction::SharedAccountsRoute((decoded, user)),
+        "exact_out_route" => JupiterInstruction::ExactOutRoute((decoded, user)),
+        "shared_accounts_exact_out_route" => {
+            JupiterInstruction::SharedAccountsExactOutRoute((decoded, user))
+        }
+        _ => return None,
+    };
+
+    Some(Instruction::Jupiter(instruction_index, instruction))
+}
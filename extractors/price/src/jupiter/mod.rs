@@ -0,0 +1,8 @@
+pub mod cpi;
+pub mod idl;
+pub mod inserts;
+pub mod instructions;
+pub mod program;
+pub mod routes;
+pub mod store;
+pub mod table;
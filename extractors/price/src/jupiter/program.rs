@@ -0,0 +1,8 @@
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub const JUPITER_ADDRESS: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+pub fn get_jupiter_program_address() -> Pubkey {
+    Pubkey::from_str(JUPITER_ADDRESS).unwrap()
+}
@@ -0,0 +1,143 @@
+use super::{cpi::JupiterSwapEvent, instructions::UserAddress, table::JupiterRouteRow};
+use crate::{
+    cpi::cpi::CpiLog,
+    instructions::{
+        instruction::{Instruction, InstructionIndex},
+        parse::InstructionWithLogs,
+    },
+    trades::db::table::TradeRow,
+};
+use solana_sdk::pubkey::Pubkey;
+use time::OffsetDateTime;
+
+// A route is identified by its instruction index, the index its legs are recorded under too
+#[derive(Debug, Clone)]
+pub struct JupiterRoute {
+    pub route_id: InstructionIndex,
+    pub user: UserAddress,
+    pub hops: Vec<JupiterSwapEvent>,
+}
+
+impl JupiterRoute {
+    // The user pays the first hop's input and is paid the last hop's output. Split routes pay
+    // into several hops from the same mint, so every hop in or out of those mints is summed.
+    pub fn net_input(&self) -> Option<(Pubkey, u64)> {
+        let input_mint = self.hops.first()?.input_mint;
+        let input_amount = self
+            .hops
+            .iter()
+            .filter(|hop| hop.input_mint == input_mint)
+            .map(|hop| hop.input_amount)
+            .sum();
+
+        Some((input_mint, input_amount))
+    }
+
+    pub fn net_output(&self) -> Option<(Pubkey, u64)> {
+        let output_mint = self.hops.last()?.output_mint;
+        let output_amount = self
+            .hops
+            .iter()
+            .filter(|hop| hop.output_mint == output_mint)
+            .map(|hop| hop.output_amount)
+            .sum();
+
+        Some((output_mint, output_amount))
+    }
+}
+
+impl JupiterRouteRow {
+    pub fn from_route(
+        jupiter_route: &JupiterRoute,
+        slot: u64,
+        block_time: u64,
+        token_tx_signature: &str,
+    ) -> Option<Self> {
+        let (input_mint, input_amount) = jupiter_route.net_input()?;
+        let (output_mint, output_amount) = jupiter_route.net_output()?;
+
+        Some(Self {
+            transaction_signature: token_tx_signature.to_string(),
+            route_id: jupiter_route.route_id,
+            slot,
+            block_time: OffsetDateTime::from_unix_timestamp(block_time as i64).unwrap(),
+            user_address: jupiter_route.user.to_string(),
+            input_mint_address: input_mint.to_string(),
+            input_amount,
+            output_mint_address: output_mint.to_string(),
+            output_amount,
+            hop_count: jupiter_route.hops.len() as u64,
+        })
+    }
+}
+
+pub fn jupiter_routes_from_instructions(
+    instructions_with_logs: &[InstructionWithLogs],
+) -> Vec<JupiterRoute> {
+    instructions_with_logs
+        .iter()
+        .filter_map(
+            |instruction_with_logs| match &instruction_with_logs.instruction {
+                Some(Instruction::Jupiter(instruction_index, instruction)) => Some(JupiterRoute {
+                    route_id: *instruction_index,
+                    user: *instruction.user(),
+                    hops: instruction_with_logs
+                        .cpi_logs
+                        .iter()
+                        .filter_map(|cpi_log| match cpi_log {
+                            CpiLog::JupiterSwap(swap_event) => Some(swap_event.clone()),
+                            _ => None,
+                        })
+                        .collect(),
+                }),
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+// Legs still waiting for their pool have no mints yet, but moved what the hop reports
+fn is_leg_of_hop(trade: &TradeRow, hop: &JupiterSwapEvent) -> bool {
+    let mints = [hop.input_mint.to_string(), hop.output_mint.to_string()];
+    let amounts = [hop.input_amount, hop.output_amount];
+
+    (mints.contains(&trade.coin_token_address) && mints.contains(&trade.price_coin_token_address))
+        || (amounts.contains(&trade.coin_token_amount)
+            && amounts.contains(&trade.price_coin_token_amount))
+}
+
+// Trades made inside a route are its legs. Each hop is linked to at most one of them, legs of
+// hops we don't decode keep the route but no hop index. Legs are traded by the route's user,
+// whichever authority the dex saw.
+pub fn link_route_legs<'a>(
+    jupiter_routes: &[JupiterRoute],
+    trades: impl Iterator<Item = &'a mut TradeRow>,
+) {
+    let mut linked_hops = jupiter_routes
+        .iter()
+        .map(|jupiter_route| vec![false; jupiter_route.hops.len()])
+        .collect::<Vec<_>>();
+
+    for trade in trades {
+        for (jupiter_route, linked) in jupiter_routes.iter().zip(linked_hops.iter_mut()) {
+            if jupiter_route.route_id != trade.instruction_index {
+                continue;
+            }
+
+            trade.route_id = Some(jupiter_route.route_id);
+            trade.trader_address = jupiter_route.user.to_string();
+
+            let hop_index = jupiter_route
+                .hops
+                .iter()
+                .enumerate()
+                .position(|(hop_index, hop)| !linked[hop_index] && is_leg_of_hop(trade, hop));
+
+            if let Some(hop_index) = hop_index {
+                linked[hop_index] = true;
+                trade.route_hop_index = Some(hop_index as u64);
+                break;
+            }
+        }
+    }
+}
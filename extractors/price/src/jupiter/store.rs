@@ -0,0 +1,46 @@
+use super::{inserts::insert_jupiter_route, table::JupiterRouteRow};
+use crate::{
+    constants::STORE_CONCURRENCY,
+    db::client::db_client,
+    utils::{blocking::blocking_call, log::log_time},
+};
+use crossbeam::channel::Receiver;
+use std::thread;
+
+const CONCURRENCY: usize = STORE_CONCURRENCY;
+
+pub fn store_jupiter_routes(
+    jupiter_routes_rx: &Receiver<JupiterRouteRow>,
+) -> Vec<thread::JoinHandle<()>> {
+    let mut handles = Vec::with_capacity(CONCURRENCY);
+
+    for thread_index in 0..CONCURRENCY {
+        let log_tag = format!(
+            "                 {} store jupiter routes #{} | ",
+            log_time(),
+            thread_index
+        );
+
+        let jupiter_routes_rx = jupiter_routes_rx.clone();
+
+        let handle = thread::spawn(move || {
+            let db_client = db_client();
+
+            while let Ok(jupiter_route) = jupiter_routes_rx.recv() {
+                println!(
+                    "{} Storing jupiter route {} of {}",
+                    log_tag, jupiter_route.route_id, jupiter_route.transaction_signature
+                );
+
+                let client = db_client.clone();
+                blocking_call(async move {
+                    insert_jupiter_route(&client, &jupiter_route).await.unwrap()
+                });
+            }
+        });
+
+        handles.push(handle);
+    }
+
+    handles
+}
@@ -0,0 +1,50 @@
+use clickhouse::Client;
+use time::OffsetDateTime;
+
+pub const CLICKHOUSE_JUPITER_ROUTES_TABLE_NAME: &str = "jupiter_routes";
+
+// What the user paid and got for a whole route. Its legs are the trades sharing the
+// transaction signature and `route_id`.
+#[derive(Debug, Clone)]
+pub struct JupiterRouteRow {
+    pub transaction_signature: String,
+    pub route_id: u64,
+    pub slot: u64,
+    pub block_time: OffsetDateTime,
+    pub user_address: String,
+    pub input_mint_address: String,
+    pub input_amount: u64,
+    pub output_mint_address: String,
+    pub output_amount: u64,
+    pub hop_count: u64,
+}
+
+pub async fn create_jupiter_routes_table(client: &Client) -> Result<(), clickhouse::error::Error> {
+    client
+        .query(
+            format!(
+                "
+        CREATE TABLE IF NOT EXISTS {} (
+            transaction_signature String,
+            route_id UInt64,
+            slot UInt64,
+            block_time DateTime,
+            user_address String,
+            input_mint_address String,
+            input_amount UInt64,
+            output_mint_address String,
+            output_amount UInt64,
+            hop_count UInt64,
+        )
+        ENGINE = ReplacingMergeTree()
+        ORDER BY (slot, transaction_signature, route_id)
+    ",
+                CLICKHOUSE_JUPITER_ROUTES_TABLE_NAME,
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+
+    Ok(())
+}
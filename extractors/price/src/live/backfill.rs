@@ -0,0 +1,58 @@
+use super::logs::LiveSignature;
+use crate::{
+    rpc::{errors::RpcError, pool::RpcPoolManager},
+    signatures::config::{build_signatures_config, DEFAULT_SIGNATURES_LIMIT},
+};
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::pubkey::Pubkey;
+
+// Pages backwards from the head until `until`, returning the missed signatures oldest first.
+pub async fn backfill_program_signatures(
+    rpc_pool_manager: &RpcPoolManager,
+    program_address: &Pubkey,
+    until: &str,
+    pin: Option<u64>,
+) -> Result<Vec<LiveSignature>, RpcError> {
+    let mut before: Option<String> = None;
+    let mut missed = Vec::new();
+
+    loop {
+        let page = rpc_pool_manager
+            .execute(
+                RpcRequest::GetSignaturesForAddress,
+                |client| {
+                    let config = build_signatures_config(
+                        before.clone(),
+                        Some(until.to_string()),
+                        Some(DEFAULT_SIGNATURES_LIMIT),
+                    );
+                    async move {
+                        client
+                            .get_signatures_for_address_with_config(program_address, config)
+                            .await
+                    }
+                },
+                pin,
+            )
+            .await?;
+
+        let page_count = page.len();
+        before = page.last().map(|signature| signature.signature.clone());
+
+        missed.extend(
+            page.into_iter()
+                .filter(|signature| signature.err.is_none())
+                .map(|signature| LiveSignature {
+                    signature: signature.signature,
+                    slot: signature.slot,
+                }),
+        );
+
+        if page_count < DEFAULT_SIGNATURES_LIMIT {
+            break;
+        }
+    }
+
+    missed.reverse();
+    Ok(missed)
+}
@@ -0,0 +1,28 @@
+use solana_client::nonblocking::pubsub_client::PubsubClientError;
+
+#[derive(Debug)]
+pub enum LiveLogsError {
+    Connect(PubsubClientError),
+    Subscribe(PubsubClientError),
+    Disconnected,
+}
+
+impl std::fmt::Display for LiveLogsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Connect(err) => write!(f, "Failed to connect to websocket: {}", err),
+            Self::Subscribe(err) => write!(f, "Failed to subscribe to logs: {}", err),
+            Self::Disconnected => write!(f, "Websocket disconnected"),
+        }
+    }
+}
+
+impl std::error::Error for LiveLogsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Connect(err) => Some(err),
+            Self::Subscribe(err) => Some(err),
+            Self::Disconnected => None,
+        }
+    }
+}
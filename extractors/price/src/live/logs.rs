@@ -0,0 +1,122 @@
+use super::{backfill::backfill_program_signatures, errors::LiveLogsError};
+use crate::{rpc::pool::RpcPoolManager, utils::log::log_time};
+use futures_util::StreamExt;
+use solana_client::{
+    nonblocking::pubsub_client::PubsubClient,
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::collections::{HashSet, VecDeque};
+
+const RECENT_SIGNATURES_CAPACITY: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveSignature {
+    pub signature: String,
+    pub slot: u64,
+}
+
+// Live notifications and gap back-fills overlap after a reconnect, so remember what was
+// already forwarded.
+pub struct RecentSignatures {
+    order: VecDeque<String>,
+    seen: HashSet<String>,
+}
+
+impl RecentSignatures {
+    pub fn new() -> Self {
+        Self {
+            order: VecDeque::with_capacity(RECENT_SIGNATURES_CAPACITY),
+            seen: HashSet::with_capacity(RECENT_SIGNATURES_CAPACITY),
+        }
+    }
+
+    // returns false if the signature was already seen
+    pub fn insert(&mut self, signature: &str) -> bool {
+        if self.seen.contains(signature) {
+            return false;
+        }
+
+        if self.order.len() == RECENT_SIGNATURES_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+
+        self.order.push_back(signature.to_string());
+        self.seen.insert(signature.to_string());
+        true
+    }
+}
+
+// Signatures missed between the last one seen and a new subscription.
+pub struct GapBackfill<'a> {
+    pub rpc_pool_manager: &'a RpcPoolManager,
+    pub until: String,
+    pub pin: Option<u64>,
+}
+
+// Streams signatures of successful transactions mentioning the program until the
+// websocket drops. The gap is back-filled only once the subscription is live, so nothing
+// landing in between is lost; notifications queue up meanwhile.
+pub async fn stream_program_logs<F>(
+    ws_url: &str,
+    program_address: &Pubkey,
+    gap: Option<GapBackfill<'_>>,
+    mut on_signature: F,
+) -> Result<(), LiveLogsError>
+where
+    F: FnMut(LiveSignature),
+{
+    let client = PubsubClient::new(ws_url)
+        .await
+        .map_err(LiveLogsError::Connect)?;
+
+    let (mut notifications, _unsubscribe) = client
+        .logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![program_address.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )
+        .await
+        .map_err(LiveLogsError::Subscribe)?;
+
+    if let Some(gap) = gap {
+        match backfill_program_signatures(
+            gap.rpc_pool_manager,
+            program_address,
+            &gap.until,
+            gap.pin,
+        )
+        .await
+        {
+            Ok(missed) => {
+                println!(
+                    "{} Back-filling {} signatures missed while disconnected",
+                    log_time(),
+                    missed.len()
+                );
+                for live_signature in missed {
+                    on_signature(live_signature);
+                }
+            }
+            Err(error) => {
+                println!("{} Gap back-fill failed: {}", log_time(), error);
+            }
+        }
+    }
+
+    while let Some(notification) = notifications.next().await {
+        if notification.value.err.is_some() {
+            continue;
+        }
+
+        on_signature(LiveSignature {
+            signature: notification.value.signature,
+            slot: notification.context.slot,
+        });
+    }
+
+    Err(LiveLogsError::Disconnected)
+}
@@ -0,0 +1,4 @@
+pub mod backfill;
+pub mod errors;
+pub mod logs;
+pub mod threads;
@@ -0,0 +1,157 @@
+use super::logs::{stream_program_logs, GapBackfill, LiveSignature, RecentSignatures};
+use crate::{
+    crawl_status::{
+        channels::create_crawl_status,
+        table::{CrawlStatus, CrawlStatusOperation, CrawlStatusRow},
+    },
+    pump_fun::program::signatures::TransactionSignature,
+    rpc::pool::RpcPoolManager,
+    termination::{is_terminated, terminate_on_error, TerminationFlag},
+    trades::signatures::TradeSignature,
+    utils::{blocking::blocking_call, log::log_time},
+};
+use crossbeam::channel::Sender;
+use solana_sdk::pubkey::Pubkey;
+use std::{thread, time::Duration};
+
+const LIVE_RECONNECT_BASE_DELAY_MS: u64 = 500;
+const LIVE_RECONNECT_MAX_DELAY_MS: u64 = 30_000;
+
+// Where signatures seen live for a program are sent. pump.fun signatures go through token
+// discovery, other programs go straight to trade extraction listed for the program.
+#[derive(Clone)]
+pub enum LiveSink {
+    PumpFunProgram(Sender<TransactionSignature>),
+    Trades(Sender<TradeSignature>),
+}
+
+impl LiveSink {
+    fn send(&self, program_address: &Pubkey, signature: String) -> Result<(), String> {
+        match self {
+            Self::PumpFunProgram(tx) => tx.send(signature).map_err(|err| err.to_string()),
+            Self::Trades(tx) => tx
+                .send(TradeSignature::Program(*program_address, signature))
+                .map_err(|err| err.to_string()),
+        }
+    }
+}
+
+fn forward_live_signature(
+    live_signature: LiveSignature,
+    program_address: &Pubkey,
+    sink: &LiveSink,
+    crawl_status_tx: &Sender<CrawlStatusOperation>,
+    recent_signatures: &mut RecentSignatures,
+    termination_flag: &TerminationFlag,
+) {
+    if !recent_signatures.insert(&live_signature.signature) {
+        return;
+    }
+
+    terminate_on_error(
+        termination_flag,
+        create_crawl_status(
+            crawl_status_tx,
+            CrawlStatusRow {
+                account_address: program_address.to_string(),
+                transaction_signature: live_signature.signature.clone(),
+                slot: live_signature.slot,
+                relative_transaction_index: 0,
+                is_first_account_signature: false,
+                status: CrawlStatus::Pending,
+                error: None,
+            },
+        ),
+    );
+    terminate_on_error(
+        termination_flag,
+        sink.send(program_address, live_signature.signature),
+    );
+}
+
+pub fn live_program_logs_threads(
+    programs: Vec<(Pubkey, LiveSink)>,
+    crawl_status_tx: &Sender<CrawlStatusOperation>,
+    rpc_pool_manager: &RpcPoolManager,
+    termination_flag: &TerminationFlag,
+) -> Vec<thread::JoinHandle<()>> {
+    let mut handles = Vec::with_capacity(programs.len());
+
+    let ws_url = match rpc_pool_manager.get_ws_url() {
+        Some(ws_url) => ws_url,
+        None => {
+            println!(
+                "{} No websocket url configured. Live mode disabled",
+                log_time()
+            );
+            return handles;
+        }
+    };
+
+    for (thread_index, (program_address, sink)) in programs.into_iter().enumerate() {
+        let log_tag = format!(
+            "     {} live logs {} #{} | ",
+            log_time(),
+            program_address,
+            thread_index
+        );
+
+        let ws_url = ws_url.clone();
+        let crawl_status_tx = crawl_status_tx.clone();
+        let rpc_pool_manager = rpc_pool_manager.clone();
+        let termination_flag = termination_flag.clone();
+
+        let handle = thread::spawn(move || {
+            let mut recent_signatures = RecentSignatures::new();
+            let mut last_signature: Option<String> = None;
+            let mut reconnects: u32 = 0;
+
+            loop {
+                if is_terminated(&termination_flag) {
+                    println!("{} Termination flag set. Exiting", log_tag);
+                    break;
+                }
+
+                println!("{} Subscribing to program logs at {}", log_tag, ws_url);
+                let gap = last_signature.clone().map(|until| GapBackfill {
+                    rpc_pool_manager: &rpc_pool_manager,
+                    until,
+                    pin: Some(thread_index as u64),
+                });
+
+                let result = blocking_call(stream_program_logs(
+                    &ws_url,
+                    &program_address,
+                    gap,
+                    |live_signature| {
+                        reconnects = 0;
+                        last_signature = Some(live_signature.signature.clone());
+                        forward_live_signature(
+                            live_signature,
+                            &program_address,
+                            &sink,
+                            &crawl_status_tx,
+                            &mut recent_signatures,
+                            &termination_flag,
+                        );
+                    },
+                ));
+
+                if let Err(error) = result {
+                    println!("{} Live logs stopped: {}", log_tag, error);
+                }
+
+                let delay = (LIVE_RECONNECT_BASE_DELAY_MS << reconnects.min(16))
+                    .min(LIVE_RECONNECT_MAX_DELAY_MS);
+                reconnects += 1;
+
+                println!("{} Reconnecting in {}ms", log_tag, delay);
+                thread::sleep(Duration::from_millis(delay));
+            }
+        });
+
+        handles.push(handle);
+    }
+
+    handles
+}
@@ -1,15 +1,37 @@
+use blocks::threads::block_range_threads;
 use clap::Parser;
-use crawl_status::store::store_crawl_statuses;
+use crawl_status::queries::get_account_signatures;
+use crawl_status::store::{discard_crawl_statuses, store_crawl_statuses};
 use crawl_status::table::{CrawlStatus, CrawlStatusOperation, CrawlStatusRow};
 use db::client::db_health_check;
 use db::init::init_db;
 use dotenvy::dotenv;
 use dragonfly::client::dragonfly_client;
 use dragonfly::health::dragonfly_health_check;
+use geyser::threads::geyser_transactions_threads;
+use jupiter::program::get_jupiter_program_address;
+use jupiter::store::store_jupiter_routes;
+use jupiter::table::JupiterRouteRow;
+use live::threads::{live_program_logs_threads, LiveSink};
+use meteora_dlmm::program::get_meteora_dlmm_program_address;
+use orca_whirlpool::program::get_orca_whirlpool_program_address;
+use pools::resolve::resolve_pool_trades_threads;
+use pools::store::store_pools;
+use pools::table::PoolRow;
+use pump_fun::idl::load_pump_fun_idl;
+use pump_fun::params::store::store_curve_params;
+use pump_fun::params::table::CurveParamsRow;
+use pump_fun::program::program::get_pump_fun_program_address;
 use pump_fun::program::signatures::TransactionSignature;
 use pump_fun::program::signatures_threads::pump_fun_program_signatures_threads;
 use pump_fun::tokens::PumpFunToken;
 use pump_fun::tokens_threads::pump_fun_tokens_threads;
+use pump_swap::program::get_pump_swap_program_address;
+use raydium::amm::get_raydium_amm_program_address;
+use raydium_clmm::program::get_raydium_clmm_program_address;
+use raydium_cpmm::program::get_raydium_cpmm_program_address;
+use rpc::cache::{TransactionCache, DEFAULT_TX_CACHE_DIR};
+use rpc::config::{RpcConfig, DEFAULT_RPC_CONFIG_PATH};
 use rpc::pool::{RpcPoolManager, DEFAULT_RATE_LIMIT_COOLOFF_MS};
 use solana_sdk::pubkey::Pubkey;
 use std::error::Error;
@@ -18,23 +40,41 @@ use std::thread;
 use std::time::Duration;
 use termination::init as termination_init;
 use token::accounts::get_token_accounts_meta;
-use token::mint::signatures::{get_token_mint_signatures, TokenMintSignatures};
+use token::events::store::store_token_events;
+use token::events::table::TokenEventRow;
+use token::mint::forward::{
+    token_mint_forward_threads, ForwardSchedule, ScheduledToken, DEFAULT_FORWARD_POLL_INTERVAL_MS,
+};
+use token::mint::signatures::get_token_mint_signatures;
 use token::store::store_tokens;
 use token::table::TokenRow;
 use trades::db::store::store_trades;
 use trades::db::table::TradeRow;
+use trades::signatures::TradeSignature;
 use trades::trades_threads::token_trades_threads;
 use utils::blocking::blocking_call;
+use utils::log::log_time;
 
 mod anchor;
+mod blocks;
+mod compute_budget;
 mod constants;
 mod cpi;
 mod crawl_status;
 mod db;
 mod dragonfly;
+mod geyser;
 mod instructions;
+mod jupiter;
+mod live;
+mod meteora_dlmm;
+mod orca_whirlpool;
+mod pools;
 mod pump_fun;
+mod pump_swap;
 mod raydium;
+mod raydium_clmm;
+mod raydium_cpmm;
 mod rpc;
 mod signatures;
 mod system;
@@ -52,6 +92,45 @@ struct Args {
 
     #[arg(long)]
     tx: Option<String>,
+
+    #[arg(long, env = "RPC_CONFIG_PATH", default_value = DEFAULT_RPC_CONFIG_PATH)]
+    rpc_config: String,
+
+    #[arg(long, env = "TX_CACHE_DIR", default_value = DEFAULT_TX_CACHE_DIR)]
+    tx_cache_dir: String,
+
+    // reparse cached transactions without sending any rpc requests
+    #[arg(long)]
+    offline: bool,
+
+    // also follow pump.fun and raydium amm at the head through websocket log subscriptions
+    #[arg(long)]
+    live: bool,
+
+    // keep polling tokens at the head once their history is complete
+    #[arg(long)]
+    follow: bool,
+
+    #[arg(long, default_value_t = DEFAULT_FORWARD_POLL_INTERVAL_MS)]
+    follow_interval_ms: u64,
+
+    // crawl whole blocks in this inclusive slot range instead of address signatures
+    #[arg(long)]
+    from_slot: Option<u64>,
+
+    #[arg(long)]
+    to_slot: Option<u64>,
+
+    // also stream pump.fun and raydium amm transactions from a yellowstone grpc endpoint
+    #[arg(long, env = "GEYSER_ENDPOINT")]
+    geyser_endpoint: Option<String>,
+
+    #[arg(long, env = "GEYSER_X_TOKEN")]
+    geyser_x_token: Option<String>,
+
+    // decode pump.fun with this anchor IDL instead of the bundled one
+    #[arg(long, env = "PUMP_FUN_IDL_PATH")]
+    pump_fun_idl: Option<String>,
 }
 
 #[cfg(test)]
@@ -63,19 +142,77 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let targetted_mint_address = args.token;
     let target_transaction_signature = args.tx;
+    let offline = args.offline;
 
     if target_transaction_signature.is_some() && targetted_mint_address.is_none() {
         panic!("Cannot set target transaction signature without setting target token mint address");
     }
 
+    if offline && targetted_mint_address.is_none() {
+        panic!("Cannot run offline without setting target token mint address");
+    }
+
+    if args.live && (offline || targetted_mint_address.is_some()) {
+        panic!("Cannot run live mode offline or with a target token mint address");
+    }
+
+    if args.follow && offline {
+        panic!("Cannot follow tokens at the head offline");
+    }
+
+    let slot_range = match (args.from_slot, args.to_slot) {
+        (None, None) => None,
+        (Some(from_slot), Some(to_slot)) if from_slot <= to_slot => Some((from_slot, to_slot)),
+        (Some(_), Some(_)) => panic!("Cannot crawl a slot range that ends before it starts"),
+        _ => panic!("Cannot crawl a slot range without setting both --from-slot and --to-slot"),
+    };
+
+    if slot_range.is_some()
+        && (targetted_mint_address.is_some() || offline || args.live || args.follow)
+    {
+        panic!("Cannot crawl a slot range with a target token, offline, live or follow mode");
+    }
+
+    if args.geyser_endpoint.is_some()
+        && (offline || targetted_mint_address.is_some() || slot_range.is_some())
+    {
+        panic!(
+            "Cannot stream from geyser offline, with a target token mint address or a slot range"
+        );
+    }
+
+    if let Some(pump_fun_idl) = &args.pump_fun_idl {
+        load_pump_fun_idl(pump_fun_idl)?;
+        println!("{} Loaded pump.fun IDL from {}", log_time(), pump_fun_idl);
+    }
+
     let termination_flag = termination_init();
 
-    let rpc_pool_manager =
-        RpcPoolManager::new(Duration::from_millis(DEFAULT_RATE_LIMIT_COOLOFF_MS));
+    let rpc_config = RpcConfig::load(&args.rpc_config)?;
+    println!(
+        "Loaded {} rpc endpoints for {:?} from {}",
+        rpc_config.active_endpoints().count(),
+        rpc_config.cluster,
+        args.rpc_config
+    );
 
     let db = blocking_call(async { init_db().await });
     let dragonfly = dragonfly_client();
 
+    let tx_cache = TransactionCache::new(&args.tx_cache_dir)?;
+    println!(
+        "Using transaction cache at {}{}",
+        args.tx_cache_dir,
+        if offline { " (offline)" } else { "" }
+    );
+
+    let rpc_pool_manager = RpcPoolManager::new(
+        &rpc_config,
+        Duration::from_millis(DEFAULT_RATE_LIMIT_COOLOFF_MS),
+        &dragonfly,
+    )
+    .with_transaction_cache(tx_cache, offline);
+
     println!("DB health check: {:?}", db_health_check(&db));
     println!(
         "Dragonfly health check: {:?}",
@@ -83,14 +220,20 @@ fn main() -> Result<(), Box<dyn Error>> {
     );
 
     let (pump_fun_program_signatures_tx, pump_fun_program_signatures_rx) =
-        crossbeam::channel::bounded::<TransactionSignature>(1);
+        crossbeam::channel::bounded::<TransactionSignature>(rpc_config.batch_size.max(1));
     let (pump_fun_tokens_tx, pump_fun_tokens_rx) = crossbeam::channel::bounded::<PumpFunToken>(1);
     let (token_accounts_tx, token_accounts_rx) = crossbeam::channel::unbounded::<TokenRow>();
     let (token_pump_fun_signatures_tx, token_pump_fun_signatures_rx) =
-        crossbeam::channel::unbounded::<TokenMintSignatures>();
+        crossbeam::channel::unbounded::<TradeSignature>();
     let (pump_fun_trades_tx, pump_fun_trades_rx) = crossbeam::channel::unbounded::<TradeRow>();
+    let (unresolved_trades_tx, unresolved_trades_rx) = crossbeam::channel::unbounded::<TradeRow>();
+    let (token_events_tx, token_events_rx) = crossbeam::channel::unbounded::<TokenEventRow>();
+    let (pools_tx, pools_rx) = crossbeam::channel::unbounded::<PoolRow>();
+    let (jupiter_routes_tx, jupiter_routes_rx) = crossbeam::channel::unbounded::<JupiterRouteRow>();
+    let (curve_params_tx, curve_params_rx) = crossbeam::channel::unbounded::<CurveParamsRow>();
     let (crawl_status_tx, crawl_status_rx) =
         crossbeam::channel::unbounded::<CrawlStatusOperation>();
+    let (forward_tokens_tx, forward_tokens_rx) = crossbeam::channel::unbounded::<ScheduledToken>();
 
     //
     //                                      ┌────────────────────────────────────────┐
@@ -129,9 +272,29 @@ fn main() -> Result<(), Box<dyn Error>> {
     //
     //
 
-    let mut handles = Vec::new();
+    let mut handles = vec![rpc_pool_manager.flush_credit_usage_thread()];
 
-    if targetted_mint_address.is_none() && target_transaction_signature.is_none() {
+    if let Some((from_slot, to_slot)) = slot_range {
+        println!(
+            "Crawling blocks for slots {} to {}. Skipping address signature crawls",
+            from_slot, to_slot
+        );
+        let handle = block_range_threads(
+            from_slot,
+            to_slot,
+            &pump_fun_tokens_tx,
+            &pump_fun_trades_tx,
+            &unresolved_trades_tx,
+            &token_events_tx,
+            &pools_tx,
+            &jupiter_routes_tx,
+            &curve_params_tx,
+            &crawl_status_tx,
+            &rpc_pool_manager,
+            &termination_flag,
+        );
+        handles.extend(handle);
+    } else if targetted_mint_address.is_none() && target_transaction_signature.is_none() {
         let handle = pump_fun_program_signatures_threads(
             &pump_fun_program_signatures_tx,
             &crawl_status_tx,
@@ -145,7 +308,79 @@ fn main() -> Result<(), Box<dyn Error>> {
         );
     };
 
-    if target_transaction_signature.is_some() {
+    if args.live {
+        println!(
+            "{} Live mode enabled. Subscribing to pump fun, pump swap and dex program logs",
+            log_time()
+        );
+        let handle = live_program_logs_threads(
+            vec![
+                (
+                    get_pump_fun_program_address(),
+                    LiveSink::PumpFunProgram(pump_fun_program_signatures_tx.clone()),
+                ),
+                (
+                    get_pump_swap_program_address(),
+                    LiveSink::Trades(token_pump_fun_signatures_tx.clone()),
+                ),
+                (
+                    get_raydium_amm_program_address(),
+                    LiveSink::Trades(token_pump_fun_signatures_tx.clone()),
+                ),
+                (
+                    get_raydium_cpmm_program_address(),
+                    LiveSink::Trades(token_pump_fun_signatures_tx.clone()),
+                ),
+                (
+                    get_raydium_clmm_program_address(),
+                    LiveSink::Trades(token_pump_fun_signatures_tx.clone()),
+                ),
+                (
+                    get_orca_whirlpool_program_address(),
+                    LiveSink::Trades(token_pump_fun_signatures_tx.clone()),
+                ),
+                (
+                    get_meteora_dlmm_program_address(),
+                    LiveSink::Trades(token_pump_fun_signatures_tx.clone()),
+                ),
+                (
+                    get_jupiter_program_address(),
+                    LiveSink::Trades(token_pump_fun_signatures_tx.clone()),
+                ),
+            ],
+            &crawl_status_tx,
+            &rpc_pool_manager,
+            &termination_flag,
+        );
+        handles.extend(handle);
+    }
+
+    if let Some(geyser_endpoint) = &args.geyser_endpoint {
+        println!(
+            "Geyser streaming enabled. Subscribing to pump fun, pump swap and dex transactions"
+        );
+        let handle = geyser_transactions_threads(
+            geyser_endpoint,
+            args.geyser_x_token.clone(),
+            &pump_fun_tokens_tx,
+            &pump_fun_trades_tx,
+            &unresolved_trades_tx,
+            &token_events_tx,
+            &pools_tx,
+            &jupiter_routes_tx,
+            &curve_params_tx,
+            &crawl_status_tx,
+            &rpc_pool_manager,
+            &termination_flag,
+        );
+        handles.extend(handle);
+    }
+
+    if slot_range.is_some() {
+        println!(
+            "Crawling blocks. Skipping pump fun program transactions and mint address crawls."
+        );
+    } else if target_transaction_signature.is_some() {
         println!("User has set a target transaction signature. Skipping mint address crawls.");
     } else if let Some(targetted_mint_address) = targetted_mint_address.clone() {
         println!("User has set a target mint address. Sending single mint address for crawl.");
@@ -164,6 +399,7 @@ fn main() -> Result<(), Box<dyn Error>> {
             &pump_fun_program_signatures_rx,
             &crawl_status_tx,
             &rpc_pool_manager,
+            &termination_flag,
         );
 
         handles.extend(handle);
@@ -175,12 +411,16 @@ fn main() -> Result<(), Box<dyn Error>> {
         crossbeam::channel::unbounded();
     let pump_fun_tokens_tx_mint_signatures_tee_inner =
         pump_fun_tokens_tx_mint_signatures_tee.clone();
+    // block crawls see every trade already, so their tokens skip the signature crawl
+    let crawl_token_signatures = slot_range.is_none();
     let tee_handles = thread::spawn(move || {
         for token in pump_fun_tokens_rx {
             pump_fun_tokens_tx_token_account_tee.send(token).unwrap();
-            pump_fun_tokens_tx_mint_signatures_tee_inner
-                .send(token)
-                .unwrap();
+            if crawl_token_signatures {
+                pump_fun_tokens_tx_mint_signatures_tee_inner
+                    .send(token)
+                    .unwrap();
+            }
         }
     });
     handles.push(tee_handles);
@@ -192,21 +432,42 @@ fn main() -> Result<(), Box<dyn Error>> {
     );
     handles.extend(token_accounts_handles);
 
+    let forward_schedule = args
+        .follow
+        .then(|| ForwardSchedule::new(forward_tokens_tx.clone()));
+
     let token_mint_signatures_handles = get_token_mint_signatures(
         &token_pump_fun_signatures_tx,
         &crawl_status_tx,
         &pump_fun_tokens_rx_mint_signatures_tee,
         &pump_fun_tokens_tx_mint_signatures_tee,
         &rpc_pool_manager,
+        forward_schedule.as_ref(),
     );
     handles.extend(token_mint_signatures_handles);
 
+    if forward_schedule.is_some() {
+        println!(
+            "Follow mode enabled. Polling completed tokens every {}ms",
+            args.follow_interval_ms
+        );
+        let token_mint_forward_handles = token_mint_forward_threads(
+            &token_pump_fun_signatures_tx,
+            &crawl_status_tx,
+            &forward_tokens_rx,
+            &forward_tokens_tx,
+            &rpc_pool_manager,
+            Duration::from_millis(args.follow_interval_ms),
+        );
+        handles.extend(token_mint_forward_handles);
+    }
+
     if let Some(target_transaction_signature) = target_transaction_signature {
         println!("User has set a target transaction signature. Sending single transaction signature for crawl.");
         let mint_address = targetted_mint_address.unwrap();
 
         token_pump_fun_signatures_tx
-            .send((
+            .send(TradeSignature::TokenMint(
                 Pubkey::from_str(&mint_address.clone()).unwrap(),
                 target_transaction_signature.clone(),
             ))
@@ -223,10 +484,32 @@ fn main() -> Result<(), Box<dyn Error>> {
                 error: None,
             }))
             .unwrap();
+    } else if offline {
+        let mint_address = targetted_mint_address.clone().unwrap();
+        let signatures = get_account_signatures(&dragonfly, &mint_address)?;
+        println!(
+            "Running offline. Replaying {} known signatures for {} from the transaction cache.",
+            signatures.len(),
+            mint_address
+        );
+
+        for signature in signatures {
+            token_pump_fun_signatures_tx
+                .send(TradeSignature::TokenMint(
+                    Pubkey::from_str(&mint_address).unwrap(),
+                    signature,
+                ))
+                .unwrap();
+        }
     }
 
     let token_trades_handles = token_trades_threads(
         &pump_fun_trades_tx,
+        &unresolved_trades_tx,
+        &token_events_tx,
+        &pools_tx,
+        &jupiter_routes_tx,
+        &curve_params_tx,
         &token_pump_fun_signatures_rx,
         &rpc_pool_manager,
         &crawl_status_tx,
@@ -234,13 +517,39 @@ fn main() -> Result<(), Box<dyn Error>> {
     );
     handles.extend(token_trades_handles);
 
+    let resolve_pool_trades_handles = resolve_pool_trades_threads(
+        &unresolved_trades_rx,
+        &pump_fun_trades_tx,
+        &pools_tx,
+        &rpc_pool_manager,
+        &crawl_status_tx,
+        &termination_flag,
+    );
+    handles.extend(resolve_pool_trades_handles);
+
     let token_prices_handles = store_trades(&pump_fun_trades_rx);
     handles.extend(token_prices_handles);
 
     let store_tokens_handles = store_tokens(&token_accounts_rx);
     handles.extend(store_tokens_handles);
 
-    let store_crawl_statuses_handles = store_crawl_statuses(&crawl_status_rx);
+    let store_token_events_handles = store_token_events(&token_events_rx);
+    handles.extend(store_token_events_handles);
+
+    let store_pools_handles = store_pools(&pools_rx);
+    handles.extend(store_pools_handles);
+
+    let store_jupiter_routes_handles = store_jupiter_routes(&jupiter_routes_rx);
+    handles.extend(store_jupiter_routes_handles);
+
+    let store_curve_params_handles = store_curve_params(&curve_params_rx);
+    handles.extend(store_curve_params_handles);
+
+    let store_crawl_statuses_handles = if offline {
+        discard_crawl_statuses(&crawl_status_rx)
+    } else {
+        store_crawl_statuses(&crawl_status_rx)
+    };
     handles.extend(store_crawl_statuses_handles);
 
     for handle in handles {
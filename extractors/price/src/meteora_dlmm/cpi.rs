@@ -0,0 +1,42 @@
+use super::idl::meteora_dlmm_decoder;
+use crate::{anchor::value::IdlValue, cpi::cpi::CpiLog, utils::log::log_time};
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Debug, Clone)]
+pub struct MeteoraDlmmSwapEvent {
+    pub lb_pair: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub swap_for_y: bool,
+}
+
+impl MeteoraDlmmSwapEvent {
+    fn from_fields(fields: &IdlValue) -> Option<Self> {
+        Some(Self {
+            lb_pair: fields.get("lb_pair")?.as_pubkey()?,
+            amount_in: fields.get("amount_in")?.as_u64()?,
+            amount_out: fields.get("amount_out")?.as_u64()?,
+            swap_for_y: fields.get("swap_for_y")?.as_bool()?,
+        })
+    }
+}
+
+// `data` is the instruction data of a meteora dlmm emit_cpi! self invocation
+pub fn meteora_dlmm_cpi_log_from_event_instruction(data: &[u8]) -> Option<CpiLog> {
+    let event = match meteora_dlmm_decoder().decode_cpi_event(data) {
+        Ok(event) => event?,
+        Err(error) => {
+            println!(
+                "{} Failed to decode meteora dlmm event: {}",
+                log_time(),
+                error
+            );
+            return None;
+        }
+    };
+
+    match event.name.as_str() {
+        "Swap" => MeteoraDlmmSwapEvent::from_fields(&event.fields).map(CpiLog::MeteoraDlmmSwap),
+        _ => None,
+    }
+}
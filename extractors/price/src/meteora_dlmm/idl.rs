@@ -0,0 +1,12 @@
+use crate::anchor::decoder::AnchorDecoder;
+use std::sync::OnceLock;
+
+const METEORA_DLMM_IDL: &str = include_str!("idl.json");
+
+static METEORA_DLMM_DECODER: OnceLock<AnchorDecoder> = OnceLock::new();
+
+pub fn meteora_dlmm_decoder() -> &'static AnchorDecoder {
+    METEORA_DLMM_DECODER.get_or_init(|| {
+        AnchorDecoder::from_json(METEORA_DLMM_IDL).expect("Bundled meteora dlmm IDL is invalid")
+    })
+}
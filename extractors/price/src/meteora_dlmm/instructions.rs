@@ -0,0 +1,60 @@
+use super::idl::meteora_dlmm_decoder;
+use crate::{
+    anchor::decoder::DecodedInstruction,
+    instructions::{instruction::Instruction, parse::ParsableInstruction},
+    utils::log::log_time,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub type MeteoraDlmmPoolAddress = Pubkey;
+pub type TokenXMint = Pubkey;
+pub type TokenYMint = Pubkey;
+
+#[derive(Debug)]
+pub enum MeteoraDlmmInstruction {
+    Swap(
+        (
+            DecodedInstruction,
+            MeteoraDlmmPoolAddress,
+            (TokenXMint, TokenYMint),
+        ),
+    ),
+}
+
+pub fn parse_single_meteora_dlmm_instruction(
+    parsable: &ParsableInstruction,
+    account_keys: &[String],
+    instruction_index: u64,
+) -> Option<Instruction> {
+    let data = bs58::decode(&parsable.data).into_vec().ok()?;
+    let accounts = parsable
+        .accounts
+        .iter()
+        .map(|account| account_keys.get(*account as usize).cloned())
+        .collect::<Option<Vec<String>>>()?;
+
+    let decoded = match meteora_dlmm_decoder().decode_instruction(&data, &accounts) {
+        Ok(decoded) => decoded?,
+        Err(error) => {
+            println!(
+                "{} Failed to decode meteora dlmm instruction: {}",
+                log_time(),
+                error
+            );
+            return None;
+        }
+    };
+
+    // the pair checks the mints passed against its own, they are the pool's
+    let pool = Pubkey::from_str(decoded.account("lb_pair")?).ok()?;
+    let mint_x = Pubkey::from_str(decoded.account("token_x_mint")?).ok()?;
+    let mint_y = Pubkey::from_str(decoded.account("token_y_mint")?).ok()?;
+
+    let instruction = match decoded.name.as_str() {
+        "swap" => MeteoraDlmmInstruction::Swap((decoded, pool, (mint_x, mint_y))),
+        _ => return None,
+    };
+
+    Some(Instruction::MeteoraDlmm(instruction_index, instruction))
+}
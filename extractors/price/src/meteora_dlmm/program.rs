@@ -0,0 +1,8 @@
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub const METEORA_DLMM_ADDRESS: &str = "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo";
+
+pub fn get_meteora_dlmm_program_address() -> Pubkey {
+    Pubkey::from_str(METEORA_DLMM_ADDRESS).unwrap()
+}
@@ -0,0 +1,47 @@
+use super::instructions::MeteoraDlmmInstruction;
+use crate::{
+    cpi::cpi::CpiLog,
+    instructions::instruction::InstructionIndex,
+    pools::trades::trade_from_pool_swap,
+    trades::db::table::{TradeRow, TradeVenue},
+};
+
+pub fn trade_from_meteora_dlmm_instruction(
+    instruction: &MeteoraDlmmInstruction,
+    instruction_index: &InstructionIndex,
+    slot: u64,
+    block_time: u64,
+    cpi_logs: &Vec<CpiLog>,
+    token_tx_signature: &str,
+) -> Option<TradeRow> {
+    let (decoded, pool_address, (mint_x, mint_y)) = match instruction {
+        MeteoraDlmmInstruction::Swap((decoded, pool_address, mints)) => {
+            (decoded, pool_address, mints)
+        }
+    };
+
+    let swap_event = cpi_logs.iter().find_map(|cpi_log| match cpi_log {
+        CpiLog::MeteoraDlmmSwap(swap_event) if swap_event.lb_pair == *pool_address => {
+            Some(swap_event)
+        }
+        _ => None,
+    })?;
+
+    let (input_mint, output_mint) = if swap_event.swap_for_y {
+        (mint_x, mint_y)
+    } else {
+        (mint_y, mint_x)
+    };
+
+    Some(trade_from_pool_swap(
+        pool_address,
+        TradeVenue::MeteoraDlmm,
+        decoded.account("user")?,
+        (input_mint, swap_event.amount_in),
+        (output_mint, swap_event.amount_out),
+        instruction_index,
+        slot,
+        block_time,
+        token_tx_signature,
+    ))
+}
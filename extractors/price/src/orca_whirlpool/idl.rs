@@ -0,0 +1,12 @@
+use crate::anchor::decoder::AnchorDecoder;
+use std::sync::OnceLock;
+
+const ORCA_WHIRLPOOL_IDL: &str = include_str!("idl.json");
+
+static ORCA_WHIRLPOOL_DECODER: OnceLock<AnchorDecoder> = OnceLock::new();
+
+pub fn orca_whirlpool_decoder() -> &'static AnchorDecoder {
+    ORCA_WHIRLPOOL_DECODER.get_or_init(|| {
+        AnchorDecoder::from_json(ORCA_WHIRLPOOL_IDL).expect("Bundled orca whirlpool IDL is invalid")
+    })
+}
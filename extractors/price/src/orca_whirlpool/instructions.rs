@@ -0,0 +1,97 @@
+use super::idl::orca_whirlpool_decoder;
+use crate::{
+    anchor::decoder::DecodedInstruction,
+    instructions::{
+        instruction::Instruction,
+        parse::{invoked_instructions, ParsableInstruction},
+    },
+    pools::{
+        pool::PoolSwap,
+        transfers::{parse_inner_transfer_instruction, swap_flow_from_transfers},
+    },
+    utils::log::log_time,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub type TokenMintA = Pubkey;
+pub type TokenMintB = Pubkey;
+
+// Whirlpools log nothing about swaps, amounts are read from the vault transfers with token a
+// and b in place of the amm's coin and pc
+#[derive(Debug)]
+pub enum OrcaWhirlpoolInstruction {
+    // the first swap only passes the vaults, its mints come from the pool state
+    Swap((DecodedInstruction, PoolSwap)),
+    SwapV2((DecodedInstruction, PoolSwap, (TokenMintA, TokenMintB))),
+}
+
+fn whirlpool_swap_from_instruction(
+    decoded: &DecodedInstruction,
+    parsable: &ParsableInstruction,
+    account_keys: &[String],
+    inner_instructions: &Vec<ParsableInstruction>,
+) -> Option<PoolSwap> {
+    let vault_a_str = decoded.account("token_vault_a")?;
+    let vault_b_str = decoded.account("token_vault_b")?;
+
+    let transfers = invoked_instructions(parsable, inner_instructions)
+        .iter()
+        .filter_map(|ix| parse_inner_transfer_instruction(ix, account_keys))
+        .collect::<Vec<_>>();
+
+    let (amount_a, amount_b, direction) =
+        swap_flow_from_transfers(&transfers, vault_a_str, vault_b_str)?;
+
+    Some(PoolSwap {
+        pool: Pubkey::from_str(decoded.account("whirlpool")?).ok()?,
+        user: Pubkey::from_str(decoded.account("token_authority")?).ok()?,
+        coin_vault: Pubkey::from_str(vault_a_str).ok()?,
+        pc_vault: Pubkey::from_str(vault_b_str).ok()?,
+        coin_amount: amount_a,
+        pc_amount: amount_b,
+        direction,
+    })
+}
+
+pub fn parse_single_orca_whirlpool_instruction(
+    parsable: &ParsableInstruction,
+    account_keys: &[String],
+    instruction_index: u64,
+    inner_instructions: &Vec<ParsableInstruction>,
+) -> Option<Instruction> {
+    let data = bs58::decode(&parsable.data).into_vec().ok()?;
+    let accounts = parsable
+        .accounts
+        .iter()
+        .map(|account| account_keys.get(*account as usize).cloned())
+        .collect::<Option<Vec<String>>>()?;
+
+    // swap v2's trailing remaining accounts info is not read
+    let decoded = match orca_whirlpool_decoder().decode_instruction(&data, &accounts) {
+        Ok(decoded) => decoded?,
+        Err(error) => {
+            println!(
+                "{} Failed to decode orca whirlpool instruction: {}",
+                log_time(),
+                error
+            );
+            return None;
+        }
+    };
+
+    let swap =
+        whirlpool_swap_from_instruction(&decoded, parsable, account_keys, inner_instructions)?;
+
+    let instruction = match decoded.name.as_str() {
+        "swap" => OrcaWhirlpoolInstruction::Swap((decoded, swap)),
+        "swap_v2" => {
+            let mint_a = Pubkey::from_str(decoded.account("token_mint_a")?).ok()?;
+            let mint_b = Pubkey::from_str(decoded.account("token_mint_b")?).ok()?;
+            OrcaWhirlpoolInstruction::SwapV2((decoded, swap, (mint_a, mint_b)))
+        }
+        _ => return None,
+    };
+
+    Some(Instruction::OrcaWhirlpool(instruction_index, instruction))
+}
@@ -0,0 +1,5 @@
+pub mod idl;
+pub mod instructions;
+pub mod program;
+pub mod state;
+pub mod trades;
@@ -0,0 +1,8 @@
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub const ORCA_WHIRLPOOL_ADDRESS: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+
+pub fn get_orca_whirlpool_program_address() -> Pubkey {
+    Pubkey::from_str(ORCA_WHIRLPOOL_ADDRESS).unwrap()
+}
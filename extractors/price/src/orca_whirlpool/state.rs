@@ -0,0 +1,34 @@
+use crate::{
+    anchor::hash::hash_anchor_account_discriminator,
+    pools::pool::{Pool, PoolAddress},
+};
+use solana_sdk::pubkey::Pubkey;
+
+// `Whirlpool`: the anchor discriminator, config, tick spacing, fee rates, liquidity, price
+// and owed protocol fees come before token a. Token a's fee growth sits between its vault
+// and token b.
+pub const WHIRLPOOL_SIZE: usize = 653;
+const TOKEN_MINT_A_OFFSET: usize = 101;
+const TOKEN_VAULT_A_OFFSET: usize = 133;
+const TOKEN_MINT_B_OFFSET: usize = 181;
+const TOKEN_VAULT_B_OFFSET: usize = 213;
+
+fn pubkey_at(data: &[u8], offset: usize) -> Option<Pubkey> {
+    Pubkey::try_from(data.get(offset..offset + 32)?).ok()
+}
+
+// Token a and b take the place of the amm's coin and pc. The program owns other accounts,
+// configs and tick arrays, that only a whirlpool's size and discriminator tell apart.
+pub fn orca_whirlpool_pool_from_state(pool: &PoolAddress, data: &[u8]) -> Option<Pool> {
+    if data.len() != WHIRLPOOL_SIZE || data[..8] != hash_anchor_account_discriminator("Whirlpool") {
+        return None;
+    }
+
+    Some(Pool {
+        pool: *pool,
+        coin_mint: pubkey_at(data, TOKEN_MINT_A_OFFSET)?,
+        pc_mint: pubkey_at(data, TOKEN_MINT_B_OFFSET)?,
+        coin_vault: pubkey_at(data, TOKEN_VAULT_A_OFFSET)?,
+        pc_vault: pubkey_at(data, TOKEN_VAULT_B_OFFSET)?,
+    })
+}
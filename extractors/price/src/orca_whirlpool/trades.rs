@@ -0,0 +1,52 @@
+use super::instructions::OrcaWhirlpoolInstruction;
+use crate::{
+    instructions::instruction::InstructionIndex,
+    pools::{
+        pool::Pool,
+        table::PoolRow,
+        trades::{resolve_pool_trade, trade_from_vault_swap},
+    },
+    trades::db::table::{TradeRow, TradeVenue},
+};
+
+// Swaps without mints are left with empty coin and price coin addresses, the same as amm
+// swaps, and are priced once the pool state is resolved
+pub fn trade_from_orca_whirlpool_instruction(
+    instruction: &OrcaWhirlpoolInstruction,
+    instruction_index: &InstructionIndex,
+    slot: u64,
+    block_time: u64,
+    token_tx_signature: &str,
+) -> TradeRow {
+    let swap = match instruction {
+        OrcaWhirlpoolInstruction::Swap((_, swap)) => swap,
+        OrcaWhirlpoolInstruction::SwapV2((_, swap, _)) => swap,
+    };
+
+    let trade = trade_from_vault_swap(
+        swap,
+        TradeVenue::OrcaWhirlpool,
+        instruction_index,
+        slot,
+        block_time,
+        token_tx_signature,
+    );
+
+    match instruction {
+        OrcaWhirlpoolInstruction::Swap(_) => trade,
+        OrcaWhirlpoolInstruction::SwapV2((_, swap, (mint_a, mint_b))) => {
+            let whirlpool = Pool {
+                pool: swap.pool,
+                coin_mint: *mint_a,
+                pc_mint: *mint_b,
+                coin_vault: swap.coin_vault,
+                pc_vault: swap.pc_vault,
+            };
+
+            resolve_pool_trade(
+                trade,
+                &PoolRow::from_pool(&whirlpool, TradeVenue::OrcaWhirlpool),
+            )
+        }
+    }
+}
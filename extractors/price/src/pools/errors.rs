@@ -0,0 +1,36 @@
+use crate::rpc::errors::RpcError;
+
+#[derive(Debug)]
+pub enum PoolError {
+    Query(clickhouse::error::Error),
+    Fetch(RpcError),
+    InvalidAddress(String),
+    InvalidOwner(String),
+    InvalidState(usize),
+}
+
+impl std::fmt::Display for PoolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Query(err) => write!(f, "Failed to query pool: {}", err),
+            Self::Fetch(err) => write!(f, "Failed to fetch pool state: {}", err),
+            Self::InvalidAddress(address) => write!(f, "Invalid pool address {}", address),
+            Self::InvalidOwner(owner) => {
+                write!(f, "Pool account is owned by {}, not a pool program", owner)
+            }
+            Self::InvalidState(length) => {
+                write!(f, "Pool state of {} bytes could not be read", length)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PoolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Query(err) => Some(err),
+            Self::Fetch(err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use super::table::{PoolRow, CLICKHOUSE_POOLS_TABLE_NAME};
+use clickhouse::Client;
+
+pub async fn insert_pool(client: &Client, pool: &PoolRow) -> Result<(), clickhouse::error::Error> {
+    client
+        .query(
+            format!(
+                "
+            INSERT INTO {} (
+                pool_address,
+                mint_address,
+                coin_mint_address,
+                pc_mint_address,
+                coin_vault_address,
+                pc_vault_address,
+                venue
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            ",
+                CLICKHOUSE_POOLS_TABLE_NAME,
+            )
+            .as_str(),
+        )
+        .bind(&pool.pool_address)
+        .bind(&pool.mint_address)
+        .bind(&pool.coin_mint_address)
+        .bind(&pool.pc_mint_address)
+        .bind(&pool.coin_vault_address)
+        .bind(&pool.pc_vault_address)
+        .bind(pool.venue.as_str())
+        .execute()
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+pub mod errors;
+pub mod inserts;
+pub mod pool;
+pub mod queries;
+pub mod resolve;
+pub mod state;
+pub mod store;
+pub mod table;
+pub mod trades;
+pub mod transfers;
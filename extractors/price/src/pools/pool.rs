@@ -0,0 +1,31 @@
+use crate::trades::db::table::TradeDirection;
+use solana_sdk::pubkey::Pubkey;
+
+pub type PoolAddress = Pubkey;
+pub type PoolCoinTokenAddress = Pubkey;
+pub type PoolPcTokenAddress = Pubkey;
+
+// Two sided pools of any venue. Venues naming their sides differently, token 0 and 1 or a
+// and b, take the place of the coin and pc.
+#[derive(Debug, Clone, Copy)]
+pub struct Pool {
+    pub pool: PoolAddress,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub coin_vault: PoolCoinTokenAddress,
+    pub pc_vault: PoolPcTokenAddress,
+}
+
+// Amounts and direction are read from the vaults' side of the swap. A buy takes coin out of
+// the pool, whichever instruction was used and whichever amount was fixed. The user is the
+// wallet that signed for the tokens paid in.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolSwap {
+    pub pool: PoolAddress,
+    pub user: Pubkey,
+    pub coin_vault: PoolCoinTokenAddress,
+    pub pc_vault: PoolPcTokenAddress,
+    pub coin_amount: u64,
+    pub pc_amount: u64,
+    pub direction: TradeDirection,
+}
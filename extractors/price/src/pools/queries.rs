@@ -0,0 +1,30 @@
+use super::table::{PoolRow, CLICKHOUSE_POOLS_TABLE_NAME};
+use clickhouse::Client;
+
+pub async fn get_pool(
+    client: &Client,
+    pool_address: &str,
+) -> Result<Option<PoolRow>, clickhouse::error::Error> {
+    client
+        .query(
+            format!(
+                "
+            SELECT
+                pool_address,
+                mint_address,
+                coin_mint_address,
+                pc_mint_address,
+                coin_vault_address,
+                pc_vault_address,
+                venue
+            FROM {} FINAL
+            WHERE pool_address = '{}'
+            LIMIT 1
+            ",
+                CLICKHOUSE_POOLS_TABLE_NAME, pool_address,
+            )
+            .as_str(),
+        )
+        .fetch_optional::<PoolRow>()
+        .await
+}
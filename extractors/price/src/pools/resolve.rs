@@ -0,0 +1,147 @@
+use super::{
+    errors::PoolError, queries::get_pool, state::pool_from_account, table::PoolRow,
+    trades::resolve_pool_trade,
+};
+use crate::{
+    crawl_status::{channels::mark_as_failed, table::CrawlStatusOperation},
+    db::client::db_client,
+    orca_whirlpool::program::get_orca_whirlpool_program_address,
+    raydium::amm::get_raydium_amm_program_address,
+    raydium_clmm::program::get_raydium_clmm_program_address,
+    rpc::pool::RpcPoolManager,
+    termination::{is_terminated, terminate_on_error, TerminationFlag},
+    trades::db::table::TradeRow,
+    utils::{blocking::blocking_call, log::log_time},
+};
+use clickhouse::Client;
+use crossbeam::channel::{Receiver, Sender};
+use dashmap::DashMap;
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::pubkey::Pubkey;
+use std::{str::FromStr, sync::Arc, thread};
+
+pub type PoolCache = Arc<DashMap<String, PoolRow>>;
+
+// Pools come from memory first, then from the stored pools, and only then from the pool
+// state on chain. Pools read from chain are stored so the next run finds them.
+fn resolve_pool(
+    pool_address: &str,
+    pool_cache: &PoolCache,
+    clickhouse_client: &Client,
+    rpc_pool_manager: &RpcPoolManager,
+    pools_tx: &Sender<PoolRow>,
+    termination_flag: &TerminationFlag,
+    pin: Option<u64>,
+) -> Result<PoolRow, PoolError> {
+    if let Some(pool) = pool_cache.get(pool_address) {
+        return Ok(pool.clone());
+    }
+
+    let stored_pool =
+        blocking_call(get_pool(clickhouse_client, pool_address)).map_err(PoolError::Query)?;
+    if let Some(pool) = stored_pool {
+        pool_cache.insert(pool_address.to_string(), pool.clone());
+        return Ok(pool);
+    }
+
+    let pool = Pubkey::from_str(pool_address)
+        .map_err(|_| PoolError::InvalidAddress(pool_address.to_string()))?;
+    let account = blocking_call(rpc_pool_manager.execute(
+        RpcRequest::GetAccountInfo,
+        |client| async move { client.get_account(&pool).await },
+        pin,
+    ))
+    .map_err(PoolError::Fetch)?;
+
+    if account.owner != get_raydium_amm_program_address()
+        && account.owner != get_raydium_clmm_program_address()
+        && account.owner != get_orca_whirlpool_program_address()
+    {
+        return Err(PoolError::InvalidOwner(account.owner.to_string()));
+    }
+
+    let pool = pool_from_account(&pool, &account.owner, &account.data)
+        .ok_or(PoolError::InvalidState(account.data.len()))?;
+
+    pool_cache.insert(pool_address.to_string(), pool.clone());
+    terminate_on_error(termination_flag, pools_tx.send(pool.clone()));
+
+    Ok(pool)
+}
+
+pub fn resolve_pool_trades_threads(
+    unresolved_trades_rx: &Receiver<TradeRow>,
+    trades_tx: &Sender<TradeRow>,
+    pools_tx: &Sender<PoolRow>,
+    rpc_pool_manager: &RpcPoolManager,
+    crawl_status_tx: &Sender<CrawlStatusOperation>,
+    termination_flag: &TerminationFlag,
+) -> Vec<thread::JoinHandle<()>> {
+    let concurrency = rpc_pool_manager.get_rpc_nodes_count();
+    let mut handles = Vec::with_capacity(concurrency);
+
+    let pool_cache: PoolCache = Arc::new(DashMap::new());
+
+    for thread_index in 0..concurrency {
+        let log_tag = format!(
+            "             {} resolve pool trades #{} | ",
+            log_time(),
+            thread_index
+        );
+
+        let unresolved_trades_rx = unresolved_trades_rx.clone();
+        let trades_tx = trades_tx.clone();
+        let pools_tx = pools_tx.clone();
+        let rpc_pool_manager = rpc_pool_manager.clone();
+        let crawl_status_tx = crawl_status_tx.clone();
+        let termination_flag = termination_flag.clone();
+        let pool_cache = pool_cache.clone();
+        let clickhouse_client = db_client();
+
+        let handle = thread::spawn(move || {
+            while let Ok(trade) = unresolved_trades_rx.recv() {
+                if is_terminated(&termination_flag) {
+                    println!("{} Termination flag set. Exiting", log_tag);
+                    break;
+                }
+
+                let pool = resolve_pool(
+                    &trade.pool_address,
+                    &pool_cache,
+                    &clickhouse_client,
+                    &rpc_pool_manager,
+                    &pools_tx,
+                    &termination_flag,
+                    Some(thread_index as u64),
+                );
+
+                match pool {
+                    // the signature was already marked as succeeded when it was crawled, so
+                    // the lost trade is recorded as a failed crawl instead of dropped silently
+                    Err(error) => {
+                        println!(
+                            "{} Could not resolve pool {} for {}: {}. Marking as failed",
+                            log_tag, trade.pool_address, trade.transaction_signature, error
+                        );
+                        terminate_on_error(
+                            &termination_flag,
+                            mark_as_failed(
+                                &crawl_status_tx,
+                                &trade.transaction_signature,
+                                &error.to_string(),
+                            ),
+                        );
+                    }
+                    Ok(pool) => {
+                        let trade = resolve_pool_trade(trade, &pool);
+                        terminate_on_error(&termination_flag, trades_tx.send(trade));
+                    }
+                }
+            }
+        });
+
+        handles.push(handle);
+    }
+
+    handles
+}
@@ -0,0 +1,29 @@
+use super::{pool::PoolAddress, table::PoolRow};
+use crate::{
+    orca_whirlpool::{
+        program::get_orca_whirlpool_program_address, state::orca_whirlpool_pool_from_state,
+    },
+    raydium::{amm::get_raydium_amm_program_address, state::raydium_pool_from_state},
+    raydium_clmm::{
+        program::get_raydium_clmm_program_address, state::raydium_clmm_pool_from_state,
+    },
+    trades::db::table::TradeVenue,
+};
+use solana_sdk::pubkey::Pubkey;
+
+// The pool's program decides its layout and venue. Only venues whose swaps leave their
+// mints out are resolved from state.
+pub fn pool_from_account(pool: &PoolAddress, owner: &Pubkey, data: &[u8]) -> Option<PoolRow> {
+    if *owner == get_raydium_amm_program_address() {
+        let pool = raydium_pool_from_state(pool, data)?;
+        Some(PoolRow::from_pool(&pool, TradeVenue::RaydiumAmm))
+    } else if *owner == get_raydium_clmm_program_address() {
+        let pool = raydium_clmm_pool_from_state(pool, data)?;
+        Some(PoolRow::from_pool(&pool, TradeVenue::RaydiumClmm))
+    } else if *owner == get_orca_whirlpool_program_address() {
+        let pool = orca_whirlpool_pool_from_state(pool, data)?;
+        Some(PoolRow::from_pool(&pool, TradeVenue::OrcaWhirlpool))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,45 @@
+use super::{inserts::insert_pool, table::PoolRow};
+use crate::{
+    constants::STORE_CONCURRENCY,
+    db::client::db_client,
+    utils::{blocking::blocking_call, log::log_time},
+};
+use crossbeam::channel::Receiver;
+use std::thread;
+
+const CONCURRENCY: usize = STORE_CONCURRENCY;
+
+pub fn store_pools(pools_rx: &Receiver<PoolRow>) -> Vec<thread::JoinHandle<()>> {
+    let mut handles = Vec::with_capacity(CONCURRENCY);
+
+    for thread_index in 0..CONCURRENCY {
+        let log_tag = format!(
+            "                 {} store pools #{} | ",
+            log_time(),
+            thread_index
+        );
+
+        let pools_rx = pools_rx.clone();
+
+        let handle = thread::spawn(move || {
+            let db_client = db_client();
+
+            while let Ok(pool) = pools_rx.recv() {
+                println!(
+                    "{} Storing {} pool {} for {}",
+                    log_tag,
+                    pool.venue.as_str(),
+                    pool.pool_address,
+                    pool.mint_address
+                );
+
+                let client = db_client.clone();
+                blocking_call(async move { insert_pool(&client, &pool).await.unwrap() });
+            }
+        });
+
+        handles.push(handle);
+    }
+
+    handles
+}
@@ -0,0 +1,90 @@
+use super::pool::Pool;
+use crate::{
+    db::migrate::add_columns, token::program::is_traded_mint, trades::db::table::TradeVenue,
+};
+use clickhouse::{Client, Row};
+use serde::Deserialize;
+
+// Named for the raydium amm pools it first held, pools of every venue are stored here
+pub const CLICKHOUSE_POOLS_TABLE_NAME: &str = "raydium_pools";
+
+// `mint_address` is the side of the pool that isn't a quote mint, the token the pool prices
+#[derive(Debug, Clone, Row, Deserialize)]
+pub struct PoolRow {
+    pub pool_address: String,
+    pub mint_address: String,
+    pub coin_mint_address: String,
+    pub pc_mint_address: String,
+    pub coin_vault_address: String,
+    pub pc_vault_address: String,
+    pub venue: TradeVenue,
+}
+
+impl PoolRow {
+    pub fn from_pool(pool: &Pool, venue: TradeVenue) -> Self {
+        let coin_mint_address = pool.coin_mint.to_string();
+        let pc_mint_address = pool.pc_mint.to_string();
+        let mint_address = if is_traded_mint(&coin_mint_address, &pc_mint_address) {
+            coin_mint_address.clone()
+        } else {
+            pc_mint_address.clone()
+        };
+
+        Self {
+            pool_address: pool.pool.to_string(),
+            mint_address,
+            coin_mint_address,
+            pc_mint_address,
+            coin_vault_address: pool.coin_vault.to_string(),
+            pc_vault_address: pool.pc_vault.to_string(),
+            venue,
+        }
+    }
+}
+
+pub async fn create_pools_table(client: &Client) -> Result<(), clickhouse::error::Error> {
+    client
+        .query(
+            format!(
+                "
+        CREATE TABLE IF NOT EXISTS {} (
+            pool_address String,
+            mint_address String,
+            coin_mint_address String,
+            pc_mint_address String,
+            coin_vault_address String,
+            pc_vault_address String,
+            venue {},
+        )
+        ENGINE = ReplacingMergeTree()
+        ORDER BY (pool_address, mint_address)
+    ",
+                CLICKHOUSE_POOLS_TABLE_NAME,
+                TradeVenue::column_type(),
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+
+    // pools stored before their venue are taken for raydium amm pools, as most of them were
+    let venue_column = format!(
+        "venue {} DEFAULT '{}'",
+        TradeVenue::column_type(),
+        TradeVenue::RaydiumAmm.as_str()
+    );
+    add_columns(
+        client,
+        CLICKHOUSE_POOLS_TABLE_NAME,
+        &[
+            "coin_mint_address String",
+            "pc_mint_address String",
+            "coin_vault_address String",
+            "pc_vault_address String",
+            venue_column.as_str(),
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
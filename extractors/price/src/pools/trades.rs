@@ -0,0 +1,180 @@
+use super::{pool::PoolSwap, table::PoolRow};
+use crate::{
+    instructions::{
+        instruction::{Instruction, InstructionIndex},
+        parse::InstructionWithLogs,
+    },
+    orca_whirlpool::{
+        instructions::OrcaWhirlpoolInstruction, trades::trade_from_orca_whirlpool_instruction,
+    },
+    raydium::trades::trade_from_raydium_instruction,
+    raydium_clmm::{
+        instructions::RaydiumClmmInstruction, trades::trade_from_raydium_clmm_instruction,
+    },
+    token::program::is_traded_mint,
+    trades::db::table::{TradeDirection, TradeRow, TradeVenue},
+    transactions::costs::TransactionCosts,
+};
+use solana_sdk::pubkey::Pubkey;
+use time::OffsetDateTime;
+
+// Amounts and direction are those of the swap's coin vault
+pub fn trade_from_vault_swap(
+    swap: &PoolSwap,
+    venue: TradeVenue,
+    instruction_index: &InstructionIndex,
+    slot: u64,
+    block_time: u64,
+    token_tx_signature: &str,
+) -> TradeRow {
+    TradeRow {
+        coin_token_address: String::new(),
+        price_coin_token_address: String::new(),
+        transaction_signature: token_tx_signature.to_string(),
+        slot,
+        block_time: OffsetDateTime::from_unix_timestamp(block_time as i64).unwrap(),
+        instruction_index: *instruction_index,
+        trade_ordinal: 0,
+        coin_token_amount: swap.coin_amount,
+        price_coin_token_amount: swap.pc_amount,
+        direction: swap.direction,
+        pool_address: swap.pool.to_string(),
+        venue,
+        route_id: None,
+        route_hop_index: None,
+        trader_address: swap.user.to_string(),
+        costs: TransactionCosts::default(),
+        virtual_sol_reserves: None,
+        virtual_token_reserves: None,
+        bonding_curve_progress: None,
+    }
+}
+
+// Trades are priced in the pool's quote side. Pools listing a quote mint as their coin are
+// flipped so the traded token is always the coin token.
+pub fn resolve_pool_trade(mut trade: TradeRow, pool: &PoolRow) -> TradeRow {
+    if pool.mint_address == pool.coin_mint_address {
+        trade.coin_token_address = pool.coin_mint_address.clone();
+        trade.price_coin_token_address = pool.pc_mint_address.clone();
+        return trade;
+    }
+
+    trade.coin_token_address = pool.pc_mint_address.clone();
+    trade.price_coin_token_address = pool.coin_mint_address.clone();
+    std::mem::swap(
+        &mut trade.coin_token_amount,
+        &mut trade.price_coin_token_amount,
+    );
+    trade.direction = match trade.direction {
+        TradeDirection::Buy => TradeDirection::Sell,
+        TradeDirection::Sell => TradeDirection::Buy,
+    };
+
+    trade
+}
+
+// The traded token is picked the same way as for resolved pools, the side that isn't a
+// quote mint. Cpmm and clmm pools order their mints by address, so pools of two tokens
+// trade their first mint like amm pools trade their coin.
+pub fn trade_from_pool_swap(
+    pool_address: &Pubkey,
+    venue: TradeVenue,
+    trader_address: &str,
+    (input_mint, input_amount): (&Pubkey, u64),
+    (output_mint, output_amount): (&Pubkey, u64),
+    instruction_index: &InstructionIndex,
+    slot: u64,
+    block_time: u64,
+    token_tx_signature: &str,
+) -> TradeRow {
+    let (mint_0, mint_1) = if input_mint < output_mint {
+        (input_mint, output_mint)
+    } else {
+        (output_mint, input_mint)
+    };
+    let coin_mint = if is_traded_mint(&mint_0.to_string(), &mint_1.to_string()) {
+        mint_0
+    } else {
+        mint_1
+    };
+
+    let (coin_token_amount, price_coin_token_amount, direction) = if output_mint == coin_mint {
+        (output_amount, input_amount, TradeDirection::Buy)
+    } else {
+        (input_amount, output_amount, TradeDirection::Sell)
+    };
+    let price_coin_mint = if coin_mint == input_mint {
+        output_mint
+    } else {
+        input_mint
+    };
+
+    TradeRow {
+        coin_token_address: coin_mint.to_string(),
+        price_coin_token_address: price_coin_mint.to_string(),
+        transaction_signature: token_tx_signature.to_string(),
+        slot,
+        block_time: OffsetDateTime::from_unix_timestamp(block_time as i64).unwrap(),
+        instruction_index: *instruction_index,
+        trade_ordinal: 0,
+        coin_token_amount,
+        price_coin_token_amount,
+        direction,
+        pool_address: pool_address.to_string(),
+        venue,
+        route_id: None,
+        route_hop_index: None,
+        trader_address: trader_address.to_string(),
+        costs: TransactionCosts::default(),
+        virtual_sol_reserves: None,
+        virtual_token_reserves: None,
+        bonding_curve_progress: None,
+    }
+}
+
+// Swaps of venues that leave their mints out, waiting for their pool to be resolved
+pub fn unresolved_trades_from_instructions(
+    instructions_with_logs: &[InstructionWithLogs],
+    slot: u64,
+    block_time: u64,
+    token_tx_signature: &str,
+) -> Vec<TradeRow> {
+    instructions_with_logs
+        .iter()
+        .filter_map(
+            |instruction_with_logs| match &instruction_with_logs.instruction {
+                Some(Instruction::Raydium(instruction_index, instruction)) => {
+                    trade_from_raydium_instruction(
+                        instruction,
+                        instruction_index,
+                        slot,
+                        block_time,
+                        token_tx_signature,
+                    )
+                }
+                Some(Instruction::RaydiumClmm(
+                    instruction_index,
+                    instruction @ RaydiumClmmInstruction::Swap(_),
+                )) => trade_from_raydium_clmm_instruction(
+                    instruction,
+                    instruction_index,
+                    slot,
+                    block_time,
+                    &instruction_with_logs.cpi_logs,
+                    token_tx_signature,
+                ),
+                Some(Instruction::OrcaWhirlpool(
+                    instruction_index,
+                    instruction @ OrcaWhirlpoolInstruction::Swap(_),
+                )) => Some(trade_from_orca_whirlpool_instruction(
+                    instruction,
+                    instruction_index,
+                    slot,
+                    block_time,
+                    token_tx_signature,
+                )),
+                _ => None,
+            },
+        )
+        .collect()
+}
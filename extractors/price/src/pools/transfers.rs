@@ -0,0 +1,131 @@
+use crate::{
+    instructions::parse::ParsableInstruction,
+    system::{
+        idl::{SystemTransferAccountsOrder, SystemTransferInstructionData},
+        program::SYSTEM_PROGRAM_ADDRESS,
+    },
+    token::{
+        idl::{
+            TokenTransferAccountsOrder, TokenTransferCheckedAccountsOrder,
+            TokenTransferCheckedInstructionData, TokenTransferInstructionData,
+        },
+        program::is_token_program,
+    },
+    trades::db::table::TradeDirection,
+    utils::log::log_time,
+};
+use borsh::BorshDeserialize;
+
+pub type TransferSource = String;
+pub type TransferDestination = String;
+pub type TransferAmount = u64;
+
+pub fn parse_inner_transfer_instruction(
+    ix: &ParsableInstruction,
+    account_keys: &[String],
+) -> Option<(TransferAmount, TransferSource, TransferDestination)> {
+    let ix_data = bs58::decode(&ix.data).into_vec().ok()?;
+    let program_address = account_keys.get(ix.program_id_index)?;
+
+    let transfer_accounts = |source_order: usize, destination_order: usize| {
+        let source_index = ix.accounts.get(source_order)?;
+        let destination_index = ix.accounts.get(destination_order)?;
+
+        let source = account_keys.get(*source_index as usize)?;
+        let destination = account_keys.get(*destination_index as usize)?;
+
+        Some((source.to_string(), destination.to_string()))
+    };
+
+    if program_address == SYSTEM_PROGRAM_ADDRESS {
+        // system instructions are tagged with a u32
+        if ix_data.get(..4)? != [2, 0, 0, 0] {
+            return None;
+        }
+
+        let amount = SystemTransferInstructionData::deserialize(&mut &ix_data[4..]).ok()?;
+        let (source, destination) = transfer_accounts(
+            SystemTransferAccountsOrder::Source as usize,
+            SystemTransferAccountsOrder::Destination as usize,
+        )?;
+
+        Some((amount.lamports, source, destination))
+    } else if is_token_program(program_address) {
+        match *ix_data.first()? {
+            3 => {
+                let amount = TokenTransferInstructionData::deserialize(&mut &ix_data[1..]).ok()?;
+                let (source, destination) = transfer_accounts(
+                    TokenTransferAccountsOrder::Source as usize,
+                    TokenTransferAccountsOrder::Destination as usize,
+                )?;
+
+                Some((amount.amount, source, destination))
+            }
+            12 => {
+                let amount =
+                    TokenTransferCheckedInstructionData::deserialize(&mut &ix_data[1..]).ok()?;
+                let (source, destination) = transfer_accounts(
+                    TokenTransferCheckedAccountsOrder::Source as usize,
+                    TokenTransferCheckedAccountsOrder::Destination as usize,
+                )?;
+
+                Some((amount.amount, source, destination))
+            }
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+// The user pays into one vault and is paid out of the other. Anything else moving in the
+// same instructions, fees or wrapping SOL, doesn't touch the vaults and is left out.
+pub fn swap_flow_from_transfers(
+    transfers: &[(TransferAmount, TransferSource, TransferDestination)],
+    coin_vault_str: &str,
+    pc_vault_str: &str,
+) -> Option<(u64, u64, TradeDirection)> {
+    let input = transfers
+        .iter()
+        .find(|(_, _, destination)| destination == coin_vault_str || destination == pc_vault_str);
+
+    let (input_amount, _, input_vault) = match input {
+        Some(input) => input,
+        None => {
+            println!(
+                "{} No transfer into the pool vaults {} or {}",
+                log_time(),
+                coin_vault_str,
+                pc_vault_str
+            );
+            return None;
+        }
+    };
+
+    let output_vault = if input_vault == coin_vault_str {
+        pc_vault_str
+    } else {
+        coin_vault_str
+    };
+
+    let output_amount = match transfers
+        .iter()
+        .find(|(_, source, _)| source == output_vault)
+    {
+        Some((output_amount, _, _)) => output_amount,
+        None => {
+            println!(
+                "{} No transfer out of the pool vault {}",
+                log_time(),
+                output_vault
+            );
+            return None;
+        }
+    };
+
+    if input_vault == coin_vault_str {
+        Some((*input_amount, *output_amount, TradeDirection::Sell))
+    } else {
+        Some((*output_amount, *input_amount, TradeDirection::Buy))
+    }
+}
@@ -1,12 +1,12 @@
+use super::idl::pump_fun_decoder;
+use crate::{anchor::value::IdlValue, cpi::cpi::CpiLog, utils::log::log_time};
 use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
-use byteorder::{LittleEndian, ReadBytesExt};
 use solana_sdk::pubkey::Pubkey;
-use std::io::{Cursor, Read};
 
 // TODO: use these features, remove allow dead
 #[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
-pub struct PumpFunCpiLog {
+#[derive(Debug, Clone)]
+pub struct PumpFunTradeEvent {
     pub sol_amount: u64,
     pub token_amount: u64,
     pub is_buy: bool,
@@ -17,47 +17,105 @@ pub struct PumpFunCpiLog {
     pub virtual_token_reserves: u64,
 }
 
-impl PumpFunCpiLog {
-    pub fn from_encoded_log(log: &str) -> Option<Self> {
-        if !log.contains("Program data:") {
-            return None;
-        }
-
-        let base64_data = log.split_whitespace().last()?;
-        let decoded = base64.decode(base64_data).ok()?;
-        let mut cursor = Cursor::new(decoded);
+#[derive(Debug, Clone)]
+pub struct PumpFunCreateEvent {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub user: Pubkey,
+}
 
-        cursor.set_position(8);
+// TODO: use these features, remove allow dead
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct PumpFunCompleteEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub timestamp: i64,
+}
 
-        let mint = {
-            let mut mint_bytes = [0u8; 32];
-            cursor.read_exact(&mut mint_bytes).ok()?;
-            Pubkey::new_from_array(mint_bytes)
-        };
+#[derive(Debug, Clone)]
+pub struct PumpFunSetParamsEvent {
+    pub initial_virtual_token_reserves: u64,
+    pub initial_real_token_reserves: u64,
+}
 
-        let sol_amount = cursor.read_u64::<LittleEndian>().ok()?;
-        let token_amount = cursor.read_u64::<LittleEndian>().ok()?;
-        let is_buy = cursor.read_u8().ok()? != 0;
+impl PumpFunTradeEvent {
+    fn from_fields(fields: &IdlValue) -> Option<Self> {
+        Some(Self {
+            sol_amount: fields.get("sol_amount")?.as_u64()?,
+            token_amount: fields.get("token_amount")?.as_u64()?,
+            is_buy: fields.get("is_buy")?.as_bool()?,
+            mint: fields.get("mint")?.as_pubkey()?,
+            user: fields.get("user")?.as_pubkey()?,
+            timestamp: fields.get("timestamp")?.as_i64()?,
+            virtual_sol_reserves: fields.get("virtual_sol_reserves")?.as_u64()?,
+            virtual_token_reserves: fields.get("virtual_token_reserves")?.as_u64()?,
+        })
+    }
+}
 
-        let user = {
-            let mut user_bytes = [0u8; 32];
-            cursor.read_exact(&mut user_bytes).ok()?;
-            Pubkey::new_from_array(user_bytes)
-        };
+impl PumpFunCreateEvent {
+    fn from_fields(fields: &IdlValue) -> Option<Self> {
+        Some(Self {
+            name: fields.get("name")?.as_str()?.to_string(),
+            symbol: fields.get("symbol")?.as_str()?.to_string(),
+            uri: fields.get("uri")?.as_str()?.to_string(),
+            mint: fields.get("mint")?.as_pubkey()?,
+            bonding_curve: fields.get("bonding_curve")?.as_pubkey()?,
+            user: fields.get("user")?.as_pubkey()?,
+        })
+    }
+}
 
-        let timestamp = cursor.read_i64::<LittleEndian>().ok()?;
-        let virtual_sol_reserves = cursor.read_u64::<LittleEndian>().ok()?;
-        let virtual_token_reserves = cursor.read_u64::<LittleEndian>().ok()?;
+impl PumpFunCompleteEvent {
+    fn from_fields(fields: &IdlValue) -> Option<Self> {
+        Some(Self {
+            user: fields.get("user")?.as_pubkey()?,
+            mint: fields.get("mint")?.as_pubkey()?,
+            bonding_curve: fields.get("bonding_curve")?.as_pubkey()?,
+            timestamp: fields.get("timestamp")?.as_i64()?,
+        })
+    }
+}
 
+impl PumpFunSetParamsEvent {
+    fn from_fields(fields: &IdlValue) -> Option<Self> {
         Some(Self {
-            sol_amount,
-            token_amount,
-            is_buy,
-            mint,
-            user,
-            timestamp,
-            virtual_sol_reserves,
-            virtual_token_reserves,
+            initial_virtual_token_reserves: fields
+                .get("initial_virtual_token_reserves")?
+                .as_u64()?,
+            initial_real_token_reserves: fields.get("initial_real_token_reserves")?.as_u64()?,
         })
     }
 }
+
+// `data` is what follows `Program data:` in the logs of a pump.fun invocation.
+// Events with an unknown discriminator are skipped.
+pub fn pump_fun_cpi_log_from_event_data(data: &str) -> Option<CpiLog> {
+    let base64_data = data.split_whitespace().last()?;
+    let decoded = base64.decode(base64_data).ok()?;
+
+    let event = match pump_fun_decoder().decode_event(&decoded) {
+        Ok(event) => event?,
+        Err(error) => {
+            println!("{} Failed to decode pump.fun event: {}", log_time(), error);
+            return None;
+        }
+    };
+
+    match event.name.as_str() {
+        "TradeEvent" => PumpFunTradeEvent::from_fields(&event.fields).map(CpiLog::PumpFunTrade),
+        "CreateEvent" => PumpFunCreateEvent::from_fields(&event.fields).map(CpiLog::PumpFunCreate),
+        "CompleteEvent" => {
+            PumpFunCompleteEvent::from_fields(&event.fields).map(CpiLog::PumpFunComplete)
+        }
+        "SetParamsEvent" => {
+            PumpFunSetParamsEvent::from_fields(&event.fields).map(CpiLog::PumpFunSetParams)
+        }
+        _ => None,
+    }
+}
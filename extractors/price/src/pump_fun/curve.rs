@@ -0,0 +1,86 @@
+use super::cpi::PumpFunSetParamsEvent;
+use crate::{cpi::cpi::CpiLog, instructions::parse::InstructionWithLogs};
+use std::{collections::BTreeMap, sync::RwLock};
+
+// The reserves pump.fun curves start from until a SetParams event changes them. The virtual
+// reserves only price the curve, the real token reserves are what is left to sell before it
+// completes.
+pub const INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000;
+pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
+pub const TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurveParams {
+    pub initial_virtual_token_reserves: u64,
+    pub initial_real_token_reserves: u64,
+}
+
+impl CurveParams {
+    pub const fn initial() -> Self {
+        Self {
+            initial_virtual_token_reserves: INITIAL_VIRTUAL_TOKEN_RESERVES,
+            initial_real_token_reserves: INITIAL_REAL_TOKEN_RESERVES,
+        }
+    }
+}
+
+impl From<&PumpFunSetParamsEvent> for CurveParams {
+    fn from(set_params_event: &PumpFunSetParamsEvent) -> Self {
+        Self {
+            initial_virtual_token_reserves: set_params_event.initial_virtual_token_reserves,
+            initial_real_token_reserves: set_params_event.initial_real_token_reserves,
+        }
+    }
+}
+
+// Params of every SetParams event seen so far, by the slot they took effect in. A trade is
+// measured against the ones in effect at its slot, whatever order slots are crawled in.
+// Events this process has not seen yet are stored and applied by the curve prices view.
+static CURVE_PARAMS: RwLock<BTreeMap<u64, CurveParams>> = RwLock::new(BTreeMap::new());
+
+pub fn curve_params_at(slot: u64) -> CurveParams {
+    CURVE_PARAMS
+        .read()
+        .unwrap()
+        .range(..=slot)
+        .next_back()
+        .map(|(_, curve_params)| *curve_params)
+        .unwrap_or(CurveParams::initial())
+}
+
+pub fn update_curve_params(instructions_with_logs: &[InstructionWithLogs], slot: u64) {
+    let latest = set_params_events(instructions_with_logs)
+        .last()
+        .map(CurveParams::from);
+
+    if let Some(latest) = latest {
+        CURVE_PARAMS.write().unwrap().insert(slot, latest);
+    }
+}
+
+pub fn set_params_events(
+    instructions_with_logs: &[InstructionWithLogs],
+) -> impl Iterator<Item = &PumpFunSetParamsEvent> {
+    instructions_with_logs
+        .iter()
+        .flat_map(|instruction_with_logs| instruction_with_logs.cpi_logs.iter())
+        .filter_map(|cpi_log| match cpi_log {
+            CpiLog::PumpFunSetParams(set_params_event) => Some(set_params_event),
+            _ => None,
+        })
+}
+
+// Percent of the sellable tokens bought off the curve, 100 once it completes
+pub fn bonding_curve_progress(virtual_token_reserves: u64, curve_params: &CurveParams) -> f64 {
+    let initial_real_token_reserves = curve_params.initial_real_token_reserves;
+    let real_token_reserves = virtual_token_reserves
+        .saturating_sub(
+            curve_params
+                .initial_virtual_token_reserves
+                .saturating_sub(initial_real_token_reserves),
+        )
+        .min(initial_real_token_reserves);
+
+    (initial_real_token_reserves - real_token_reserves) as f64 * 100.0
+        / initial_real_token_reserves.max(1) as f64
+}
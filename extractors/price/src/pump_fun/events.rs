@@ -0,0 +1,93 @@
+use super::instructions::PumpFunInstruction;
+use crate::{
+    cpi::cpi::CpiLog,
+    instructions::{instruction::Instruction, parse::InstructionWithLogs},
+    raydium::pools::raydium_pools_from_instructions,
+    token::events::table::{TokenEventKind, TokenEventRow},
+    trades::errors::TradeCrawlError,
+};
+use time::OffsetDateTime;
+
+pub fn token_events_from_instructions(
+    instructions_with_logs: &[InstructionWithLogs],
+    slot: u64,
+    block_time: u64,
+    transaction_signature: &str,
+) -> Result<Vec<TokenEventRow>, TradeCrawlError> {
+    let block_time = OffsetDateTime::from_unix_timestamp(block_time as i64)
+        .map_err(|error| TradeCrawlError::BlockTimeParseError(error))?;
+    let raydium_pools = raydium_pools_from_instructions(instructions_with_logs);
+
+    let mut token_events = Vec::new();
+
+    for instruction_with_logs in instructions_with_logs {
+        // the migration account withdraws the curve's liquidity and seeds the raydium pool
+        // with it in the same transaction
+        if let Some(Instruction::PumpFun(_, PumpFunInstruction::Withdraw((decoded, token)))) =
+            &instruction_with_logs.instruction
+        {
+            let (mint_address, bonding_curve_address) = token;
+            let pool_address = raydium_pools
+                .iter()
+                .find(|raydium_pool| {
+                    raydium_pool.coin_mint == *mint_address || raydium_pool.pc_mint == *mint_address
+                })
+                .map(|raydium_pool| raydium_pool.pool.to_string())
+                .unwrap_or_default();
+
+            token_events.push(TokenEventRow {
+                mint_address: mint_address.to_string(),
+                bonding_curve_address: bonding_curve_address.to_string(),
+                kind: TokenEventKind::Migrate,
+                transaction_signature: transaction_signature.to_string(),
+                slot,
+                block_time,
+                user_address: decoded.account("user").unwrap_or_default().to_string(),
+                name: String::new(),
+                symbol: String::new(),
+                uri: String::new(),
+                pool_address,
+            });
+        }
+
+        for cpi_log in instruction_with_logs.cpi_logs.iter() {
+            let (kind, mint, bonding_curve, user, name, symbol, uri) = match cpi_log {
+                CpiLog::PumpFunCreate(create_event) => (
+                    TokenEventKind::Create,
+                    create_event.mint,
+                    create_event.bonding_curve,
+                    create_event.user,
+                    create_event.name.clone(),
+                    create_event.symbol.clone(),
+                    create_event.uri.clone(),
+                ),
+                CpiLog::PumpFunComplete(complete_event) => (
+                    TokenEventKind::Complete,
+                    complete_event.mint,
+                    complete_event.bonding_curve,
+                    complete_event.user,
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ),
+                _ => continue,
+            };
+
+            token_events.push(TokenEventRow {
+                mint_address: mint.to_string(),
+                bonding_curve_address: bonding_curve.to_string(),
+                kind,
+                transaction_signature: transaction_signature.to_string(),
+                slot,
+                block_time,
+                user_address: user.to_string(),
+                name,
+                symbol,
+                uri,
+                pool_address: String::new(),
+            });
+        }
+    }
+
+    Ok(token_events)
+}
@@ -1,25 +1,19 @@
-use super::{
-    idl::{BuyInstructionArgs, CreateInstructionArgs, SellInstructionArgs},
-    tokens::PumpFunToken,
-};
+use super::{idl::pump_fun_decoder, tokens::PumpFunToken};
 use crate::{
+    anchor::decoder::DecodedInstruction,
     instructions::{instruction::Instruction, parse::ParsableInstruction},
-    pump_fun::{
-        discriminators::{
-            build_pump_fun_instruction_discriminators, PumpFunInstructionDiscriminator,
-        },
-        idl::{BuyAccountsOrder, CreateAccountsOrder, SellAccountsOrder},
-    },
+    utils::log::log_time,
 };
-use borsh::BorshDeserialize;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 
 #[derive(Debug)]
 pub enum PumpFunInstruction {
-    Create((CreateInstructionArgs, PumpFunToken)),
-    Buy((BuyInstructionArgs, PumpFunToken)),
-    Sell((SellInstructionArgs, PumpFunToken)),
+    Create((DecodedInstruction, PumpFunToken)),
+    Buy((DecodedInstruction, PumpFunToken)),
+    Sell((DecodedInstruction, PumpFunToken)),
+    // the migration account taking the liquidity of a completed curve to raydium
+    Withdraw((DecodedInstruction, PumpFunToken)),
 }
 
 pub fn parse_single_pump_fun_instruction(
@@ -28,85 +22,38 @@ pub fn parse_single_pump_fun_instruction(
     instruction_index: u64,
 ) -> Option<Instruction> {
     let data = bs58::decode(&parsable.data).into_vec().ok()?;
-    if data.len() < 8 {
-        return None;
-    }
-
-    let discriminator: [u8; 8] = data[..8].try_into().unwrap();
-    let pump_fun_discriminators = build_pump_fun_instruction_discriminators();
-
-    let found_discriminator = pump_fun_discriminators.get(&discriminator);
-
-    let instruction = match found_discriminator {
-        None => None,
-        Some(PumpFunInstructionDiscriminator::Create) => {
-            let args = CreateInstructionArgs::deserialize(&mut &data[8..]).unwrap();
-
-            // TODO: We now have found the name, symbol and metadata uri of a coin,
-            // we should insert into db
-            //and trigger a scrape of the metadata uri for json payload returning name, symbol, description, image uri, showName, createdOn, twitter, and maybe more?
-
-            let mint = parsable
-                .accounts
-                .get(CreateAccountsOrder::Mint as usize)
-                .unwrap();
-            let bonding_curve = parsable
-                .accounts
-                .get(CreateAccountsOrder::BondingCurve as usize)
-                .unwrap();
-            let mint_str = account_keys.get(*mint as usize).unwrap();
-            let bonding_curve_str = account_keys.get(*bonding_curve as usize).unwrap();
-            let mint_address = Pubkey::from_str(mint_str).unwrap();
-            let bonding_curve_address = Pubkey::from_str(bonding_curve_str).unwrap();
-
-            Some(Instruction::PumpFun(
-                instruction_index,
-                PumpFunInstruction::Create((args, (mint_address, bonding_curve_address))),
-            ))
+    let accounts = parsable
+        .accounts
+        .iter()
+        .map(|account| account_keys.get(*account as usize).cloned())
+        .collect::<Option<Vec<String>>>()?;
+
+    let decoded = match pump_fun_decoder().decode_instruction(&data, &accounts) {
+        Ok(decoded) => decoded?,
+        Err(error) => {
+            println!(
+                "{} Failed to decode pump.fun instruction: {}",
+                log_time(),
+                error
+            );
+            return None;
         }
-        Some(PumpFunInstructionDiscriminator::Buy) => {
-            let args = BuyInstructionArgs::deserialize(&mut &data[8..]).unwrap();
-
-            let mint: &u8 = parsable
-                .accounts
-                .get(BuyAccountsOrder::Mint as usize)
-                .unwrap();
-            let bonding_curve = parsable
-                .accounts
-                .get(BuyAccountsOrder::BondingCurve as usize)
-                .unwrap();
-            let mint_str = account_keys.get(*mint as usize).unwrap();
-            let bonding_curve_str = account_keys.get(*bonding_curve as usize).unwrap();
-            let mint_address = Pubkey::from_str(mint_str).unwrap();
-            let bonding_curve_address = Pubkey::from_str(bonding_curve_str).unwrap();
-
-            Some(Instruction::PumpFun(
-                instruction_index,
-                PumpFunInstruction::Buy((args, (mint_address, bonding_curve_address))),
-            ))
-        }
-        Some(PumpFunInstructionDiscriminator::Sell) => {
-            let args = SellInstructionArgs::deserialize(&mut &data[8..]).unwrap();
-
-            let mint = parsable
-                .accounts
-                .get(SellAccountsOrder::Mint as usize)
-                .unwrap();
-            let bonding_curve = parsable
-                .accounts
-                .get(SellAccountsOrder::BondingCurve as usize)
-                .unwrap();
-            let mint_str = account_keys.get(*mint as usize).unwrap();
-            let bonding_curve_str = account_keys.get(*bonding_curve as usize).unwrap();
-            let mint_address = Pubkey::from_str(mint_str).unwrap();
-            let bonding_curve_address = Pubkey::from_str(bonding_curve_str).unwrap();
+    };
 
-            Some(Instruction::PumpFun(
-                instruction_index,
-                PumpFunInstruction::Sell((args, (mint_address, bonding_curve_address))),
-            ))
-        }
+    // TODO: We now have found the name, symbol and metadata uri of a coin on create,
+    // we should insert into db
+    //and trigger a scrape of the metadata uri for json payload returning name, symbol, description, image uri, showName, createdOn, twitter, and maybe more?
+    let mint_address = Pubkey::from_str(decoded.account("mint")?).ok()?;
+    let bonding_curve_address = Pubkey::from_str(decoded.account("bonding_curve")?).ok()?;
+    let pump_fun_token = (mint_address, bonding_curve_address);
+
+    let instruction = match decoded.name.as_str() {
+        "create" => PumpFunInstruction::Create((decoded, pump_fun_token)),
+        "buy" => PumpFunInstruction::Buy((decoded, pump_fun_token)),
+        "sell" => PumpFunInstruction::Sell((decoded, pump_fun_token)),
+        "withdraw" => PumpFunInstruction::Withdraw((decoded, pump_fun_token)),
+        _ => return None,
     };
 
-    return instruction;
+    Some(Instruction::PumpFun(instruction_index, instruction))
 }
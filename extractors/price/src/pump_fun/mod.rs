@@ -1,8 +1,10 @@
 pub mod cpi;
-pub mod discriminators;
+pub mod curve;
 pub mod errors;
+pub mod events;
 pub mod idl;
 pub mod instructions;
+pub mod params;
 pub mod program;
 pub mod tokens;
 pub mod tokens_threads;
@@ -0,0 +1,33 @@
+use super::table::{CurveParamsRow, CLICKHOUSE_PUMP_FUN_CURVE_PARAMS_TABLE_NAME};
+use clickhouse::Client;
+
+pub async fn insert_curve_params(
+    client: &Client,
+    curve_params: &CurveParamsRow,
+) -> Result<(), clickhouse::error::Error> {
+    client
+        .query(
+            format!(
+                "
+            INSERT INTO {} (
+                transaction_signature,
+                slot,
+                block_time,
+                initial_virtual_token_reserves,
+                initial_real_token_reserves
+            ) VALUES ('{}', {}, {}, {}, {})
+            ",
+                CLICKHOUSE_PUMP_FUN_CURVE_PARAMS_TABLE_NAME,
+                curve_params.transaction_signature,
+                curve_params.slot,
+                curve_params.block_time.unix_timestamp(),
+                curve_params.initial_virtual_token_reserves,
+                curve_params.initial_real_token_reserves,
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+
+    Ok(())
+}
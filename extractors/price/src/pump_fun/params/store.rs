@@ -0,0 +1,46 @@
+use super::{inserts::insert_curve_params, table::CurveParamsRow};
+use crate::{
+    constants::STORE_CONCURRENCY,
+    db::client::db_client,
+    utils::{blocking::blocking_call, log::log_time},
+};
+use crossbeam::channel::Receiver;
+use std::thread;
+
+const CONCURRENCY: usize = STORE_CONCURRENCY;
+
+pub fn store_curve_params(
+    curve_params_rx: &Receiver<CurveParamsRow>,
+) -> Vec<thread::JoinHandle<()>> {
+    let mut handles = Vec::with_capacity(CONCURRENCY);
+
+    for thread_index in 0..CONCURRENCY {
+        let log_tag = format!(
+            "                 {} store curve params #{} | ",
+            log_time(),
+            thread_index
+        );
+
+        let curve_params_rx = curve_params_rx.clone();
+
+        let handle = thread::spawn(move || {
+            let db_client = db_client();
+
+            while let Ok(curve_params) = curve_params_rx.recv() {
+                println!(
+                    "{} Storing curve params of {} from slot {}",
+                    log_tag, curve_params.transaction_signature, curve_params.slot
+                );
+
+                let client = db_client.clone();
+                blocking_call(
+                    async move { insert_curve_params(&client, &curve_params).await.unwrap() },
+                );
+            }
+        });
+
+        handles.push(handle);
+    }
+
+    handles
+}
@@ -0,0 +1,62 @@
+use crate::pump_fun::{cpi::PumpFunSetParamsEvent, curve::CurveParams};
+use clickhouse::Client;
+use time::OffsetDateTime;
+
+pub const CLICKHOUSE_PUMP_FUN_CURVE_PARAMS_TABLE_NAME: &str = "pump_fun_curve_params";
+
+// Reserves new curves start from, as set by a SetParams event. They are in effect from its
+// slot until the next one.
+#[derive(Debug, Clone)]
+pub struct CurveParamsRow {
+    pub transaction_signature: String,
+    pub slot: u64,
+    pub block_time: OffsetDateTime,
+    pub initial_virtual_token_reserves: u64,
+    pub initial_real_token_reserves: u64,
+}
+
+impl CurveParamsRow {
+    pub fn from_event(
+        set_params_event: &PumpFunSetParamsEvent,
+        slot: u64,
+        block_time: u64,
+        transaction_signature: &str,
+    ) -> Self {
+        let curve_params = CurveParams::from(set_params_event);
+
+        Self {
+            transaction_signature: transaction_signature.to_string(),
+            slot,
+            block_time: OffsetDateTime::from_unix_timestamp(block_time as i64).unwrap(),
+            initial_virtual_token_reserves: curve_params.initial_virtual_token_reserves,
+            initial_real_token_reserves: curve_params.initial_real_token_reserves,
+        }
+    }
+}
+
+pub async fn create_pump_fun_curve_params_table(
+    client: &Client,
+) -> Result<(), clickhouse::error::Error> {
+    client
+        .query(
+            format!(
+                "
+        CREATE TABLE IF NOT EXISTS {} (
+            transaction_signature String,
+            slot UInt64,
+            block_time DateTime,
+            initial_virtual_token_reserves UInt64,
+            initial_real_token_reserves UInt64,
+        )
+        ENGINE = ReplacingMergeTree()
+        ORDER BY (slot, transaction_signature)
+    ",
+                CLICKHOUSE_PUMP_FUN_CURVE_PARAMS_TABLE_NAME,
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+
+    Ok(())
+}
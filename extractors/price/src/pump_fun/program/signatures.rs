@@ -8,9 +8,10 @@ use crate::{
     signatures::config::{
         build_signatures_config, build_signatures_window_config, DEFAULT_SIGNATURES_LIMIT,
     },
-    utils::log::log_time,
+    utils::{blocking::blocking_call, log::log_time},
 };
 use redis::Client;
+use solana_client::rpc_request::RpcRequest;
 use solana_sdk::pubkey::Pubkey;
 
 pub type TransactionSignature = String;
@@ -33,17 +34,19 @@ pub fn get_pump_fun_program_signatures(
                 log_time(), oldest_signature, limit
             );
 
-    let signatures = rpc_pool_manager
-        .execute(
-            |client| {
-                client.get_signatures_for_address_with_config(
-                    &program_address,
-                    build_signatures_config(oldest_signature, None, Some(limit)),
-                )
-            },
-            Some(thread_index as u64),
-        )
-        .map_err(PumpFunProgramSignaturesError::GetSignaturesFailed)?;
+    let signatures = blocking_call(rpc_pool_manager.execute(
+        RpcRequest::GetSignaturesForAddress,
+        |client| {
+            let config = build_signatures_config(oldest_signature.clone(), None, Some(limit));
+            async move {
+                client
+                    .get_signatures_for_address_with_config(program_address, config)
+                    .await
+            }
+        },
+        Some(thread_index as u64),
+    ))
+    .map_err(PumpFunProgramSignaturesError::GetSignaturesFailed)?;
 
     let signatures_count = signatures.len();
 
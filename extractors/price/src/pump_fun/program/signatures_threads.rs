@@ -76,6 +76,15 @@ pub fn pump_fun_program_signatures_threads(
                     println!("{} History complete. Skipping...", log_tag);
                     continue;
                 }
+                Err(PumpFunProgramSignaturesError::GetSignaturesFailed(error))
+                    if !error.is_fatal() =>
+                {
+                    println!(
+                        "{} Failed to get pump fun program signatures after retries: {}. Retrying batch",
+                        log_tag, error
+                    );
+                    continue;
+                }
                 Err(error) => {
                     println!(
                         "{} Error in pump fun program signatures thread: {}",
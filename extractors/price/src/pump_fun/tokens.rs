@@ -1,13 +1,17 @@
 use super::program::signatures::TransactionSignature;
 use crate::{
-    cpi::cpi::CpiLog,
     crawl_status::queries::has_crawled_signature,
     instructions::{instruction::Instruction, parse::InstructionWithLogs},
-    pump_fun::{errors::PumpFunTokenCrawlError, instructions::PumpFunInstruction},
+    pump_fun::{
+        curve::update_curve_params, errors::PumpFunTokenCrawlError,
+        instructions::PumpFunInstruction, trades::pump_fun_trades_from_cpi_logs,
+    },
     rpc::pool::RpcPoolManager,
-    trades::db::table::{TradeDirection, TradeRow},
+    trades::{db::table::TradeRow, trades::number_trades},
     transactions::{
-        config::TRANSACTION_CONFIG, parse::parse_transaction_with_logs,
+        costs::{transaction_costs, TransactionCosts},
+        fetch::get_transactions,
+        parse::parse_transaction_with_logs,
         status::is_failed_transaction,
     },
     utils::log::log_time,
@@ -28,143 +32,183 @@ pub type PumpFunToken = (MintAddress, BondingCurveAddress);
 
 type Tokens = HashMap<MintAddress, (PumpFunToken, Vec<TradeRow>)>;
 
-pub fn pump_fun_tokens_from_pump_fun_program_signature(
+pub async fn pump_fun_tokens_from_pump_fun_program_signatures(
     rpc_pool_manager: &RpcPoolManager,
     dragonfly_client: &Client,
-    pump_fun_program_signature: &TransactionSignature,
+    pump_fun_program_signatures: &[TransactionSignature],
     pump_fun_program_address: &Pubkey,
     thread_index: u64,
-    system_program_address: &Pubkey,
     raydium_amm_program_address: &Pubkey,
-) -> Result<Tokens, PumpFunTokenCrawlError> {
-    let log_tag = format!("{} Crawling pump fun transaction", log_time());
-
-    println!("{} Crawling pump fun transaction", log_tag);
-    if let Ok(has_crawled) = has_crawled_signature(&dragonfly_client, &pump_fun_program_signature) {
-        if has_crawled {
-            println!(
-                "{} Pump fun program signature already crawled. Skipping",
-                log_tag
-            );
-            return Err(PumpFunTokenCrawlError::AlreadyCrawled);
+) -> Vec<(TransactionSignature, Result<Tokens, PumpFunTokenCrawlError>)> {
+    let log_tag = format!("{} Crawling pump fun transactions", log_time());
+
+    let mut results = Vec::with_capacity(pump_fun_program_signatures.len());
+    let mut to_fetch = Vec::with_capacity(pump_fun_program_signatures.len());
+
+    for pump_fun_program_signature in pump_fun_program_signatures {
+        // offline runs reparse from the cache, so crawled signatures are processed again
+        if rpc_pool_manager.is_offline() {
+            to_fetch.push(pump_fun_program_signature.clone());
+            continue;
         }
+
+        if let Ok(has_crawled) =
+            has_crawled_signature(&dragonfly_client, &pump_fun_program_signature)
+        {
+            if has_crawled {
+                println!(
+                    "{} Pump fun program signature already crawled. Skipping",
+                    log_tag
+                );
+                results.push((
+                    pump_fun_program_signature.clone(),
+                    Err(PumpFunTokenCrawlError::AlreadyCrawled),
+                ));
+                continue;
+            }
+        }
+
+        to_fetch.push(pump_fun_program_signature.clone());
+    }
+
+    if to_fetch.is_empty() {
+        return results;
     }
 
     println!(
-        "\n     --------------------------------------------\n\n{} Getting mint addresses for {}",
-        log_tag, pump_fun_program_signature
-    );
-    let sig = Signature::from_str(&pump_fun_program_signature).unwrap();
-    let tx = rpc_pool_manager.execute(
-        |client| client.get_transaction_with_config(&sig, TRANSACTION_CONFIG),
-        Some(thread_index as u64),
+        "\n     --------------------------------------------\n\n{} Getting mint addresses for {} transactions",
+        log_tag,
+        to_fetch.len()
     );
+    let sigs = to_fetch
+        .iter()
+        .map(|signature| Signature::from_str(signature).unwrap())
+        .collect::<Vec<_>>();
+    let txs = get_transactions(rpc_pool_manager, &sigs, Some(thread_index)).await;
+
+    for ((pump_fun_program_signature, sig), tx) in to_fetch.into_iter().zip(sigs).zip(txs) {
+        let result = match tx {
+            Err(error) => Err(PumpFunTokenCrawlError::TransactionFetchFailed(error)),
+            Ok(tx) => pump_fun_tokens_from_transaction(
+                &tx,
+                &sig,
+                pump_fun_program_address,
+                raydium_amm_program_address,
+            ),
+        };
 
-    match tx {
-        Err(error) => {
-            return Err(PumpFunTokenCrawlError::TransactionFetchFailed(error));
-        }
-        Ok(tx) => {
-            if is_failed_transaction(&tx) {
-                return Err(PumpFunTokenCrawlError::TransactionFailed);
-            }
+        results.push((pump_fun_program_signature, result));
+    }
 
-            let message = if let EncodedTransaction::Json(tx_json) = &tx.transaction.transaction {
-                if let UiMessage::Raw(raw_message) = &tx_json.message {
-                    let account_keys = &raw_message.account_keys;
-
-                    if account_keys.contains(&pump_fun_program_address.to_string()) {
-                        Some((account_keys, raw_message))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
+    results
+}
+
+fn pump_fun_tokens_from_transaction(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+    sig: &Signature,
+    pump_fun_program_address: &Pubkey,
+    raydium_amm_program_address: &Pubkey,
+) -> Result<Tokens, PumpFunTokenCrawlError> {
+    if is_failed_transaction(&tx) {
+        return Err(PumpFunTokenCrawlError::TransactionFailed);
+    }
+
+    let message = if let EncodedTransaction::Json(tx_json) = &tx.transaction.transaction {
+        if let UiMessage::Raw(raw_message) = &tx_json.message {
+            let account_keys = &raw_message.account_keys;
+
+            if account_keys.contains(&pump_fun_program_address.to_string()) {
+                Some((account_keys, raw_message))
             } else {
                 None
-            };
-
-            if message.is_none() {
-                return Err(PumpFunTokenCrawlError::TransactionMessageParseFailed);
-            }
-            let (_account_keys, raw_message) = message.unwrap();
-
-            let instructions_with_logs = parse_transaction_with_logs(
-                &tx.transaction.meta,
-                &raw_message,
-                &pump_fun_program_address,
-                &raydium_amm_program_address,
-            );
-
-            let mut tokens: Tokens = Tokens::new();
-
-            for (instruction_index, instruction_with_logs) in
-                instructions_with_logs.iter().enumerate()
-            {
-                let token_with_trades = pump_fun_token_from_instruction_with_logs(
-                    instruction_with_logs,
-                    system_program_address,
-                    &sig,
-                    &tx,
-                    instruction_index as u64,
-                )?;
-                let token = token_with_trades.0;
-                let mint_address = token.0.clone();
-                tokens.insert(mint_address, token_with_trades);
             }
-
-            Ok(tokens)
+        } else {
+            None
         }
+    } else {
+        None
+    };
+
+    if message.is_none() {
+        return Err(PumpFunTokenCrawlError::TransactionMessageParseFailed);
     }
+    let (_account_keys, raw_message) = message.unwrap();
+
+    let instructions_with_logs = parse_transaction_with_logs(
+        &tx.transaction.meta,
+        &raw_message,
+        &pump_fun_program_address,
+        &raydium_amm_program_address,
+    );
+    let costs = transaction_costs(&raw_message, &tx.transaction.meta);
+    update_curve_params(&instructions_with_logs, tx.slot);
+
+    let mut tokens: Tokens = Tokens::new();
+
+    for instruction_with_logs in instructions_with_logs.iter() {
+        let (token, trades) =
+            pump_fun_token_from_instruction_with_logs(instruction_with_logs, sig, tx, &costs)?;
+        tokens
+            .entry(token.0)
+            .or_insert_with(|| (token, Vec::new()))
+            .1
+            .extend(trades);
+    }
+
+    number_trades(
+        tokens
+            .values_mut()
+            .flat_map(|(_, trades)| trades.iter_mut()),
+    );
+
+    Ok(tokens)
 }
 
 fn pump_fun_token_from_instruction_with_logs(
     instruction_with_logs: &InstructionWithLogs,
-    system_program_address: &Pubkey,
     pump_fun_program_signature: &Signature,
     tx: &EncodedConfirmedTransactionWithStatusMeta,
-    instruction_index: u64,
+    costs: &TransactionCosts,
 ) -> Result<(PumpFunToken, Vec<TradeRow>), PumpFunTokenCrawlError> {
-    let found_token = match instruction_with_logs.instruction {
-        Some(Instruction::PumpFun(_, PumpFunInstruction::Create((_, token)))) => token,
-        Some(Instruction::PumpFun(_, PumpFunInstruction::Buy((_, token)))) => token,
-        Some(Instruction::PumpFun(_, PumpFunInstruction::Sell((_, token)))) => token,
+    // the top-level instruction's index, the same one the trades crawl stores
+    let (instruction_index, found_token) = match instruction_with_logs.instruction {
+        Some(Instruction::PumpFun(index, PumpFunInstruction::Create((_, token)))) => (index, token),
+        Some(Instruction::PumpFun(index, PumpFunInstruction::Buy((_, token)))) => (index, token),
+        Some(Instruction::PumpFun(index, PumpFunInstruction::Sell((_, token)))) => (index, token),
+        Some(Instruction::PumpFun(index, PumpFunInstruction::Withdraw((_, token)))) => {
+            (index, token)
+        }
         _ => return Err(PumpFunTokenCrawlError::TokenNotFound),
     };
 
-    let (mint_address, _bonding_curve_address) = found_token;
-    let mint_address_string = mint_address.to_string();
-
-    let mut trades = Vec::new();
-    for cpi_log in instruction_with_logs.cpi_logs.iter() {
-        let cpi_log = match cpi_log {
-            CpiLog::PumpFun(cpi_log) => cpi_log,
-        };
+    let trades = pump_fun_trades_from_cpi_logs(
+        &instruction_with_logs.cpi_logs,
+        found_token,
+        &pump_fun_program_signature.to_string(),
+        tx.slot,
+        instruction_index,
+        OffsetDateTime::from_unix_timestamp(tx.block_time.unwrap()).unwrap(),
+        costs,
+    );
 
-        if cpi_log.mint.to_string() != mint_address_string {
-            // log is not for this mint
-            continue;
-        }
+    Ok((found_token, trades))
+}
 
-        let trade = TradeRow {
-            coin_token_address: mint_address_string.clone(),
-            price_coin_token_address: system_program_address.to_string(),
-            transaction_signature: pump_fun_program_signature.to_string(),
-            slot: tx.slot,
-            instruction_index: instruction_index as u64,
-            block_time: OffsetDateTime::from_unix_timestamp(tx.block_time.unwrap()).unwrap(),
-            coin_token_amount: cpi_log.token_amount,
-            price_coin_token_amount: cpi_log.sol_amount,
-            direction: if cpi_log.is_buy {
-                TradeDirection::Buy
-            } else {
-                TradeDirection::Sell
+pub fn pump_fun_tokens_from_instructions(
+    instructions_with_logs: &[InstructionWithLogs],
+) -> Vec<PumpFunToken> {
+    instructions_with_logs
+        .iter()
+        .filter_map(
+            |instruction_with_logs| match instruction_with_logs.instruction {
+                Some(Instruction::PumpFun(_, PumpFunInstruction::Create((_, token))))
+                | Some(Instruction::PumpFun(_, PumpFunInstruction::Buy((_, token))))
+                | Some(Instruction::PumpFun(_, PumpFunInstruction::Sell((_, token))))
+                | Some(Instruction::PumpFun(_, PumpFunInstruction::Withdraw((_, token)))) => {
+                    Some(token)
+                }
+                _ => None,
             },
-        };
-
-        trades.push(trade);
-    }
-
-    Ok((found_token, trades))
+        )
+        .collect()
 }
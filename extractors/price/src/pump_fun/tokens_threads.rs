@@ -6,14 +6,13 @@ use crate::{
     },
     dragonfly::client::dragonfly_client,
     pump_fun::{
-        errors::PumpFunTokenCrawlError, tokens::pump_fun_tokens_from_pump_fun_program_signature,
+        errors::PumpFunTokenCrawlError, tokens::pump_fun_tokens_from_pump_fun_program_signatures,
     },
     raydium::amm::get_raydium_amm_program_address,
-    rpc::{clients::get_rpc_nodes_count, pool::RpcPoolManager},
-    system::program::get_system_program_address,
+    rpc::pool::RpcPoolManager,
     termination::{terminate, terminate_on_error, TerminationFlag},
     trades::db::table::TradeRow,
-    utils::log::log_time,
+    utils::{blocking::blocking_call, channels::recv_batch, log::log_time},
 };
 use crossbeam::channel::{Receiver, Sender};
 use solana_sdk::pubkey::Pubkey;
@@ -33,13 +32,13 @@ pub fn pump_fun_tokens_threads(
     rpc_pool_manager: &RpcPoolManager,
     termination_flag: &TerminationFlag,
 ) -> Vec<thread::JoinHandle<()>> {
-    let concurrency = get_rpc_nodes_count();
+    let concurrency = rpc_pool_manager.get_rpc_nodes_count();
     let mut handles = Vec::with_capacity(concurrency);
 
     let pump_fun_program_address = get_pump_fun_program_address();
-    let system_program_address = get_system_program_address();
 
     let raydium_amm_program_address = get_raydium_amm_program_address();
+    let batch_size = rpc_pool_manager.get_batch_size();
 
     for thread_index in 0..CONCURRENCY {
         let log_tag = format!(
@@ -57,64 +56,100 @@ pub fn pump_fun_tokens_threads(
         let termination_flag = termination_flag.clone();
 
         let handle = thread::spawn(move || {
-            while let Ok(pump_fun_program_signature) = pump_fun_signatures_rx.recv() {
-                println!("{} Crawling pump fun transaction", log_tag);
-                let tokens_with_trades = pump_fun_tokens_from_pump_fun_program_signature(
-                    &rpc_pool_manager,
-                    &dragonfly_client,
-                    &pump_fun_program_signature,
-                    &pump_fun_program_address,
-                    thread_index as u64,
-                    &system_program_address,
-                    &raydium_amm_program_address,
-                );
+            blocking_call(async move {
+                while let Some(pump_fun_program_signatures) =
+                    recv_batch(&pump_fun_signatures_rx, batch_size)
+                {
+                    println!(
+                        "{} Crawling {} pump fun transactions",
+                        log_tag,
+                        pump_fun_program_signatures.len()
+                    );
+                    let results = pump_fun_tokens_from_pump_fun_program_signatures(
+                        &rpc_pool_manager,
+                        &dragonfly_client,
+                        &pump_fun_program_signatures,
+                        &pump_fun_program_address,
+                        thread_index as u64,
+                        &raydium_amm_program_address,
+                    )
+                    .await;
 
-                match tokens_with_trades {
-                    Ok(tokens_with_trades) => {
-                        for (token, trades) in tokens_with_trades.values() {
-                            terminate_on_error(&termination_flag, pump_fun_tokens_tx.send(*token));
-                            for trade in trades {
+                    for (pump_fun_program_signature, tokens_with_trades) in results {
+                        match tokens_with_trades {
+                            Ok(tokens_with_trades) => {
+                                for (token, trades) in tokens_with_trades.values() {
+                                    terminate_on_error(
+                                        &termination_flag,
+                                        pump_fun_tokens_tx.send(*token),
+                                    );
+                                    for trade in trades {
+                                        terminate_on_error(
+                                            &termination_flag,
+                                            trades_tx.send(trade.clone()),
+                                        );
+                                    }
+                                }
                                 terminate_on_error(
                                     &termination_flag,
-                                    trades_tx.send(trade.clone()),
+                                    mark_as_succeeded(
+                                        &crawl_status_tx,
+                                        &pump_fun_program_signature,
+                                    ),
                                 );
                             }
+                            Err(PumpFunTokenCrawlError::AlreadyCrawled) => {
+                                println!("{} Transaction already crawled. Skipping", log_tag);
+                                continue;
+                            }
+                            Err(PumpFunTokenCrawlError::TransactionFailed) => {
+                                println!("{} Transaction failed. Skipping", log_tag);
+                                terminate_on_error(
+                                    &termination_flag,
+                                    mark_as_succeeded(
+                                        &crawl_status_tx,
+                                        &pump_fun_program_signature,
+                                    ),
+                                );
+                                continue;
+                            }
+                            Err(PumpFunTokenCrawlError::TransactionFetchFailed(error))
+                                if !error.is_fatal() =>
+                            {
+                                println!(
+                                "{} Pump fun transaction fetch failed after retries: {}. Continuing",
+                                log_tag, error
+                            );
+                                terminate_on_error(
+                                    &termination_flag,
+                                    mark_as_failed(
+                                        &crawl_status_tx,
+                                        &pump_fun_program_signature,
+                                        &error.to_string(),
+                                    ),
+                                );
+                                continue;
+                            }
+                            Err(err @ PumpFunTokenCrawlError::TransactionMessageParseFailed)
+                            | Err(err @ PumpFunTokenCrawlError::TransactionFetchFailed(_))
+                            | Err(err @ PumpFunTokenCrawlError::CrawlStatusSend(_))
+                            | Err(err @ PumpFunTokenCrawlError::BlockTimeParseError(_))
+                            | Err(err @ PumpFunTokenCrawlError::TokenNotFound) => {
+                                println!("{} Token transaction could not be parsed", log_tag);
+                                terminate_on_error(
+                                    &termination_flag,
+                                    mark_as_failed(
+                                        &crawl_status_tx,
+                                        &pump_fun_program_signature,
+                                        &err.to_string(),
+                                    ),
+                                );
+                                terminate(&termination_flag);
+                            }
                         }
-                        terminate_on_error(
-                            &termination_flag,
-                            mark_as_succeeded(&crawl_status_tx, &pump_fun_program_signature),
-                        );
-                    }
-                    Err(PumpFunTokenCrawlError::AlreadyCrawled) => {
-                        println!("{} Transaction already crawled. Skipping", log_tag);
-                        continue;
-                    }
-                    Err(PumpFunTokenCrawlError::TransactionFailed) => {
-                        println!("{} Transaction failed. Skipping", log_tag);
-                        terminate_on_error(
-                            &termination_flag,
-                            mark_as_succeeded(&crawl_status_tx, &pump_fun_program_signature),
-                        );
-                        continue;
-                    }
-                    Err(err @ PumpFunTokenCrawlError::TransactionMessageParseFailed)
-                    | Err(err @ PumpFunTokenCrawlError::TransactionFetchFailed(_))
-                    | Err(err @ PumpFunTokenCrawlError::CrawlStatusSend(_))
-                    | Err(err @ PumpFunTokenCrawlError::BlockTimeParseError(_))
-                    | Err(err @ PumpFunTokenCrawlError::TokenNotFound) => {
-                        println!("{} Token transaction could not be parsed", log_tag);
-                        terminate_on_error(
-                            &termination_flag,
-                            mark_as_failed(
-                                &crawl_status_tx,
-                                &pump_fun_program_signature,
-                                &err.to_string(),
-                            ),
-                        );
-                        terminate(&termination_flag);
                     }
                 }
-            }
+            })
         });
 
         handles.push(handle);
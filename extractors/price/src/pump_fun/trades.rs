@@ -3,11 +3,16 @@ use time::OffsetDateTime;
 use crate::{
     cpi::cpi::CpiLog,
     instructions::instruction::InstructionIndex,
+    pump_fun::{
+        curve::{bonding_curve_progress, curve_params_at},
+        tokens::PumpFunToken,
+    },
     system::program::SYSTEM_PROGRAM_ADDRESS,
     trades::{
-        db::table::{TradeDirection, TradeRow},
+        db::table::{TradeDirection, TradeRow, TradeVenue},
         errors::TradeCrawlError,
     },
+    transactions::costs::TransactionCosts,
 };
 
 use super::instructions::PumpFunInstruction;
@@ -20,41 +25,76 @@ pub fn trade_from_pump_fun_instruction(
     cpi_logs: &Vec<CpiLog>,
     token_tx_signature: &str,
 ) -> Result<Option<TradeRow>, TradeCrawlError> {
-    let (discovered_mint_address, _bonding_curve_address) = match instruction {
+    let pump_fun_token = match instruction {
         &PumpFunInstruction::Create((_, pump_fun_token)) => pump_fun_token,
         PumpFunInstruction::Buy((_, pump_fun_token)) => *pump_fun_token,
         PumpFunInstruction::Sell((_, pump_fun_token)) => *pump_fun_token,
+        PumpFunInstruction::Withdraw(_) => return Ok(None),
     };
-    let discovered_mint_address_string = discovered_mint_address.to_string();
 
-    for cpi_log in cpi_logs.iter() {
-        let CpiLog::PumpFun(cpi_log) = cpi_log;
-        if cpi_log.mint.to_string() != discovered_mint_address_string {
-            // log is not for this mint
-            continue;
-        }
+    let block_time = OffsetDateTime::from_unix_timestamp(block_time as i64)
+        .map_err(|error| TradeCrawlError::BlockTimeParseError(error))?;
+
+    Ok(pump_fun_trades_from_cpi_logs(
+        cpi_logs,
+        pump_fun_token,
+        token_tx_signature,
+        slot,
+        *instruction_index,
+        block_time,
+        &TransactionCosts::default(),
+    )
+    .into_iter()
+    .next())
+}
 
-        let block_time = OffsetDateTime::from_unix_timestamp(block_time as i64)
-            .map_err(|error| TradeCrawlError::BlockTimeParseError(error))?;
+// One trade row for every pump fun trade event the instruction logged for its token.
+pub fn pump_fun_trades_from_cpi_logs(
+    cpi_logs: &[CpiLog],
+    (mint_address, bonding_curve_address): PumpFunToken,
+    transaction_signature: &str,
+    slot: u64,
+    instruction_index: InstructionIndex,
+    block_time: OffsetDateTime,
+    costs: &TransactionCosts,
+) -> Vec<TradeRow> {
+    let curve_params = curve_params_at(slot);
 
-        let trade = TradeRow {
-            coin_token_address: discovered_mint_address_string.clone(),
+    cpi_logs
+        .iter()
+        .filter_map(|cpi_log| match cpi_log {
+            CpiLog::PumpFunTrade(trade_event) if trade_event.mint == mint_address => {
+                Some(trade_event)
+            }
+            _ => None,
+        })
+        .map(|trade_event| TradeRow {
+            coin_token_address: mint_address.to_string(),
             price_coin_token_address: SYSTEM_PROGRAM_ADDRESS.to_string(),
-            transaction_signature: token_tx_signature.to_string(),
+            transaction_signature: transaction_signature.to_string(),
             slot,
-            instruction_index: *instruction_index,
+            instruction_index,
+            trade_ordinal: 0,
             block_time,
-            coin_token_amount: cpi_log.token_amount,
-            price_coin_token_amount: cpi_log.sol_amount,
-            direction: if cpi_log.is_buy {
+            coin_token_amount: trade_event.token_amount,
+            price_coin_token_amount: trade_event.sol_amount,
+            direction: if trade_event.is_buy {
                 TradeDirection::Buy
             } else {
                 TradeDirection::Sell
             },
-        };
-
-        return Ok(Some(trade));
-    }
-
-    Ok(None)
+            pool_address: bonding_curve_address.to_string(),
+            venue: TradeVenue::PumpFun,
+            route_id: None,
+            route_hop_index: None,
+            trader_address: trade_event.user.to_string(),
+            costs: costs.clone(),
+            virtual_sol_reserves: Some(trade_event.virtual_sol_reserves),
+            virtual_token_reserves: Some(trade_event.virtual_token_reserves),
+            bonding_curve_progress: Some(bonding_curve_progress(
+                trade_event.virtual_token_reserves,
+                &curve_params,
+            )),
+        })
+        .collect()
 }
@@ -0,0 +1,75 @@
+use super::idl::pump_swap_decoder;
+use crate::{anchor::value::IdlValue, cpi::cpi::CpiLog, utils::log::log_time};
+use solana_sdk::pubkey::Pubkey;
+
+// Later program versions append creator fees, only the leading fields are read
+// TODO: use these features, remove allow dead
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct PumpSwapBuyEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub base_amount_out: u64,
+    pub quote_amount_in: u64,
+    pub user_quote_amount_in: u64,
+    pub pool_base_token_reserves: u64,
+    pub pool_quote_token_reserves: u64,
+}
+
+impl PumpSwapBuyEvent {
+    fn from_fields(fields: &IdlValue) -> Option<Self> {
+        Some(Self {
+            pool: fields.get("pool")?.as_pubkey()?,
+            user: fields.get("user")?.as_pubkey()?,
+            base_amount_out: fields.get("base_amount_out")?.as_u64()?,
+            quote_amount_in: fields.get("quote_amount_in")?.as_u64()?,
+            user_quote_amount_in: fields.get("user_quote_amount_in")?.as_u64()?,
+            pool_base_token_reserves: fields.get("pool_base_token_reserves")?.as_u64()?,
+            pool_quote_token_reserves: fields.get("pool_quote_token_reserves")?.as_u64()?,
+        })
+    }
+}
+
+// TODO: use these features, remove allow dead
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct PumpSwapSellEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub base_amount_in: u64,
+    pub quote_amount_out: u64,
+    pub user_quote_amount_out: u64,
+    pub pool_base_token_reserves: u64,
+    pub pool_quote_token_reserves: u64,
+}
+
+impl PumpSwapSellEvent {
+    fn from_fields(fields: &IdlValue) -> Option<Self> {
+        Some(Self {
+            pool: fields.get("pool")?.as_pubkey()?,
+            user: fields.get("user")?.as_pubkey()?,
+            base_amount_in: fields.get("base_amount_in")?.as_u64()?,
+            quote_amount_out: fields.get("quote_amount_out")?.as_u64()?,
+            user_quote_amount_out: fields.get("user_quote_amount_out")?.as_u64()?,
+            pool_base_token_reserves: fields.get("pool_base_token_reserves")?.as_u64()?,
+            pool_quote_token_reserves: fields.get("pool_quote_token_reserves")?.as_u64()?,
+        })
+    }
+}
+
+// Pump swap logs nothing, `data` is the instruction data of its emit_cpi! self invocation
+pub fn pump_swap_cpi_log_from_event_instruction(data: &[u8]) -> Option<CpiLog> {
+    let event = match pump_swap_decoder().decode_cpi_event(data) {
+        Ok(event) => event?,
+        Err(error) => {
+            println!("{} Failed to decode pump swap event: {}", log_time(), error);
+            return None;
+        }
+    };
+
+    match event.name.as_str() {
+        "BuyEvent" => PumpSwapBuyEvent::from_fields(&event.fields).map(CpiLog::PumpSwapBuy),
+        "SellEvent" => PumpSwapSellEvent::from_fields(&event.fields).map(CpiLog::PumpSwapSell),
+        _ => None,
+    }
+}
@@ -0,0 +1,12 @@
+use crate::anchor::decoder::AnchorDecoder;
+use std::sync::OnceLock;
+
+const PUMP_SWAP_IDL: &str = include_str!("idl.json");
+
+static PUMP_SWAP_DECODER: OnceLock<AnchorDecoder> = OnceLock::new();
+
+pub fn pump_swap_decoder() -> &'static AnchorDecoder {
+    PUMP_SWAP_DECODER.get_or_init(|| {
+        AnchorDecoder::from_json(PUMP_SWAP_IDL).expect("Bundled pump swap IDL is invalid")
+    })
+}
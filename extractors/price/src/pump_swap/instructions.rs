@@ -0,0 +1,69 @@
+use super::idl::pump_swap_decoder;
+use crate::{
+    anchor::decoder::DecodedInstruction,
+    instructions::{instruction::Instruction, parse::ParsableInstruction},
+    utils::log::log_time,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub type PumpSwapPoolAddress = Pubkey;
+pub type BaseMint = Pubkey;
+pub type QuoteMint = Pubkey;
+
+pub type PumpSwapPoolInstruction = (
+    DecodedInstruction,
+    PumpSwapPoolAddress,
+    (BaseMint, QuoteMint),
+);
+
+#[derive(Debug)]
+pub enum PumpSwapInstruction {
+    Buy(PumpSwapPoolInstruction),
+    Sell(PumpSwapPoolInstruction),
+    CreatePool(PumpSwapPoolInstruction),
+    Deposit(PumpSwapPoolInstruction),
+    Withdraw(PumpSwapPoolInstruction),
+}
+
+pub fn parse_single_pump_swap_instruction(
+    parsable: &ParsableInstruction,
+    account_keys: &[String],
+    instruction_index: u64,
+) -> Option<Instruction> {
+    let data = bs58::decode(&parsable.data).into_vec().ok()?;
+    let accounts = parsable
+        .accounts
+        .iter()
+        .map(|account| account_keys.get(*account as usize).cloned())
+        .collect::<Option<Vec<String>>>()?;
+
+    // event self invocations carry no instruction discriminator and decode to nothing
+    let decoded = match pump_swap_decoder().decode_instruction(&data, &accounts) {
+        Ok(decoded) => decoded?,
+        Err(error) => {
+            println!(
+                "{} Failed to decode pump swap instruction: {}",
+                log_time(),
+                error
+            );
+            return None;
+        }
+    };
+
+    let pool = Pubkey::from_str(decoded.account("pool")?).ok()?;
+    let base_mint = Pubkey::from_str(decoded.account("base_mint")?).ok()?;
+    let quote_mint = Pubkey::from_str(decoded.account("quote_mint")?).ok()?;
+    let mints = (base_mint, quote_mint);
+
+    let instruction = match decoded.name.as_str() {
+        "buy" => PumpSwapInstruction::Buy((decoded, pool, mints)),
+        "sell" => PumpSwapInstruction::Sell((decoded, pool, mints)),
+        "create_pool" => PumpSwapInstruction::CreatePool((decoded, pool, mints)),
+        "deposit" => PumpSwapInstruction::Deposit((decoded, pool, mints)),
+        "withdraw" => PumpSwapInstruction::Withdraw((decoded, pool, mints)),
+        _ => return None,
+    };
+
+    Some(Instruction::PumpSwap(instruction_index, instruction))
+}
@@ -0,0 +1,6 @@
+pub mod cpi;
+pub mod idl;
+pub mod instructions;
+pub mod pools;
+pub mod program;
+pub mod trades;
@@ -0,0 +1,33 @@
+use super::instructions::PumpSwapInstruction;
+use crate::{
+    instructions::{instruction::Instruction, parse::InstructionWithLogs},
+    pools::pool::Pool,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+// The base side of a pump swap pool stands in for the coin, the quote side for the pc
+pub fn pump_swap_pools_from_instructions(
+    instructions_with_logs: &[InstructionWithLogs],
+) -> Vec<Pool> {
+    instructions_with_logs
+        .iter()
+        .filter_map(
+            |instruction_with_logs| match &instruction_with_logs.instruction {
+                Some(Instruction::PumpSwap(
+                    _,
+                    PumpSwapInstruction::CreatePool((decoded, pool, (base_mint, quote_mint))),
+                )) => Some(Pool {
+                    pool: *pool,
+                    coin_mint: *base_mint,
+                    pc_mint: *quote_mint,
+                    coin_vault: Pubkey::from_str(decoded.account("pool_base_token_account")?)
+                        .ok()?,
+                    pc_vault: Pubkey::from_str(decoded.account("pool_quote_token_account")?)
+                        .ok()?,
+                }),
+                _ => None,
+            },
+        )
+        .collect()
+}
@@ -0,0 +1,8 @@
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub const PUMP_SWAP_ADDRESS: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
+
+pub fn get_pump_swap_program_address() -> Pubkey {
+    Pubkey::from_str(PUMP_SWAP_ADDRESS).unwrap()
+}
@@ -0,0 +1,93 @@
+use super::instructions::PumpSwapInstruction;
+use crate::{
+    cpi::cpi::CpiLog,
+    instructions::instruction::InstructionIndex,
+    token::program::is_traded_mint,
+    trades::db::table::{TradeDirection, TradeRow, TradeVenue},
+    transactions::costs::TransactionCosts,
+};
+use time::OffsetDateTime;
+
+// Buys take the base mint out of the pool for the quote mint, sells put it back. Amounts are
+// the pool side of the swap, before the lp and protocol fees.
+pub fn trade_from_pump_swap_instruction(
+    instruction: &PumpSwapInstruction,
+    instruction_index: &InstructionIndex,
+    slot: u64,
+    block_time: u64,
+    cpi_logs: &Vec<CpiLog>,
+    token_tx_signature: &str,
+) -> Option<TradeRow> {
+    let (pool_address, (base_mint, quote_mint), base_amount, quote_amount, direction, user) =
+        match instruction {
+            PumpSwapInstruction::Buy((_, pool_address, mints)) => {
+                let buy_event = cpi_logs.iter().find_map(|cpi_log| match cpi_log {
+                    CpiLog::PumpSwapBuy(buy_event) if buy_event.pool == *pool_address => {
+                        Some(buy_event)
+                    }
+                    _ => None,
+                })?;
+
+                (
+                    pool_address,
+                    mints,
+                    buy_event.base_amount_out,
+                    buy_event.quote_amount_in,
+                    TradeDirection::Buy,
+                    buy_event.user,
+                )
+            }
+            PumpSwapInstruction::Sell((_, pool_address, mints)) => {
+                let sell_event = cpi_logs.iter().find_map(|cpi_log| match cpi_log {
+                    CpiLog::PumpSwapSell(sell_event) if sell_event.pool == *pool_address => {
+                        Some(sell_event)
+                    }
+                    _ => None,
+                })?;
+
+                (
+                    pool_address,
+                    mints,
+                    sell_event.base_amount_in,
+                    sell_event.quote_amount_out,
+                    TradeDirection::Sell,
+                    sell_event.user,
+                )
+            }
+            _ => return None,
+        };
+
+    // graduated pools list the token as base, pools with a quote mint as base are flipped
+    let (coin_mint, price_coin_mint, coin_token_amount, price_coin_token_amount, direction) =
+        if !is_traded_mint(&base_mint.to_string(), &quote_mint.to_string()) {
+            let direction = match direction {
+                TradeDirection::Buy => TradeDirection::Sell,
+                TradeDirection::Sell => TradeDirection::Buy,
+            };
+            (quote_mint, base_mint, quote_amount, base_amount, direction)
+        } else {
+            (base_mint, quote_mint, base_amount, quote_amount, direction)
+        };
+
+    Some(TradeRow {
+        coin_token_address: coin_mint.to_string(),
+        price_coin_token_address: price_coin_mint.to_string(),
+        transaction_signature: token_tx_signature.to_string(),
+        slot,
+        block_time: OffsetDateTime::from_unix_timestamp(block_time as i64).unwrap(),
+        instruction_index: *instruction_index,
+        trade_ordinal: 0,
+        coin_token_amount,
+        price_coin_token_amount,
+        direction,
+        pool_address: pool_address.to_string(),
+        venue: TradeVenue::PumpSwap,
+        route_id: None,
+        route_hop_index: None,
+        trader_address: user.to_string(),
+        costs: TransactionCosts::default(),
+        virtual_sol_reserves: None,
+        virtual_token_reserves: None,
+        bonding_curve_progress: None,
+    })
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 #[derive(PartialEq, Debug, Clone, Copy)]
 pub enum RaydiumInstructionDiscriminator {
+    Initialize2,
     SwapBaseIn,
     SwapBaseOut,
 }
@@ -9,6 +10,7 @@ pub enum RaydiumInstructionDiscriminator {
 pub fn build_raydium_instruction_discriminators() -> HashMap<u8, RaydiumInstructionDiscriminator> {
     let mut map = HashMap::new();
 
+    map.insert(1, RaydiumInstructionDiscriminator::Initialize2);
     map.insert(9, RaydiumInstructionDiscriminator::SwapBaseIn);
     map.insert(11, RaydiumInstructionDiscriminator::SwapBaseOut);
 
@@ -1,5 +1,14 @@
 use borsh::{BorshDeserialize, BorshSchema};
 
+#[allow(dead_code)]
+#[derive(BorshDeserialize, BorshSchema, Debug)]
+pub struct Initialize2InstructionArgs {
+    pub nonce: u8,
+    pub open_time: u64,
+    pub init_pc_amount: u64,
+    pub init_coin_amount: u64,
+}
+
 #[allow(dead_code)]
 #[derive(BorshDeserialize, BorshSchema, Debug)]
 pub struct SwapBaseInInstructionArgs {
@@ -14,6 +23,32 @@ pub struct SwapBaseOutInstructionArgs {
     pub amount_out: u64,
 }
 
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub enum Initialize2AccountsOrder {
+    TokenProgram = 0,
+    AssociatedTokenProgram = 1,
+    SystemProgram = 2,
+    Rent = 3,
+    Amm = 4,
+    AmmAuthority = 5,
+    AmmOpenOrders = 6,
+    LpMint = 7,
+    CoinMint = 8,
+    PcMint = 9,
+    PoolCoinTokenAccount = 10,
+    PoolPcTokenAccount = 11,
+    AmmTargetOrders = 12,
+    AmmConfig = 13,
+    CreatePoolFeeDestination = 14,
+    SerumProgram = 15,
+    SerumMarket = 16,
+    UserWallet = 17,
+    UserTokenCoin = 18,
+    UserTokenPc = 19,
+    UserLpTokenAccount = 20,
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 pub enum SwapBaseInAccountsOrder {
@@ -1,36 +1,30 @@
 use super::{
     discriminators::RaydiumInstructionDiscriminator,
-    idl::{SwapBaseInInstructionArgs, SwapBaseOutInstructionArgs},
+    idl::{Initialize2InstructionArgs, SwapBaseInInstructionArgs, SwapBaseOutInstructionArgs},
 };
 use crate::{
-    instructions::{instruction::Instruction, parse::ParsableInstruction},
+    instructions::{
+        instruction::Instruction,
+        parse::{invoked_instructions, ParsableInstruction},
+    },
+    pools::{
+        pool::{Pool, PoolSwap},
+        transfers::{parse_inner_transfer_instruction, swap_flow_from_transfers},
+    },
     raydium::{
         discriminators::build_raydium_instruction_discriminators,
-        idl::{SwapBaseInAccountsOrder, SwapBaseOutAccountsOrder},
+        idl::{Initialize2AccountsOrder, SwapBaseInAccountsOrder},
     },
-    system::idl::{SystemTransferAccountsOrder, SystemTransferInstructionData},
-    token::idl::{TokenTransferAccountsOrder, TokenTransferInstructionData},
-    utils::log::log_time,
 };
 use borsh::BorshDeserialize;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 
-pub type PoolCoinTokenAddress = Pubkey;
-pub type PoolPcTokenAddress = Pubkey;
-pub type RaydiumToken = (PoolCoinTokenAddress, PoolPcTokenAddress);
-pub type RaydiumTokenAmounts = (u64, u64);
-
 #[derive(Debug)]
 pub enum RaydiumInstruction {
-    SwapBaseIn((SwapBaseInInstructionArgs, RaydiumToken, RaydiumTokenAmounts)),
-    SwapBaseOut(
-        (
-            SwapBaseOutInstructionArgs,
-            RaydiumToken,
-            RaydiumTokenAmounts,
-        ),
-    ),
+    Initialize2((Initialize2InstructionArgs, Pool)),
+    SwapBaseIn((SwapBaseInInstructionArgs, PoolSwap)),
+    SwapBaseOut((SwapBaseOutInstructionArgs, PoolSwap)),
 }
 
 pub fn parse_single_raydium_instruction(
@@ -51,87 +45,43 @@ pub fn parse_single_raydium_instruction(
 
     let instruction = match found_discriminator {
         None => None,
-        Some(RaydiumInstructionDiscriminator::SwapBaseIn) => {
-            let args = SwapBaseInInstructionArgs::deserialize(&mut &data[1..]).unwrap();
-
-            let inner_instructions = if let Some(inner_instructions) = &parsable.inner_instructions
-            {
-                inner_instructions.clone()
-            } else {
-                let next_inst =
-                    inner_instructions.get(parsable.inner_instruction_index.unwrap() as usize + 1);
-                let next_next_inst =
-                    inner_instructions.get(parsable.inner_instruction_index.unwrap() as usize + 2);
-
-                if next_inst.is_none() || next_next_inst.is_none() {
-                    vec![]
-                } else {
-                    vec![next_inst.unwrap().clone(), next_next_inst.unwrap().clone()]
-                }
-            };
+        Some(RaydiumInstructionDiscriminator::Initialize2) => {
+            let args = Initialize2InstructionArgs::deserialize(&mut &data[1..]).ok()?;
 
-            let transfers = inner_instructions
-                .iter()
-                .filter_map(|ix| parse_inner_transfer_instruction(ix, account_keys))
-                .collect::<Vec<_>>();
-
-            let coin_token = parsable
-                .accounts
-                .get(SwapBaseInAccountsOrder::PoolCoinTokenAccount as usize)?;
-            let coin_token_str = account_keys.get(*coin_token as usize)?;
-            let coin_token_address = Pubkey::from_str(coin_token_str).ok()?;
+            let account = |order: Initialize2AccountsOrder| -> Option<Pubkey> {
+                let account_index = parsable.accounts.get(order as usize)?;
+                Pubkey::from_str(account_keys.get(*account_index as usize)?).ok()
+            };
 
-            let pc_token = parsable
-                .accounts
-                .get(SwapBaseInAccountsOrder::PoolPcTokenAccount as usize)?;
-            let pc_token_str = account_keys.get(*pc_token as usize)?;
-            let pc_token_address = Pubkey::from_str(pc_token_str).ok()?;
+            let pool = Pool {
+                pool: account(Initialize2AccountsOrder::Amm)?,
+                coin_mint: account(Initialize2AccountsOrder::CoinMint)?,
+                pc_mint: account(Initialize2AccountsOrder::PcMint)?,
+                coin_vault: account(Initialize2AccountsOrder::PoolCoinTokenAccount)?,
+                pc_vault: account(Initialize2AccountsOrder::PoolPcTokenAccount)?,
+            };
 
-            let (coin_token_amount, pc_token_amount) =
-                token_amounts_from_transfers(transfers, coin_token_str, pc_token_str)?;
+            Some(Instruction::Raydium(
+                instruction_index,
+                RaydiumInstruction::Initialize2((args, pool)),
+            ))
+        }
+        Some(RaydiumInstructionDiscriminator::SwapBaseIn) => {
+            let args = SwapBaseInInstructionArgs::deserialize(&mut &data[1..]).ok()?;
+            let swap = raydium_swap_from_instruction(parsable, account_keys, inner_instructions)?;
 
             Some(Instruction::Raydium(
                 instruction_index,
-                RaydiumInstruction::SwapBaseIn((
-                    args,
-                    (coin_token_address, pc_token_address),
-                    (coin_token_amount, pc_token_amount),
-                )),
+                RaydiumInstruction::SwapBaseIn((args, swap)),
             ))
         }
         Some(RaydiumInstructionDiscriminator::SwapBaseOut) => {
-            let args = SwapBaseOutInstructionArgs::deserialize(&mut &data[1..]).unwrap();
-
-            let inner_instructions = parsable.inner_instructions.clone().unwrap();
-            let transfers = inner_instructions
-                .iter()
-                .filter_map(|ix| parse_inner_transfer_instruction(ix, account_keys))
-                .collect::<Vec<_>>();
-
-            let coin_token = parsable
-                .accounts
-                .get(SwapBaseOutAccountsOrder::PoolCoinTokenAccount as usize)
-                .unwrap();
-            let coin_token_str = account_keys.get(*coin_token as usize).unwrap();
-            let coin_token_address = Pubkey::from_str(coin_token_str).unwrap();
-
-            let pc_token = parsable
-                .accounts
-                .get(SwapBaseOutAccountsOrder::PoolPcTokenAccount as usize)
-                .unwrap();
-            let pc_token_str = account_keys.get(*pc_token as usize).unwrap();
-            let pc_token_address = Pubkey::from_str(pc_token_str).unwrap();
-
-            let (coin_token_amount, pc_token_amount) =
-                token_amounts_from_transfers(transfers, coin_token_str, pc_token_str)?;
+            let args = SwapBaseOutInstructionArgs::deserialize(&mut &data[1..]).ok()?;
+            let swap = raydium_swap_from_instruction(parsable, account_keys, inner_instructions)?;
 
             Some(Instruction::Raydium(
                 instruction_index,
-                RaydiumInstruction::SwapBaseOut((
-                    args,
-                    (coin_token_address, pc_token_address),
-                    (coin_token_amount, pc_token_amount),
-                )),
+                RaydiumInstruction::SwapBaseOut((args, swap)),
             ))
         }
     };
@@ -139,82 +89,47 @@ pub fn parse_single_raydium_instruction(
     return instruction;
 }
 
-pub type TransferSource = String;
-pub type TransferDestination = String;
-pub type TransferAmount = u64;
-
-fn parse_inner_transfer_instruction(
-    ix: &ParsableInstruction,
-    account_keys: &[String],
-) -> Option<(TransferAmount, TransferSource, TransferDestination)> {
-    let ix_data = bs58::decode(&ix.data).into_vec().ok()?;
-
-    if ix.program_id_index == 0 {
-        if ix_data[0] != 2 {
-            None
-        } else {
-            let amount = SystemTransferInstructionData::deserialize(&mut &ix_data[1..]).ok()?;
-
-            let source_index = ix
-                .accounts
-                .get(SystemTransferAccountsOrder::Source as usize)?;
-
-            let destination_index = ix
-                .accounts
-                .get(SystemTransferAccountsOrder::Destination as usize)?;
-
-            let source = account_keys.get(*source_index as usize)?;
-            let destination = account_keys.get(*destination_index as usize)?;
-
-            Some((amount.lamports, source.to_string(), destination.to_string()))
-        }
+// 17 account swaps leave out the target orders, shifting the vaults down by one
+fn swap_account_index(parsable: &ParsableInstruction, order: SwapBaseInAccountsOrder) -> usize {
+    let index = order as usize;
+    if parsable.accounts.len() == 17 && index > SwapBaseInAccountsOrder::AmmOpenOrders as usize {
+        index - 1
     } else {
-        if ix_data[0] != 3 {
-            None
-        } else {
-            let amount = TokenTransferInstructionData::deserialize(&mut &ix_data[1..]).ok()?;
-
-            let source_index = ix
-                .accounts
-                .get(TokenTransferAccountsOrder::Source as usize)?;
-
-            let destination_index = ix
-                .accounts
-                .get(TokenTransferAccountsOrder::Destination as usize)?;
-
-            let source = account_keys.get(*source_index as usize)?;
-            let destination = account_keys.get(*destination_index as usize)?;
-
-            Some((amount.amount, source.to_string(), destination.to_string()))
-        }
+        index
     }
 }
 
-fn token_amounts_from_transfers(
-    transfers: Vec<(u64, String, String)>,
-    coin_token_str: &str,
-    pc_token_str: &str,
-) -> Option<(u64, u64)> {
-    if transfers.len() != 2 {
-        return None;
-    }
-
-    let (first_amount, _, first_dest) = transfers.first().unwrap();
-    let (second_amount, second_src, _) = transfers.last().unwrap();
+// swap base in and out share their accounts
+fn raydium_swap_from_instruction(
+    parsable: &ParsableInstruction,
+    account_keys: &[String],
+    inner_instructions: &Vec<ParsableInstruction>,
+) -> Option<PoolSwap> {
+    let account = |order: SwapBaseInAccountsOrder| -> Option<&String> {
+        let account_index = parsable.accounts.get(swap_account_index(parsable, order))?;
+        account_keys.get(*account_index as usize)
+    };
 
-    match (first_dest.as_str(), second_src.as_str()) {
-        (dest, src) if dest == coin_token_str && src == pc_token_str => {
-            Some((*first_amount, *second_amount))
-        }
-        (dest, src) if dest == pc_token_str && src == coin_token_str => {
-            Some((*second_amount, *first_amount))
-        }
-        _ => {
-            println!(
-                "{} Transfer addresses don't match expected token pairs",
-                log_time()
-            );
-            None
-        }
-    }
+    let pool_str = account(SwapBaseInAccountsOrder::Amm)?;
+    let coin_vault_str = account(SwapBaseInAccountsOrder::PoolCoinTokenAccount)?;
+    let pc_vault_str = account(SwapBaseInAccountsOrder::PoolPcTokenAccount)?;
+    let user_str = account(SwapBaseInAccountsOrder::UserSourceOwner)?;
+
+    let transfers = invoked_instructions(parsable, inner_instructions)
+        .iter()
+        .filter_map(|ix| parse_inner_transfer_instruction(ix, account_keys))
+        .collect::<Vec<_>>();
+
+    let (coin_amount, pc_amount, direction) =
+        swap_flow_from_transfers(&transfers, coin_vault_str, pc_vault_str)?;
+
+    Some(PoolSwap {
+        pool: Pubkey::from_str(pool_str).ok()?,
+        user: Pubkey::from_str(user_str).ok()?,
+        coin_vault: Pubkey::from_str(coin_vault_str).ok()?,
+        pc_vault: Pubkey::from_str(pc_vault_str).ok()?,
+        coin_amount,
+        pc_amount,
+        direction,
+    })
 }
@@ -2,5 +2,6 @@ pub mod amm;
 pub mod discriminators;
 pub mod idl;
 pub mod instructions;
-pub mod table;
+pub mod pools;
+pub mod state;
 pub mod trades;
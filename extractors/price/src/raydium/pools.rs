@@ -0,0 +1,22 @@
+use super::instructions::RaydiumInstruction;
+use crate::{
+    instructions::{instruction::Instruction, parse::InstructionWithLogs},
+    pools::pool::Pool,
+};
+
+pub fn raydium_pools_from_instructions(
+    instructions_with_logs: &[InstructionWithLogs],
+) -> Vec<Pool> {
+    instructions_with_logs
+        .iter()
+        .filter_map(
+            |instruction_with_logs| match &instruction_with_logs.instruction {
+                Some(Instruction::Raydium(
+                    _,
+                    RaydiumInstruction::Initialize2((_, raydium_pool)),
+                )) => Some(*raydium_pool),
+                _ => None,
+            },
+        )
+        .collect()
+}
@@ -0,0 +1,28 @@
+use crate::pools::pool::{Pool, PoolAddress};
+use solana_sdk::pubkey::Pubkey;
+
+// AMM v4 `AmmInfo`: 16 u64 params, 8 u64 fees and the 144 byte swap totals come before
+// the vault and mint accounts
+pub const AMM_INFO_SIZE: usize = 752;
+const COIN_VAULT_OFFSET: usize = 336;
+const PC_VAULT_OFFSET: usize = 368;
+const COIN_MINT_OFFSET: usize = 400;
+const PC_MINT_OFFSET: usize = 432;
+
+fn pubkey_at(data: &[u8], offset: usize) -> Option<Pubkey> {
+    Pubkey::try_from(data.get(offset..offset + 32)?).ok()
+}
+
+pub fn raydium_pool_from_state(pool: &PoolAddress, data: &[u8]) -> Option<Pool> {
+    if data.len() != AMM_INFO_SIZE {
+        return None;
+    }
+
+    Some(Pool {
+        pool: *pool,
+        coin_mint: pubkey_at(data, COIN_MINT_OFFSET)?,
+        pc_mint: pubkey_at(data, PC_MINT_OFFSET)?,
+        coin_vault: pubkey_at(data, COIN_VAULT_OFFSET)?,
+        pc_vault: pubkey_at(data, PC_VAULT_OFFSET)?,
+    })
+}
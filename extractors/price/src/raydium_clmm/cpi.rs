@@ -0,0 +1,49 @@
+use super::idl::raydium_clmm_decoder;
+use crate::{anchor::value::IdlValue, cpi::cpi::CpiLog, utils::log::log_time};
+use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Debug, Clone)]
+pub struct RaydiumClmmSwapEvent {
+    pub pool_state: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+    // token 0 went into the pool
+    pub zero_for_one: bool,
+}
+
+impl RaydiumClmmSwapEvent {
+    fn from_fields(fields: &IdlValue) -> Option<Self> {
+        Some(Self {
+            pool_state: fields.get("pool_state")?.as_pubkey()?,
+            amount_0: fields.get("amount0")?.as_u64()?,
+            amount_1: fields.get("amount1")?.as_u64()?,
+            zero_for_one: fields.get("zero_for_one")?.as_bool()?,
+        })
+    }
+}
+
+// `data` is what follows `Program data:` in the logs of a raydium clmm invocation
+pub fn raydium_clmm_cpi_log_from_event_data(data: &str) -> Option<CpiLog> {
+    let base64_data = data.split_whitespace().last()?;
+    let decoded = base64.decode(base64_data).ok()?;
+
+    let event = match raydium_clmm_decoder().decode_event(&decoded) {
+        Ok(event) => event?,
+        Err(error) => {
+            println!(
+                "{} Failed to decode raydium clmm event: {}",
+                log_time(),
+                error
+            );
+            return None;
+        }
+    };
+
+    match event.name.as_str() {
+        "SwapEvent" => {
+            RaydiumClmmSwapEvent::from_fields(&event.fields).map(CpiLog::RaydiumClmmSwap)
+        }
+        _ => None,
+    }
+}
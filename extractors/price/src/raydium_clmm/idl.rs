@@ -0,0 +1,12 @@
+use crate::anchor::decoder::AnchorDecoder;
+use std::sync::OnceLock;
+
+const RAYDIUM_CLMM_IDL: &str = include_str!("idl.json");
+
+static RAYDIUM_CLMM_DECODER: OnceLock<AnchorDecoder> = OnceLock::new();
+
+pub fn raydium_clmm_decoder() -> &'static AnchorDecoder {
+    RAYDIUM_CLMM_DECODER.get_or_init(|| {
+        AnchorDecoder::from_json(RAYDIUM_CLMM_IDL).expect("Bundled raydium clmm IDL is invalid")
+    })
+}
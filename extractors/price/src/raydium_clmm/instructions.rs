@@ -0,0 +1,64 @@
+use super::idl::raydium_clmm_decoder;
+use crate::{
+    anchor::decoder::DecodedInstruction,
+    instructions::{instruction::Instruction, parse::ParsableInstruction},
+    utils::log::log_time,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub type RaydiumClmmPoolAddress = Pubkey;
+pub type InputMint = Pubkey;
+pub type OutputMint = Pubkey;
+
+#[derive(Debug)]
+pub enum RaydiumClmmInstruction {
+    // the first swap only passes the vaults, its mints come from the pool state
+    Swap((DecodedInstruction, RaydiumClmmPoolAddress)),
+    SwapV2(
+        (
+            DecodedInstruction,
+            RaydiumClmmPoolAddress,
+            (InputMint, OutputMint),
+        ),
+    ),
+}
+
+pub fn parse_single_raydium_clmm_instruction(
+    parsable: &ParsableInstruction,
+    account_keys: &[String],
+    instruction_index: u64,
+) -> Option<Instruction> {
+    let data = bs58::decode(&parsable.data).into_vec().ok()?;
+    let accounts = parsable
+        .accounts
+        .iter()
+        .map(|account| account_keys.get(*account as usize).cloned())
+        .collect::<Option<Vec<String>>>()?;
+
+    let decoded = match raydium_clmm_decoder().decode_instruction(&data, &accounts) {
+        Ok(decoded) => decoded?,
+        Err(error) => {
+            println!(
+                "{} Failed to decode raydium clmm instruction: {}",
+                log_time(),
+                error
+            );
+            return None;
+        }
+    };
+
+    let pool = Pubkey::from_str(decoded.account("pool_state")?).ok()?;
+
+    let instruction = match decoded.name.as_str() {
+        "swap" => RaydiumClmmInstruction::Swap((decoded, pool)),
+        "swap_v2" => {
+            let input_mint = Pubkey::from_str(decoded.account("input_vault_mint")?).ok()?;
+            let output_mint = Pubkey::from_str(decoded.account("output_vault_mint")?).ok()?;
+            RaydiumClmmInstruction::SwapV2((decoded, pool, (input_mint, output_mint)))
+        }
+        _ => return None,
+    };
+
+    Some(Instruction::RaydiumClmm(instruction_index, instruction))
+}
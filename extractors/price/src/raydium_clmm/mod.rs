@@ -0,0 +1,6 @@
+pub mod cpi;
+pub mod idl;
+pub mod instructions;
+pub mod program;
+pub mod state;
+pub mod trades;
@@ -0,0 +1,8 @@
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub const RAYDIUM_CLMM_ADDRESS: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK";
+
+pub fn get_raydium_clmm_program_address() -> Pubkey {
+    Pubkey::from_str(RAYDIUM_CLMM_ADDRESS).unwrap()
+}
@@ -0,0 +1,24 @@
+use crate::pools::pool::{Pool, PoolAddress};
+use solana_sdk::pubkey::Pubkey;
+
+// Clmm `PoolState`: the anchor discriminator, a bump, the amm config and owner come before
+// the mints and vaults
+const TOKEN_MINT_0_OFFSET: usize = 73;
+const TOKEN_MINT_1_OFFSET: usize = 105;
+const TOKEN_VAULT_0_OFFSET: usize = 137;
+const TOKEN_VAULT_1_OFFSET: usize = 169;
+
+fn pubkey_at(data: &[u8], offset: usize) -> Option<Pubkey> {
+    Pubkey::try_from(data.get(offset..offset + 32)?).ok()
+}
+
+// Token 0 and 1 take the place of the amm's coin and pc
+pub fn raydium_clmm_pool_from_state(pool: &PoolAddress, data: &[u8]) -> Option<Pool> {
+    Some(Pool {
+        pool: *pool,
+        coin_mint: pubkey_at(data, TOKEN_MINT_0_OFFSET)?,
+        pc_mint: pubkey_at(data, TOKEN_MINT_1_OFFSET)?,
+        coin_vault: pubkey_at(data, TOKEN_VAULT_0_OFFSET)?,
+        pc_vault: pubkey_at(data, TOKEN_VAULT_1_OFFSET)?,
+    })
+}
@@ -0,0 +1,82 @@
+use super::instructions::RaydiumClmmInstruction;
+use crate::{
+    cpi::cpi::CpiLog,
+    instructions::instruction::InstructionIndex,
+    pools::trades::trade_from_pool_swap,
+    trades::db::table::{TradeDirection, TradeRow, TradeVenue},
+    transactions::costs::TransactionCosts,
+};
+use time::OffsetDateTime;
+
+// Swaps without mints are left with empty coin and price coin addresses, the same as amm
+// swaps, and are priced once the pool state is resolved
+pub fn trade_from_raydium_clmm_instruction(
+    instruction: &RaydiumClmmInstruction,
+    instruction_index: &InstructionIndex,
+    slot: u64,
+    block_time: u64,
+    cpi_logs: &Vec<CpiLog>,
+    token_tx_signature: &str,
+) -> Option<TradeRow> {
+    let (decoded, pool_address) = match instruction {
+        RaydiumClmmInstruction::Swap((decoded, pool_address)) => (decoded, pool_address),
+        RaydiumClmmInstruction::SwapV2((decoded, pool_address, _)) => (decoded, pool_address),
+    };
+    let trader_address = decoded.account("payer")?;
+
+    let swap_event = cpi_logs.iter().find_map(|cpi_log| match cpi_log {
+        CpiLog::RaydiumClmmSwap(swap_event) if swap_event.pool_state == *pool_address => {
+            Some(swap_event)
+        }
+        _ => None,
+    })?;
+
+    let ((input_amount, output_amount), direction) = if swap_event.zero_for_one {
+        (
+            (swap_event.amount_0, swap_event.amount_1),
+            TradeDirection::Sell,
+        )
+    } else {
+        (
+            (swap_event.amount_1, swap_event.amount_0),
+            TradeDirection::Buy,
+        )
+    };
+
+    match instruction {
+        RaydiumClmmInstruction::Swap(_) => Some(TradeRow {
+            coin_token_address: String::new(),
+            price_coin_token_address: String::new(),
+            transaction_signature: token_tx_signature.to_string(),
+            slot,
+            block_time: OffsetDateTime::from_unix_timestamp(block_time as i64).unwrap(),
+            instruction_index: *instruction_index,
+            trade_ordinal: 0,
+            coin_token_amount: swap_event.amount_0,
+            price_coin_token_amount: swap_event.amount_1,
+            direction,
+            pool_address: pool_address.to_string(),
+            venue: TradeVenue::RaydiumClmm,
+            route_id: None,
+            route_hop_index: None,
+            trader_address: trader_address.to_string(),
+            costs: TransactionCosts::default(),
+            virtual_sol_reserves: None,
+            virtual_token_reserves: None,
+            bonding_curve_progress: None,
+        }),
+        RaydiumClmmInstruction::SwapV2((_, _, (input_mint, output_mint))) => {
+            Some(trade_from_pool_swap(
+                pool_address,
+                TradeVenue::RaydiumClmm,
+                trader_address,
+                (input_mint, input_amount),
+                (output_mint, output_amount),
+                instruction_index,
+                slot,
+                block_time,
+                token_tx_signature,
+            ))
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use super::idl::raydium_cpmm_decoder;
+use crate::{anchor::value::IdlValue, cpi::cpi::CpiLog, utils::log::log_time};
+use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
+use solana_sdk::pubkey::Pubkey;
+
+// Later program versions append the mints and fees, only the leading fields are read
+#[derive(Debug, Clone)]
+pub struct RaydiumCpmmSwapEvent {
+    pub pool_id: Pubkey,
+    pub input_amount: u64,
+    pub output_amount: u64,
+}
+
+impl RaydiumCpmmSwapEvent {
+    fn from_fields(fields: &IdlValue) -> Option<Self> {
+        Some(Self {
+            pool_id: fields.get("pool_id")?.as_pubkey()?,
+            input_amount: fields.get("input_amount")?.as_u64()?,
+            output_amount: fields.get("output_amount")?.as_u64()?,
+        })
+    }
+}
+
+// `data` is what follows `Program data:` in the logs of a raydium cpmm invocation
+pub fn raydium_cpmm_cpi_log_from_event_data(data: &str) -> Option<CpiLog> {
+    let base64_data = data.split_whitespace().last()?;
+    let decoded = base64.decode(base64_data).ok()?;
+
+    let event = match raydium_cpmm_decoder().decode_event(&decoded) {
+        Ok(event) => event?,
+        Err(error) => {
+            println!(
+                "{} Failed to decode raydium cpmm event: {}",
+                log_time(),
+                error
+            );
+            return None;
+        }
+    };
+
+    match event.name.as_str() {
+        "SwapEvent" => {
+            RaydiumCpmmSwapEvent::from_fields(&event.fields).map(CpiLog::RaydiumCpmmSwap)
+        }
+        _ => None,
+    }
+}
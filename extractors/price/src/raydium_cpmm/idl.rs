@@ -0,0 +1,12 @@
+use crate::anchor::decoder::AnchorDecoder;
+use std::sync::OnceLock;
+
+const RAYDIUM_CPMM_IDL: &str = include_str!("idl.json");
+
+static RAYDIUM_CPMM_DECODER: OnceLock<AnchorDecoder> = OnceLock::new();
+
+pub fn raydium_cpmm_decoder() -> &'static AnchorDecoder {
+    RAYDIUM_CPMM_DECODER.get_or_init(|| {
+        AnchorDecoder::from_json(RAYDIUM_CPMM_IDL).expect("Bundled raydium cpmm IDL is invalid")
+    })
+}
@@ -0,0 +1,68 @@
+use super::idl::raydium_cpmm_decoder;
+use crate::{
+    anchor::decoder::DecodedInstruction,
+    instructions::{instruction::Instruction, parse::ParsableInstruction},
+    utils::log::log_time,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub type RaydiumCpmmPoolAddress = Pubkey;
+pub type InputMint = Pubkey;
+pub type OutputMint = Pubkey;
+
+#[derive(Debug)]
+pub enum RaydiumCpmmInstruction {
+    SwapBaseInput(
+        (
+            DecodedInstruction,
+            RaydiumCpmmPoolAddress,
+            (InputMint, OutputMint),
+        ),
+    ),
+    SwapBaseOutput(
+        (
+            DecodedInstruction,
+            RaydiumCpmmPoolAddress,
+            (InputMint, OutputMint),
+        ),
+    ),
+}
+
+pub fn parse_single_raydium_cpmm_instruction(
+    parsable: &ParsableInstruction,
+    account_keys: &[String],
+    instruction_index: u64,
+) -> Option<Instruction> {
+    let data = bs58::decode(&parsable.data).into_vec().ok()?;
+    let accounts = parsable
+        .accounts
+        .iter()
+        .map(|account| account_keys.get(*account as usize).cloned())
+        .collect::<Option<Vec<String>>>()?;
+
+    let decoded = match raydium_cpmm_decoder().decode_instruction(&data, &accounts) {
+        Ok(decoded) => decoded?,
+        Err(error) => {
+            println!(
+                "{} Failed to decode raydium cpmm instruction: {}",
+                log_time(),
+                error
+            );
+            return None;
+        }
+    };
+
+    let pool = Pubkey::from_str(decoded.account("pool_state")?).ok()?;
+    let input_mint = Pubkey::from_str(decoded.account("input_token_mint")?).ok()?;
+    let output_mint = Pubkey::from_str(decoded.account("output_token_mint")?).ok()?;
+    let mints = (input_mint, output_mint);
+
+    let instruction = match decoded.name.as_str() {
+        "swap_base_input" => RaydiumCpmmInstruction::SwapBaseInput((decoded, pool, mints)),
+        "swap_base_output" => RaydiumCpmmInstruction::SwapBaseOutput((decoded, pool, mints)),
+        _ => return None,
+    };
+
+    Some(Instruction::RaydiumCpmm(instruction_index, instruction))
+}
@@ -0,0 +1,8 @@
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub const RAYDIUM_CPMM_ADDRESS: &str = "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP8C";
+
+pub fn get_raydium_cpmm_program_address() -> Pubkey {
+    Pubkey::from_str(RAYDIUM_CPMM_ADDRESS).unwrap()
+}
@@ -0,0 +1,45 @@
+use super::instructions::RaydiumCpmmInstruction;
+use crate::{
+    cpi::cpi::CpiLog,
+    instructions::instruction::InstructionIndex,
+    pools::trades::trade_from_pool_swap,
+    trades::db::table::{TradeRow, TradeVenue},
+};
+
+pub fn trade_from_raydium_cpmm_instruction(
+    instruction: &RaydiumCpmmInstruction,
+    instruction_index: &InstructionIndex,
+    slot: u64,
+    block_time: u64,
+    cpi_logs: &Vec<CpiLog>,
+    token_tx_signature: &str,
+) -> Option<TradeRow> {
+    let (decoded, pool_address, (input_mint, output_mint)) = match instruction {
+        RaydiumCpmmInstruction::SwapBaseInput((decoded, pool_address, mints)) => {
+            (decoded, pool_address, mints)
+        }
+        RaydiumCpmmInstruction::SwapBaseOutput((decoded, pool_address, mints)) => {
+            (decoded, pool_address, mints)
+        }
+    };
+
+    // the event carries what actually moved, the args only bound it
+    let swap_event = cpi_logs.iter().find_map(|cpi_log| match cpi_log {
+        CpiLog::RaydiumCpmmSwap(swap_event) if swap_event.pool_id == *pool_address => {
+            Some(swap_event)
+        }
+        _ => None,
+    })?;
+
+    Some(trade_from_pool_swap(
+        pool_address,
+        TradeVenue::RaydiumCpmm,
+        decoded.account("payer")?,
+        (input_mint, swap_event.input_amount),
+        (output_mint, swap_event.output_amount),
+        instruction_index,
+        slot,
+        block_time,
+        token_tx_signature,
+    ))
+}
@@ -0,0 +1,220 @@
+use super::{errors::RpcError, pool::RpcPoolManager};
+use crate::utils::log::log_time;
+use futures_util::{stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use solana_client::{
+    client_error::{
+        reqwest::{self, header::CONTENT_TYPE, StatusCode},
+        ClientError, ClientErrorKind,
+    },
+    rpc_request::{RpcError as RpcRequestError, RpcRequest, RpcResponseErrorData},
+};
+use std::{collections::HashMap, sync::OnceLock};
+
+pub const BATCH_REJECTED_PREFIX: &str = "batch request rejected";
+
+static HTTP_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+fn http_client() -> &'static reqwest::Client {
+    HTTP_CLIENT.get_or_init(reqwest::Client::new)
+}
+
+fn batch_rejected(reason: String) -> ClientError {
+    ClientError::from(ClientErrorKind::Custom(format!(
+        "{}: {}",
+        BATCH_REJECTED_PREFIX, reason
+    )))
+}
+
+fn response_error(error: &Value) -> ClientError {
+    let code = error["code"].as_i64().unwrap_or_default();
+    let message = error["message"].as_str().unwrap_or_default().to_string();
+
+    ClientError::from(RpcRequestError::RpcResponseError {
+        code,
+        message,
+        data: RpcResponseErrorData::Empty,
+    })
+}
+
+// Sends every set of params as one JSON-RPC array. The outer error fails the whole
+// batch, the inner results line up with `params`.
+pub async fn send_batch(
+    url: &str,
+    method: RpcRequest,
+    params: &[Value],
+) -> Result<Vec<Result<Value, ClientError>>, ClientError> {
+    let body = params
+        .iter()
+        .enumerate()
+        .map(|(id, params)| method.build_request_json(id as u64, params.clone()))
+        .collect::<Vec<_>>();
+
+    let response = http_client()
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Value::Array(body).to_string())
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS {
+        return Err(batch_rejected(format!("http status {}", status)));
+    }
+
+    let response = response.error_for_status()?;
+    let body: Value = serde_json::from_str(&response.text().await?)?;
+
+    // providers without batch support answer with a single error object
+    let items = match body {
+        Value::Array(items) => items,
+        other => return Err(batch_rejected(other.to_string())),
+    };
+
+    let mut results_by_id = items
+        .into_iter()
+        .filter_map(|mut item| {
+            let id = item["id"].as_u64()?;
+            let error = item
+                .get("error")
+                .filter(|error| !error.is_null())
+                .map(response_error);
+            let result = match error {
+                Some(error) => Err(error),
+                None => Ok(item["result"].take()),
+            };
+
+            Some((id as usize, result))
+        })
+        .collect::<HashMap<_, _>>();
+
+    Ok((0..params.len())
+        .map(|id| {
+            results_by_id.remove(&id).unwrap_or_else(|| {
+                Err(ClientError::from(RpcRequestError::RpcRequestError(
+                    format!("no response for batch request {}", id),
+                )))
+            })
+        })
+        .collect())
+}
+
+async fn execute_single<T: DeserializeOwned>(
+    rpc_pool_manager: &RpcPoolManager,
+    method: RpcRequest,
+    params: &Value,
+    pin: Option<u64>,
+) -> Result<T, RpcError> {
+    rpc_pool_manager
+        .execute(
+            method,
+            |client| {
+                let params = params.clone();
+                async move { client.send::<T>(method, params).await }
+            },
+            pin,
+        )
+        .await
+}
+
+// Runs the same method for every set of params, batched up to the pool's batch size.
+// Failed items that are worth retrying go back through the pool one at a time, and
+// everything falls back to single requests when no endpoint accepts batches. Up to the
+// pool's max in flight batches or single requests run at once, results keep the order
+// of `params`.
+pub async fn execute_batched<T: DeserializeOwned>(
+    rpc_pool_manager: &RpcPoolManager,
+    method: RpcRequest,
+    params: Vec<Value>,
+    pin: Option<u64>,
+) -> Vec<Result<T, RpcError>> {
+    let max_in_flight = rpc_pool_manager.get_max_in_flight();
+
+    stream::iter(
+        params
+            .chunks(rpc_pool_manager.get_batch_size())
+            .map(|chunk| execute_chunk(rpc_pool_manager, method, chunk, pin)),
+    )
+    .buffered(max_in_flight)
+    .flat_map(stream::iter)
+    .collect()
+    .await
+}
+
+async fn execute_chunk<T: DeserializeOwned>(
+    rpc_pool_manager: &RpcPoolManager,
+    method: RpcRequest,
+    chunk: &[Value],
+    pin: Option<u64>,
+) -> Vec<Result<T, RpcError>> {
+    let max_in_flight = rpc_pool_manager.get_max_in_flight();
+
+    let batch = if chunk.len() > 1 && rpc_pool_manager.has_batch_endpoint() {
+        rpc_pool_manager
+            .execute_batch(
+                method,
+                chunk.len(),
+                |client| async move { send_batch(&client.url(), method, chunk).await },
+                pin,
+            )
+            .await
+    } else {
+        Err(RpcError::NoBatchEndpoint)
+    };
+
+    let batch_results = match batch {
+        Ok((batch_results, _endpoint)) => batch_results,
+        Err(
+            RpcError::NoBatchEndpoint
+            | RpcError::BatchRejected(_)
+            | RpcError::BudgetExhausted(_)
+            | RpcError::Offline(_),
+        ) => {
+            return stream::iter(
+                chunk
+                    .iter()
+                    .map(|params| execute_single(rpc_pool_manager, method, params, pin)),
+            )
+            .buffered(max_in_flight)
+            .collect()
+            .await;
+        }
+        Err(error) => {
+            // the whole batch failed after retries, so every item fails with it
+            let message = error.to_string();
+            let mut results = vec![Err(error)];
+            for _ in 1..chunk.len() {
+                results.push(Err(RpcError::BatchFailed(message.clone())));
+            }
+            return results;
+        }
+    };
+
+    stream::iter(
+        chunk
+            .iter()
+            .zip(batch_results)
+            .map(|(params, result)| async move {
+                let result = result
+                    .and_then(|value| serde_json::from_value::<T>(value).map_err(ClientError::from))
+                    .map_err(RpcError::from_client_error);
+
+                match result {
+                    Err(error) if error.is_retryable() => {
+                        println!(
+                            "{} Batched {} failed, retrying on its own: {}",
+                            log_time(),
+                            method,
+                            error
+                        );
+                        execute_single(rpc_pool_manager, method, params, pin).await
+                    }
+                    result => result,
+                }
+            }),
+    )
+    .buffered(max_in_flight)
+    .collect()
+    .await
+}
@@ -0,0 +1,90 @@
+use super::errors::TransactionCacheError;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+pub const DEFAULT_TX_CACHE_DIR: &str = ".cache/transactions";
+
+const TX_CACHE_COMPRESSION_LEVEL: i32 = 3;
+const TX_CACHE_EXTENSION: &str = "json.zst";
+
+static TMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// Raw transactions keyed by signature, stored as zstd-compressed JSON and sharded by the
+// first characters of the signature so no single directory grows too large.
+#[derive(Debug, Clone)]
+pub struct TransactionCache {
+    dir: PathBuf,
+}
+
+impl TransactionCache {
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, TransactionCacheError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(TransactionCacheError::Io)?;
+
+        Ok(Self { dir })
+    }
+
+    fn path(&self, signature: &Signature) -> PathBuf {
+        let signature = signature.to_string();
+
+        self.dir
+            .join(&signature[..2])
+            .join(&signature[2..4])
+            .join(format!("{}.{}", signature, TX_CACHE_EXTENSION))
+    }
+
+    pub fn get(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<EncodedConfirmedTransactionWithStatusMeta>, TransactionCacheError> {
+        let compressed = match fs::read(self.path(signature)) {
+            Ok(compressed) => compressed,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(TransactionCacheError::Io(err)),
+        };
+
+        let raw = zstd::decode_all(compressed.as_slice()).map_err(TransactionCacheError::Io)?;
+        let tx = serde_json::from_slice(&raw).map_err(TransactionCacheError::Json)?;
+
+        Ok(Some(tx))
+    }
+
+    pub fn put(
+        &self,
+        signature: &Signature,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Result<(), TransactionCacheError> {
+        let path = self.path(signature);
+        if path.exists() {
+            return Ok(());
+        }
+
+        let raw = serde_json::to_vec(tx).map_err(TransactionCacheError::Json)?;
+        let compressed = zstd::encode_all(raw.as_slice(), TX_CACHE_COMPRESSION_LEVEL)
+            .map_err(TransactionCacheError::Io)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(TransactionCacheError::Io)?;
+        }
+
+        // write then rename so a crash never leaves a truncated entry behind, to a tmp file of
+        // our own so concurrent writers of the same signature never share one
+        let tmp_path = path.with_file_name(format!(
+            "{}.{}.{}.tmp",
+            signature,
+            process::id(),
+            TMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp_path, compressed).map_err(TransactionCacheError::Io)?;
+        fs::rename(&tmp_path, &path).map_err(TransactionCacheError::Io)?;
+
+        Ok(())
+    }
+}
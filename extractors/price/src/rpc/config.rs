@@ -0,0 +1,259 @@
+use super::{errors::RpcConfigError, retry::RetryPolicy};
+use serde::Deserialize;
+use std::{collections::HashMap, env, fs, str::FromStr};
+
+pub const DEFAULT_RPC_CONFIG_PATH: &str = "rpc.toml";
+pub const RPC_CLUSTER_ENV: &str = "RPC_CLUSTER";
+pub const RPC_BATCH_SIZE_ENV: &str = "RPC_BATCH_SIZE";
+pub const RPC_MAX_IN_FLIGHT_ENV: &str = "RPC_MAX_IN_FLIGHT";
+pub const RPC_ENDPOINT_ENV_PREFIX: &str = "RPC_ENDPOINT";
+
+pub const DEFAULT_REQUESTS_PER_SECOND: u64 = 1;
+pub const DEFAULT_PRIORITY: u8 = 1;
+pub const DEFAULT_CREDITS_PER_REQUEST: u64 = 1;
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+}
+
+impl FromStr for Cluster {
+    type Err = RpcConfigError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "mainnet" => Ok(Self::Mainnet),
+            "devnet" => Ok(Self::Devnet),
+            _ => Err(RpcConfigError::InvalidOverride(
+                RPC_CLUSTER_ENV.to_string(),
+                value.to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcEndpointConfig {
+    pub name: String,
+    pub cluster: Cluster,
+    pub url: Option<String>,
+    // name of an env var holding the url, so api keys can stay in .env
+    pub url_env: Option<String>,
+    // websocket url for live subscriptions, derived from `url` when unset
+    pub ws_url: Option<String>,
+    #[serde(default = "default_requests_per_second")]
+    pub requests_per_second: u64,
+    pub burst: Option<u64>,
+    // lower is preferred
+    #[serde(default = "default_priority")]
+    pub priority: u8,
+    #[serde(default = "default_credits_per_request")]
+    pub default_credits: u64,
+    #[serde(default)]
+    pub credits: HashMap<String, u64>,
+    pub daily_credit_budget: Option<u64>,
+    pub monthly_credit_budget: Option<u64>,
+    // set to false for providers that reject JSON-RPC batch requests
+    #[serde(default = "default_enabled")]
+    pub supports_batch: bool,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcConfig {
+    pub cluster: Cluster,
+    #[serde(default)]
+    pub endpoints: Vec<RpcEndpointConfig>,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    // max requests per JSON-RPC batch, 1 disables batching
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    // concurrent requests per pipeline thread, the endpoint limiters still cap the total rate
+    #[serde(default = "default_max_in_flight")]
+    pub max_in_flight: usize,
+}
+
+fn default_requests_per_second() -> u64 {
+    DEFAULT_REQUESTS_PER_SECOND
+}
+
+fn default_priority() -> u8 {
+    DEFAULT_PRIORITY
+}
+
+fn default_credits_per_request() -> u64 {
+    DEFAULT_CREDITS_PER_REQUEST
+}
+
+fn default_batch_size() -> usize {
+    DEFAULT_BATCH_SIZE
+}
+
+fn default_max_in_flight() -> usize {
+    DEFAULT_MAX_IN_FLIGHT
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl RpcEndpointConfig {
+    pub fn burst(&self) -> u64 {
+        self.burst
+            .unwrap_or(self.requests_per_second)
+            .max(self.requests_per_second)
+    }
+
+    pub fn ws_url(&self) -> String {
+        if let Some(ws_url) = &self.ws_url {
+            return ws_url.clone();
+        }
+
+        let url = self.url.clone().unwrap_or_default();
+        if let Some(rest) = url.strip_prefix("https://") {
+            format!("wss://{}", rest)
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            format!("ws://{}", rest)
+        } else {
+            url
+        }
+    }
+
+    fn env_key(&self, field: &str) -> String {
+        format!(
+            "{}_{}_{}",
+            RPC_ENDPOINT_ENV_PREFIX,
+            self.name.to_uppercase().replace(['-', ' ', '.'], "_"),
+            field
+        )
+    }
+
+    fn apply_env_overrides(&mut self) -> Result<(), RpcConfigError> {
+        if let Some(url) = env_override::<String>(&self.env_key("URL"))? {
+            self.url = Some(url);
+        }
+        if let Some(ws_url) = env_override::<String>(&self.env_key("WS_URL"))? {
+            self.ws_url = Some(ws_url);
+        }
+        if let Some(requests_per_second) = env_override(&self.env_key("RPS"))? {
+            self.requests_per_second = requests_per_second;
+        }
+        if let Some(burst) = env_override(&self.env_key("BURST"))? {
+            self.burst = Some(burst);
+        }
+        if let Some(priority) = env_override(&self.env_key("PRIORITY"))? {
+            self.priority = priority;
+        }
+        if let Some(daily_budget) = env_override(&self.env_key("DAILY_BUDGET"))? {
+            self.daily_credit_budget = Some(daily_budget);
+        }
+        if let Some(monthly_budget) = env_override(&self.env_key("MONTHLY_BUDGET"))? {
+            self.monthly_credit_budget = Some(monthly_budget);
+        }
+        if let Some(supports_batch) = env_override(&self.env_key("BATCH"))? {
+            self.supports_batch = supports_batch;
+        }
+        if let Some(enabled) = env_override(&self.env_key("ENABLED"))? {
+            self.enabled = enabled;
+        }
+
+        Ok(())
+    }
+
+    fn resolve_url(&mut self) -> Result<(), RpcConfigError> {
+        if self.url.is_some() {
+            return Ok(());
+        }
+
+        let url_env = self
+            .url_env
+            .as_ref()
+            .ok_or_else(|| RpcConfigError::MissingUrl(self.name.clone()))?;
+
+        let url = env::var(url_env).map_err(|_| RpcConfigError::MissingUrl(self.name.clone()))?;
+        self.url = Some(url);
+
+        Ok(())
+    }
+}
+
+impl RpcConfig {
+    pub fn load(path: &str) -> Result<Self, RpcConfigError> {
+        let raw = fs::read_to_string(path)
+            .map_err(|error| RpcConfigError::Io(path.to_string(), error))?;
+
+        let mut config = Self::parse(&raw)?;
+        config.apply_env_overrides()?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn parse(raw: &str) -> Result<Self, RpcConfigError> {
+        toml::from_str(raw).map_err(RpcConfigError::Parse)
+    }
+
+    pub fn active_endpoints(&self) -> impl Iterator<Item = &RpcEndpointConfig> {
+        self.endpoints
+            .iter()
+            .filter(move |endpoint| endpoint.enabled && endpoint.cluster == self.cluster)
+    }
+
+    fn apply_env_overrides(&mut self) -> Result<(), RpcConfigError> {
+        if let Ok(cluster) = env::var(RPC_CLUSTER_ENV) {
+            self.cluster = Cluster::from_str(&cluster)?;
+        }
+        if let Some(batch_size) = env_override(RPC_BATCH_SIZE_ENV)? {
+            self.batch_size = batch_size;
+        }
+        if let Some(max_in_flight) = env_override(RPC_MAX_IN_FLIGHT_ENV)? {
+            self.max_in_flight = max_in_flight;
+        }
+
+        for endpoint in self.endpoints.iter_mut() {
+            endpoint.apply_env_overrides()?;
+        }
+
+        Ok(())
+    }
+
+    fn validate(&mut self) -> Result<(), RpcConfigError> {
+        let cluster = self.cluster;
+        let mut seen_names = Vec::new();
+
+        for endpoint in self
+            .endpoints
+            .iter_mut()
+            .filter(|endpoint| endpoint.enabled && endpoint.cluster == cluster)
+        {
+            if seen_names.contains(&endpoint.name) {
+                return Err(RpcConfigError::DuplicateEndpoint(endpoint.name.clone()));
+            }
+            seen_names.push(endpoint.name.clone());
+
+            endpoint.resolve_url()?;
+        }
+
+        if seen_names.is_empty() {
+            return Err(RpcConfigError::NoEndpoints(cluster));
+        }
+
+        Ok(())
+    }
+}
+
+fn env_override<T: FromStr>(key: &str) -> Result<Option<T>, RpcConfigError> {
+    match env::var(key) {
+        Ok(value) => value
+            .parse::<T>()
+            .map(Some)
+            .map_err(|_| RpcConfigError::InvalidOverride(key.to_string(), value)),
+        Err(_) => Ok(None),
+    }
+}
@@ -0,0 +1,213 @@
+use crate::utils::log::log_time;
+use redis::{Client, Connection, RedisError};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use time::{Date, OffsetDateTime};
+
+pub const REDIS_RPC_CREDITS_PREFIX: &str = "rpc_credits";
+
+const DAILY_USAGE_TTL_SECONDS: u64 = 2 * 24 * 60 * 60;
+const MONTHLY_USAGE_TTL_SECONDS: u64 = 62 * 24 * 60 * 60;
+
+pub const CREDIT_BUDGET_WARNING_RATIO: f64 = 0.8;
+pub const CREDIT_USAGE_FLUSH_INTERVAL_MS: u64 = 5000;
+
+// credits used per endpoint name since the last flush to dragonfly
+pub type PendingCreditUsage = Arc<Mutex<HashMap<String, u64>>>;
+
+#[derive(Debug, Clone)]
+pub struct CreditBudget {
+    pub daily_limit: Option<u64>,
+    pub monthly_limit: Option<u64>,
+    pub daily_used: u64,
+    pub monthly_used: u64,
+    pub day: Date,
+}
+
+impl CreditBudget {
+    pub fn new(
+        daily_limit: Option<u64>,
+        monthly_limit: Option<u64>,
+        daily_used: u64,
+        monthly_used: u64,
+    ) -> Self {
+        Self {
+            daily_limit,
+            monthly_limit,
+            daily_used,
+            monthly_used,
+            day: today(),
+        }
+    }
+
+    pub fn can_afford(&mut self, credits: u64) -> bool {
+        self.roll_over();
+
+        let within_daily = self
+            .daily_limit
+            .map(|limit| self.daily_used + credits <= limit)
+            .unwrap_or(true);
+        let within_monthly = self
+            .monthly_limit
+            .map(|limit| self.monthly_used + credits <= limit)
+            .unwrap_or(true);
+
+        within_daily && within_monthly
+    }
+
+    pub fn spend(&mut self, credits: u64) {
+        self.roll_over();
+
+        self.daily_used += credits;
+        self.monthly_used += credits;
+    }
+
+    pub fn refund(&mut self, credits: u64) {
+        self.roll_over();
+
+        self.daily_used = self.daily_used.saturating_sub(credits);
+        self.monthly_used = self.monthly_used.saturating_sub(credits);
+    }
+
+    // fraction of the tighter of the two limits that has been used
+    pub fn usage_ratio(&self) -> f64 {
+        let daily = self
+            .daily_limit
+            .map(|limit| self.daily_used as f64 / limit.max(1) as f64);
+        let monthly = self
+            .monthly_limit
+            .map(|limit| self.monthly_used as f64 / limit.max(1) as f64);
+
+        daily.into_iter().chain(monthly).fold(0.0, f64::max)
+    }
+
+    fn roll_over(&mut self) {
+        self.roll_over_to(today());
+    }
+
+    pub fn roll_over_to(&mut self, today: Date) {
+        if today == self.day {
+            return;
+        }
+
+        if today.year() != self.day.year() || today.month() != self.day.month() {
+            self.monthly_used = 0;
+        }
+        self.daily_used = 0;
+        self.day = today;
+    }
+}
+
+pub fn today() -> Date {
+    OffsetDateTime::now_utc().date()
+}
+
+pub fn daily_usage_key(endpoint_name: &str, date: Date) -> String {
+    format!(
+        "{}:{}:day:{}",
+        REDIS_RPC_CREDITS_PREFIX, endpoint_name, date
+    )
+}
+
+pub fn monthly_usage_key(endpoint_name: &str, date: Date) -> String {
+    format!(
+        "{}:{}:month:{}-{:02}",
+        REDIS_RPC_CREDITS_PREFIX,
+        endpoint_name,
+        date.year(),
+        u8::from(date.month())
+    )
+}
+
+pub fn get_credit_usage(client: &Client, endpoint_name: &str) -> Result<(u64, u64), RedisError> {
+    let mut conn = client.get_connection()?;
+    let date = today();
+
+    let daily_used: Option<u64> = redis::cmd("GET")
+        .arg(daily_usage_key(endpoint_name, date))
+        .query(&mut conn)?;
+    let monthly_used: Option<u64> = redis::cmd("GET")
+        .arg(monthly_usage_key(endpoint_name, date))
+        .query(&mut conn)?;
+
+    Ok((daily_used.unwrap_or(0), monthly_used.unwrap_or(0)))
+}
+
+pub fn add_credit_usage(
+    conn: &mut Connection,
+    endpoint_name: &str,
+    credits: u64,
+) -> Result<(), RedisError> {
+    let date = today();
+
+    let daily_key = daily_usage_key(endpoint_name, date);
+    let monthly_key = monthly_usage_key(endpoint_name, date);
+
+    let _: () = redis::pipe()
+        .cmd("INCRBY")
+        .arg(&daily_key)
+        .arg(credits)
+        .ignore()
+        .cmd("EXPIRE")
+        .arg(&daily_key)
+        .arg(DAILY_USAGE_TTL_SECONDS)
+        .ignore()
+        .cmd("INCRBY")
+        .arg(&monthly_key)
+        .arg(credits)
+        .ignore()
+        .cmd("EXPIRE")
+        .arg(&monthly_key)
+        .arg(MONTHLY_USAGE_TTL_SECONDS)
+        .ignore()
+        .query(conn)?;
+
+    Ok(())
+}
+
+// Usage is accumulated in process and flushed on one connection so budgets survive
+// restarts without a redis round trip per request.
+pub fn flush_credit_usage_thread(client: Client, pending: PendingCreditUsage) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut conn: Option<Connection> = None;
+
+        loop {
+            thread::sleep(Duration::from_millis(CREDIT_USAGE_FLUSH_INTERVAL_MS));
+            flush_credit_usage(&client, &mut conn, &pending);
+        }
+    })
+}
+
+// Usage that could not be persisted is put back and goes out with the next flush
+pub fn flush_credit_usage(
+    client: &Client,
+    conn: &mut Option<Connection>,
+    pending: &PendingCreditUsage,
+) {
+    let usage = std::mem::take(&mut *pending.lock().unwrap());
+
+    for (endpoint_name, credits) in usage {
+        let result = conn
+            .take()
+            .map_or_else(|| client.get_connection(), Ok)
+            .and_then(|mut flush_conn| {
+                add_credit_usage(&mut flush_conn, &endpoint_name, credits)?;
+                *conn = Some(flush_conn);
+                Ok(())
+            });
+
+        if let Err(err) = result {
+            println!(
+                "{} Failed to persist credit usage for {}, retrying on next flush: {}",
+                log_time(),
+                endpoint_name,
+                err
+            );
+            *pending.lock().unwrap().entry(endpoint_name).or_insert(0) += credits;
+        }
+    }
+}
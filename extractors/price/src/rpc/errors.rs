@@ -1,14 +1,147 @@
-use solana_client::client_error::ClientError;
+use super::{batch::BATCH_REJECTED_PREFIX, config::Cluster};
+use solana_client::{
+    client_error::{reqwest::StatusCode, ClientError, ClientErrorKind},
+    rpc_custom_error::{
+        JSON_RPC_SERVER_ERROR_BLOCK_CLEANED_UP, JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+        JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET,
+        JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED,
+        JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+        JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+        JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
+    },
+    rpc_request::RpcError as RpcRequestError,
+};
 
 #[derive(Debug)]
 pub enum RpcError {
-    ClientError(ClientError),
+    RateLimited(ClientError),
+    TransientNetwork(ClientError),
+    NodeBehind(ClientError),
+    NotFound(ClientError),
+    Permanent(ClientError),
+    BatchRejected(ClientError),
+    NoAvailableEndpoint,
+    NoBatchEndpoint,
+    BatchFailed(String),
+    BudgetExhausted(String),
+    Offline(String),
+}
+
+impl RpcError {
+    pub fn from_client_error(error: ClientError) -> Self {
+        let kind = match error.kind() {
+            ClientErrorKind::Reqwest(reqwest_err) => match reqwest_err.status() {
+                Some(status) if status == StatusCode::TOO_MANY_REQUESTS => {
+                    RpcErrorKind::RateLimited
+                }
+                Some(status) if status.is_server_error() => RpcErrorKind::TransientNetwork,
+                Some(_) => RpcErrorKind::Permanent,
+                None => RpcErrorKind::TransientNetwork,
+            },
+            ClientErrorKind::Io(_) => RpcErrorKind::TransientNetwork,
+            ClientErrorKind::Custom(message) if message.starts_with(BATCH_REJECTED_PREFIX) => {
+                RpcErrorKind::BatchRejected
+            }
+            ClientErrorKind::RpcError(RpcRequestError::RpcResponseError {
+                code, message, ..
+            }) => match *code {
+                JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                | JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE
+                | JSON_RPC_SERVER_ERROR_SLOT_SKIPPED
+                | JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED
+                | JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET
+                | JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED => RpcErrorKind::NodeBehind,
+                JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE
+                | JSON_RPC_SERVER_ERROR_BLOCK_CLEANED_UP => RpcErrorKind::NotFound,
+                _ => kind_from_message(message).unwrap_or(RpcErrorKind::Permanent),
+            },
+            ClientErrorKind::RpcError(RpcRequestError::RpcRequestError(message)) => {
+                kind_from_message(message).unwrap_or(RpcErrorKind::TransientNetwork)
+            }
+            // rpc methods deserialize a `null` result into the expected type
+            ClientErrorKind::SerdeJson(serde_err) if serde_err.to_string().contains("null") => {
+                RpcErrorKind::NotFound
+            }
+            _ => RpcErrorKind::Permanent,
+        };
+
+        match kind {
+            RpcErrorKind::RateLimited => Self::RateLimited(error),
+            RpcErrorKind::TransientNetwork => Self::TransientNetwork(error),
+            RpcErrorKind::NodeBehind => Self::NodeBehind(error),
+            RpcErrorKind::NotFound => Self::NotFound(error),
+            RpcErrorKind::Permanent => Self::Permanent(error),
+            RpcErrorKind::BatchRejected => Self::BatchRejected(error),
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited(_)
+                | Self::TransientNetwork(_)
+                | Self::NodeBehind(_)
+                | Self::BatchRejected(_)
+        )
+    }
+
+    // counts towards opening the endpoint's circuit
+    pub fn is_endpoint_failure(&self) -> bool {
+        matches!(self, Self::TransientNetwork(_) | Self::NodeBehind(_))
+    }
+
+    // providers only bill requests they answered
+    pub fn is_billed(&self) -> bool {
+        !matches!(self, Self::RateLimited(_) | Self::TransientNetwork(_))
+    }
+
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Self::Permanent(_) | Self::BudgetExhausted(_))
+    }
+}
+
+enum RpcErrorKind {
+    RateLimited,
+    TransientNetwork,
+    NodeBehind,
+    NotFound,
+    Permanent,
+    BatchRejected,
+}
+
+fn kind_from_message(message: &str) -> Option<RpcErrorKind> {
+    let message = message.to_lowercase();
+
+    if message.contains("slot skipped")
+        || message.contains("block not available")
+        || message.contains("behind")
+    {
+        Some(RpcErrorKind::NodeBehind)
+    } else if message.contains("timed out") || message.contains("connection") {
+        Some(RpcErrorKind::TransientNetwork)
+    } else if message.contains("not found") {
+        Some(RpcErrorKind::NotFound)
+    } else {
+        None
+    }
 }
 
 impl std::fmt::Display for RpcError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::ClientError(err) => write!(f, "{}", err),
+            Self::RateLimited(err) => write!(f, "Rate limited: {}", err),
+            Self::TransientNetwork(err) => write!(f, "Transient network error: {}", err),
+            Self::NodeBehind(err) => write!(f, "Node behind: {}", err),
+            Self::NotFound(err) => write!(f, "Not found: {}", err),
+            Self::Permanent(err) => write!(f, "{}", err),
+            Self::BatchRejected(err) => write!(f, "Batch request rejected: {}", err),
+            Self::NoAvailableEndpoint => write!(f, "No rpc endpoint became available in time"),
+            Self::NoBatchEndpoint => write!(f, "No rpc endpoint accepts batch requests"),
+            Self::BatchFailed(err) => write!(f, "Batch request failed: {}", err),
+            Self::Offline(method) => write!(f, "Running offline, cannot send {}", method),
+            Self::BudgetExhausted(method) => {
+                write!(f, "No rpc endpoint has credit budget left for {}", method)
+            }
         }
     }
 }
@@ -16,8 +149,78 @@ impl std::fmt::Display for RpcError {
 impl std::error::Error for RpcError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Self::ClientError(err) => Some(err),
+            Self::RateLimited(err)
+            | Self::TransientNetwork(err)
+            | Self::NodeBehind(err)
+            | Self::NotFound(err)
+            | Self::Permanent(err)
+            | Self::BatchRejected(err) => Some(err),
+            Self::NoAvailableEndpoint
+            | Self::NoBatchEndpoint
+            | Self::BatchFailed(_)
+            | Self::BudgetExhausted(_)
+            | Self::Offline(_) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RpcConfigError {
+    Io(String, std::io::Error),
+    Parse(toml::de::Error),
+    MissingUrl(String),
+    DuplicateEndpoint(String),
+    InvalidOverride(String, String),
+    NoEndpoints(Cluster),
+}
+
+impl std::fmt::Display for RpcConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "Failed to read rpc config {}: {}", path, err),
+            Self::Parse(err) => write!(f, "Failed to parse rpc config: {}", err),
+            Self::MissingUrl(name) => write!(f, "No url configured for rpc endpoint {}", name),
+            Self::DuplicateEndpoint(name) => write!(f, "Duplicate rpc endpoint name {}", name),
+            Self::InvalidOverride(key, value) => {
+                write!(f, "Invalid rpc config override {}={}", key, value)
+            }
+            Self::NoEndpoints(cluster) => {
+                write!(f, "No enabled rpc endpoints configured for {:?}", cluster)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RpcConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(_, err) => Some(err),
+            Self::Parse(err) => Some(err),
             _ => None,
         }
     }
 }
+
+#[derive(Debug)]
+pub enum TransactionCacheError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for TransactionCacheError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Transaction cache io error: {}", err),
+            Self::Json(err) => write!(f, "Transaction cache entry is not valid json: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for TransactionCacheError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Json(err) => Some(err),
+        }
+    }
+}
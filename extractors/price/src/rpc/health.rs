@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+pub const HEALTH_EWMA_ALPHA: f64 = 0.2;
+pub const CIRCUIT_FAILURE_THRESHOLD: u32 = 5;
+pub const CIRCUIT_OPEN_DURATION_MS: u64 = 30_000;
+
+// floor so a struggling endpoint still gets the odd request once its circuit closes
+const MIN_HEALTH_SCORE: f64 = 0.05;
+const LATENCY_SCORE_SCALE_MS: f64 = 250.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CircuitState {
+    Closed,
+    Open(Instant),
+    HalfOpen,
+}
+
+#[derive(Debug, Clone)]
+pub struct EndpointHealth {
+    pub latency_ewma_ms: f64,
+    pub error_rate_ewma: f64,
+    pub consecutive_failures: u32,
+    pub circuit: CircuitState,
+    probe_in_flight: bool,
+}
+
+impl EndpointHealth {
+    pub fn new() -> Self {
+        Self {
+            latency_ewma_ms: 0.0,
+            error_rate_ewma: 0.0,
+            consecutive_failures: 0,
+            circuit: CircuitState::Closed,
+            probe_in_flight: false,
+        }
+    }
+
+    pub fn can_accept(&mut self) -> bool {
+        if let CircuitState::Open(until) = self.circuit {
+            if Instant::now() < until {
+                return false;
+            }
+
+            self.circuit = CircuitState::HalfOpen;
+            self.probe_in_flight = false;
+        }
+
+        match self.circuit {
+            CircuitState::HalfOpen => !self.probe_in_flight,
+            _ => true,
+        }
+    }
+
+    pub fn on_selected(&mut self) {
+        if self.circuit == CircuitState::HalfOpen {
+            self.probe_in_flight = true;
+        }
+    }
+
+    pub fn release_probe(&mut self) {
+        self.probe_in_flight = false;
+    }
+
+    pub fn open_until(&self) -> Option<Instant> {
+        match self.circuit {
+            CircuitState::Open(until) => Some(until),
+            _ => None,
+        }
+    }
+
+    pub fn record_success(&mut self, latency: Duration) {
+        self.record_latency(latency);
+        self.error_rate_ewma = ewma(self.error_rate_ewma, 0.0);
+        self.consecutive_failures = 0;
+        self.circuit = CircuitState::Closed;
+        self.probe_in_flight = false;
+    }
+
+    pub fn record_failure(&mut self, latency: Duration) {
+        self.record_latency(latency);
+        self.error_rate_ewma = ewma(self.error_rate_ewma, 1.0);
+        self.consecutive_failures += 1;
+        self.probe_in_flight = false;
+
+        let should_open = self.circuit == CircuitState::HalfOpen
+            || self.consecutive_failures >= CIRCUIT_FAILURE_THRESHOLD;
+
+        if should_open {
+            self.circuit = CircuitState::Open(
+                Instant::now() + Duration::from_millis(CIRCUIT_OPEN_DURATION_MS),
+            );
+        }
+    }
+
+    pub fn score(&self) -> f64 {
+        let reliability = 1.0 - self.error_rate_ewma;
+        let speed = 1.0 / (1.0 + self.latency_ewma_ms / LATENCY_SCORE_SCALE_MS);
+
+        (reliability * speed).max(MIN_HEALTH_SCORE)
+    }
+
+    fn record_latency(&mut self, latency: Duration) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+
+        self.latency_ewma_ms = if self.latency_ewma_ms == 0.0 {
+            latency_ms
+        } else {
+            ewma(self.latency_ewma_ms, latency_ms)
+        };
+    }
+}
+
+fn ewma(previous: f64, sample: f64) -> f64 {
+    HEALTH_EWMA_ALPHA * sample + (1.0 - HEALTH_EWMA_ALPHA) * previous
+}
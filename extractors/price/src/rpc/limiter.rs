@@ -0,0 +1,64 @@
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_second: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(requests_per_second: u64, burst: u64) -> Self {
+        let capacity = burst.max(1) as f64;
+
+        Self {
+            capacity,
+            refill_per_second: requests_per_second.max(1) as f64,
+            tokens: capacity,
+            last_refill: Instant::now(),
+        }
+    }
+
+    // A batch larger than the burst waits for a full bucket and then goes into debt,
+    // so the requests after it wait until the whole batch has been paid for.
+    fn required(&self, count: u64) -> f64 {
+        (count.max(1) as f64).min(self.capacity)
+    }
+
+    pub fn has_tokens(&mut self, count: u64) -> bool {
+        self.refill();
+        self.tokens >= self.required(count)
+    }
+
+    pub fn try_acquire(&mut self, count: u64) -> bool {
+        if !self.has_tokens(count) {
+            return false;
+        }
+
+        self.tokens -= count.max(1) as f64;
+        true
+    }
+
+    pub fn next_tokens_at(&mut self, count: u64) -> Instant {
+        self.refill();
+
+        let required = self.required(count);
+        if self.tokens >= required {
+            return Instant::now();
+        }
+
+        let missing = required - self.tokens;
+        Instant::now() + Duration::from_secs_f64(missing / self.refill_per_second)
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+}
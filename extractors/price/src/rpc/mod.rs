@@ -1,3 +1,10 @@
+pub mod batch;
+pub mod cache;
 pub mod clients;
+pub mod config;
+pub mod credits;
 pub mod errors;
+pub mod health;
+pub mod limiter;
 pub mod pool;
+pub mod retry;
@@ -1,204 +1,646 @@
-use super::{clients::build_rpc_client_states, errors::RpcError};
-use crate::{rpc::clients::build_rpc_client, utils::log::log_time};
+use super::{
+    cache::TransactionCache,
+    clients::{build_rpc_client, build_rpc_client_states},
+    config::{RpcConfig, RpcEndpointConfig},
+    credits::{
+        flush_credit_usage_thread, get_credit_usage, CreditBudget, PendingCreditUsage,
+        CREDIT_BUDGET_WARNING_RATIO,
+    },
+    errors::RpcError,
+    health::EndpointHealth,
+    limiter::TokenBucket,
+    retry::RetryPolicy,
+};
+use crate::utils::log::log_time;
+use redis::Client;
 use solana_client::{
-    client_error::{reqwest::StatusCode, ClientError, ClientErrorKind},
-    rpc_client::RpcClient,
+    client_error::ClientError, nonblocking::rpc_client::RpcClient, rpc_request::RpcRequest,
 };
+use solana_sdk::signature::Signature;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
 use std::{
     collections::HashMap,
+    future::Future,
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc, Mutex,
     },
-    thread::sleep,
+    thread::JoinHandle,
     time::{Duration, Instant},
 };
+use tokio::time::sleep;
 
 pub const DEFAULT_RATE_LIMIT_COOLOFF_MS: u64 = 1000;
+const HEALTH_SCORE_WEIGHT_SCALE: f64 = 100.0;
+
+pub type EndpointId = usize;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcEndpoint {
+    pub id: EndpointId,
+    pub name: String,
+    pub url: String,
+}
+
+// A single rpc request, or a JSON-RPC batch of `count` requests for the same method.
+// A batch takes a rate limiter token and is charged credits for every request in it.
+#[derive(Clone, Copy, Debug)]
+pub struct RpcCall {
+    pub method: RpcRequest,
+    pub count: u64,
+    pub batch: bool,
+}
+
+impl RpcCall {
+    pub fn single(method: RpcRequest) -> Self {
+        Self {
+            method,
+            count: 1,
+            batch: false,
+        }
+    }
+
+    pub fn batch(method: RpcRequest, count: usize) -> Self {
+        Self {
+            method,
+            count: count as u64,
+            batch: true,
+        }
+    }
+}
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct RpcClientState {
+    pub id: EndpointId,
+    pub name: String,
     pub url: String,
+    pub ws_url: String,
+    pub client: Arc<RpcClient>,
     pub rate_limited_until: Option<Instant>,
-    pub requests_per_second: u64,
-    pub recent_requests: Vec<Instant>,
+    pub priority: u8,
+    pub limiter: TokenBucket,
+    pub health: EndpointHealth,
+    pub credits: HashMap<String, u64>,
+    pub default_credits: u64,
+    pub budget: CreditBudget,
+    pub supports_batch: bool,
 }
 
 impl RpcClientState {
-    pub fn new(url: &str, requests_per_second: u64) -> Self {
+    pub fn from_config(id: EndpointId, config: &RpcEndpointConfig, dragonfly: &Client) -> Self {
+        let url = config.url.clone().unwrap_or_default();
+
+        let (daily_used, monthly_used) = match get_credit_usage(dragonfly, &config.name) {
+            Ok(usage) => usage,
+            Err(err) => {
+                println!(
+                    "{} Failed to load credit usage for {}, starting from zero: {}",
+                    log_time(),
+                    config.name,
+                    err
+                );
+                (0, 0)
+            }
+        };
+
         Self {
-            url: url.to_string(),
-            requests_per_second,
+            id,
+            name: config.name.clone(),
+            client: build_rpc_client(url.clone()),
+            url,
+            ws_url: config.ws_url(),
+            priority: config.priority,
             rate_limited_until: None,
-            recent_requests: Vec::new(),
+            limiter: TokenBucket::new(config.requests_per_second, config.burst()),
+            health: EndpointHealth::new(),
+            credits: config.credits.clone(),
+            default_credits: config.default_credits,
+            budget: CreditBudget::new(
+                config.daily_credit_budget,
+                config.monthly_credit_budget,
+                daily_used,
+                monthly_used,
+            ),
+            supports_batch: config.supports_batch,
         }
     }
 
-    pub fn is_available(&self) -> bool {
+    pub fn credits_for(&self, method: RpcRequest) -> u64 {
+        *self
+            .credits
+            .get(&method.to_string())
+            .unwrap_or(&self.default_credits)
+    }
+
+    pub fn can_afford(&mut self, call: RpcCall) -> bool {
+        let credits = self.credits_for(call.method) * call.count;
+        self.budget.can_afford(credits)
+    }
+
+    pub fn is_available(&mut self, call: RpcCall) -> bool {
         if let Some(until) = self.rate_limited_until {
             if Instant::now() < until {
                 return false;
             }
         }
 
-        let one_second_ago = Instant::now() - Duration::from_secs(1);
-        let current_rps = self
-            .recent_requests
-            .iter()
-            .filter(|&&timestamp| timestamp > one_second_ago)
-            .count() as u64;
+        self.limiter.has_tokens(call.count)
+    }
 
-        current_rps < self.requests_per_second
+    pub fn is_selectable(&mut self, call: RpcCall) -> bool {
+        (!call.batch || self.supports_batch)
+            && self.can_afford(call)
+            && self.is_available(call)
+            && self.health.can_accept()
     }
 
-    fn record_request(&mut self) {
-        let now = Instant::now();
+    pub fn available_at(&mut self, call: RpcCall) -> Instant {
+        let next_token_at = self.limiter.next_tokens_at(call.count);
 
-        self.recent_requests
-            .retain(|&timestamp| now - timestamp <= Duration::from_secs(1));
-        self.recent_requests.push(now);
+        [
+            Some(next_token_at),
+            self.rate_limited_until,
+            self.health.open_until(),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(next_token_at)
+    }
 
-        let current_rps = self.recent_requests.len() as u64;
-        if current_rps >= self.requests_per_second {
-            self.rate_limited_until =
-                Some(now + Duration::from_millis(DEFAULT_RATE_LIMIT_COOLOFF_MS));
+    pub fn endpoint(&self) -> RpcEndpoint {
+        RpcEndpoint {
+            id: self.id,
+            name: self.name.clone(),
+            url: self.url.clone(),
         }
     }
+
+    fn select(&mut self, call: RpcCall) -> (RpcEndpoint, Arc<RpcClient>, u64) {
+        let credits = self.credits_for(call.method) * call.count;
+
+        let usage_before = self.budget.usage_ratio();
+        self.budget.spend(credits);
+        let usage_after = self.budget.usage_ratio();
+
+        if usage_before < CREDIT_BUDGET_WARNING_RATIO && usage_after >= CREDIT_BUDGET_WARNING_RATIO
+        {
+            println!(
+                "{} WARNING: {} has used {:.0}% of its credit budget ({} today, {} this month)",
+                log_time(),
+                self.name,
+                usage_after * 100.0,
+                self.budget.daily_used,
+                self.budget.monthly_used
+            );
+        }
+
+        self.limiter.try_acquire(call.count);
+        self.health.on_selected();
+        (self.endpoint(), self.client.clone(), credits)
+    }
 }
 
 #[derive(Clone)]
 pub struct RpcPoolManager {
-    clients: Arc<Mutex<HashMap<String, RpcClientState>>>,
+    clients: Arc<Mutex<Vec<RpcClientState>>>,
     rate_limit_duration: Duration,
+    retry_policy: RetryPolicy,
+    batch_size: usize,
+    max_in_flight: usize,
     request_counter: Arc<AtomicU64>,
+    dragonfly: Client,
+    pending_credits: PendingCreditUsage,
+    tx_cache: Option<TransactionCache>,
+    offline: bool,
 }
 
 impl RpcPoolManager {
-    pub fn new(rate_limit_duration: Duration) -> Self {
-        let clients = build_rpc_client_states();
+    pub fn new(config: &RpcConfig, rate_limit_duration: Duration, dragonfly: &Client) -> Self {
+        let clients = build_rpc_client_states(config, dragonfly);
 
         Self {
             clients: Arc::new(Mutex::new(clients)),
             rate_limit_duration,
+            retry_policy: config.retry,
+            batch_size: config.batch_size.max(1),
+            max_in_flight: config.max_in_flight.max(1),
             request_counter: Arc::new(AtomicU64::new(0)),
+            dragonfly: dragonfly.clone(),
+            pending_credits: Arc::new(Mutex::new(HashMap::new())),
+            tx_cache: None,
+            offline: false,
         }
     }
 
-    pub fn get_available_client(&self, pin: Option<u64>) -> Option<String> {
-        let mut clients = self.clients.lock().unwrap();
+    // offline runs never touch the network and can only serve cached transactions
+    pub fn with_transaction_cache(mut self, tx_cache: TransactionCache, offline: bool) -> Self {
+        self.tx_cache = Some(tx_cache);
+        self.offline = offline;
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    pub fn get_cached_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Option<EncodedConfirmedTransactionWithStatusMeta> {
+        match self.tx_cache.as_ref()?.get(signature) {
+            Ok(tx) => tx,
+            Err(err) => {
+                println!(
+                    "{} Ignoring unreadable cached transaction {}: {}",
+                    log_time(),
+                    signature,
+                    err
+                );
+                None
+            }
+        }
+    }
 
-        if let Some(pin) = pin {
-            let client = clients.get_mut(&pin.to_string());
-            if let Some(client) = client {
-                client.record_request();
+    pub fn cache_transaction(
+        &self,
+        signature: &Signature,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+    ) {
+        if let Some(tx_cache) = &self.tx_cache {
+            if let Err(err) = tx_cache.put(signature, tx) {
                 println!(
-                    "{} FOUND PIN SO RETURNING PINNED CLIENT {} | ",
+                    "{} Failed to cache transaction {}: {}",
                     log_time(),
-                    client.url
+                    signature,
+                    err
                 );
-                return Some(client.url.clone());
             }
         }
+    }
+
+    pub fn get_rpc_nodes_count(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    // websocket url of the most preferred endpoint, used for live subscriptions
+    pub fn get_ws_url(&self) -> Option<String> {
+        self.clients
+            .lock()
+            .unwrap()
+            .iter()
+            .min_by_key(|client| client.priority)
+            .map(|client| client.ws_url.clone())
+    }
 
-        let available = clients
-            .values_mut()
-            .filter(|client| client.is_available())
-            .map(|client| {
-                client.record_request();
-                client.url.clone()
-            })
-            .collect::<Vec<String>>();
+    pub fn get_batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    pub fn get_max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
 
-        if available.is_empty() {
+    // Threads are pinned to an endpoint by index so the same worker keeps hitting the
+    // same provider, and only fail over while that endpoint is rate limited or unhealthy.
+    pub fn get_pinned_endpoint_id(&self, pin: u64) -> Option<EndpointId> {
+        let count = self.get_rpc_nodes_count();
+        if count == 0 {
             return None;
         }
 
+        Some(pin as usize % count)
+    }
+
+    pub fn get_available_client(
+        &self,
+        pin: Option<u64>,
+        call: RpcCall,
+    ) -> Option<(RpcEndpoint, Arc<RpcClient>, u64)> {
+        let pinned_id = pin.and_then(|pin| self.get_pinned_endpoint_id(pin));
+
+        let mut clients = self.clients.lock().unwrap();
+
+        if let Some(pinned_id) = pinned_id {
+            let client = &mut clients[pinned_id];
+            if client.is_selectable(call) {
+                return Some(client.select(call));
+            }
+
+            println!(
+                "{} Pinned client {} is unavailable. Failing over | ",
+                log_time(),
+                client.name
+            );
+        }
+
+        let mut selectable = clients
+            .iter_mut()
+            .filter(|client| Some(client.id) != pinned_id)
+            .filter_map(|client| client.is_selectable(call).then_some(client))
+            .collect::<Vec<_>>();
+
+        let best_priority = selectable.iter().map(|client| client.priority).min()?;
+        selectable.retain(|client| client.priority == best_priority);
+
+        // weighted round robin so healthier endpoints take proportionally more traffic
+        let weights = selectable
+            .iter()
+            .map(|client| (client.health.score() * HEALTH_SCORE_WEIGHT_SCALE).ceil() as u64)
+            .collect::<Vec<_>>();
+        let total_weight: u64 = weights.iter().sum();
+
         let next_count = self.request_counter.fetch_add(1, Ordering::SeqCst);
+        let mut position = next_count % total_weight.max(1);
+
+        let mut chosen = selectable.len() - 1;
+        for (index, weight) in weights.iter().enumerate() {
+            if position < *weight {
+                chosen = index;
+                break;
+            }
+            position -= weight;
+        }
+
+        Some(selectable[chosen].select(call))
+    }
+
+    pub fn has_available_client(&self, call: RpcCall) -> bool {
+        let mut clients = self.clients.lock().unwrap();
+
+        clients.iter_mut().any(|client| client.is_selectable(call))
+    }
+
+    pub fn has_budget_for(&self, call: RpcCall) -> bool {
+        let mut clients = self.clients.lock().unwrap();
+
+        clients.iter_mut().any(|client| client.can_afford(call))
+    }
+
+    pub fn has_batch_endpoint(&self) -> bool {
+        self.batch_size > 1
+            && self
+                .clients
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|client| client.supports_batch)
+    }
+
+    pub fn disable_batching(&self, endpoint: &RpcEndpoint) {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get_mut(endpoint.id) {
+            println!(
+                "{} {} rejected a batch request. Falling back to single requests",
+                log_time(),
+                client.name
+            );
+            client.supports_batch = false;
+            client.health.release_probe();
+        }
+    }
 
-        available
-            .get(next_count as usize % available.len())
-            .cloned()
+    pub fn flush_credit_usage_thread(&self) -> JoinHandle<()> {
+        flush_credit_usage_thread(self.dragonfly.clone(), self.pending_credits.clone())
     }
 
-    pub fn mark_rate_limited(&self, url: &str) {
+    fn record_credit_usage(&self, endpoint: &RpcEndpoint, credits: u64) {
+        let mut pending = self.pending_credits.lock().unwrap();
+        *pending.entry(endpoint.name.clone()).or_insert(0) += credits;
+    }
+
+    // credits are reserved when an endpoint is selected and handed back if it never answered
+    fn refund_credits(&self, endpoint: &RpcEndpoint, credits: u64) {
         let mut clients = self.clients.lock().unwrap();
-        if let Some(client) = clients.get_mut(url) {
+        if let Some(client) = clients.get_mut(endpoint.id) {
+            client.budget.refund(credits);
+        }
+    }
+
+    pub fn record_success(&self, endpoint: &RpcEndpoint, latency: Duration) {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get_mut(endpoint.id) {
+            client.health.record_success(latency);
+        }
+    }
+
+    pub fn record_failure(&self, endpoint: &RpcEndpoint, latency: Duration) {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get_mut(endpoint.id) {
+            client.health.record_failure(latency);
+
+            if let Some(open_until) = client.health.open_until() {
+                println!(
+                    "{} Circuit open for {} for {}ms after {} consecutive failures",
+                    log_time(),
+                    client.name,
+                    open_until
+                        .saturating_duration_since(Instant::now())
+                        .as_millis(),
+                    client.health.consecutive_failures
+                );
+            }
+        }
+    }
+
+    pub fn mark_rate_limited(&self, endpoint: &RpcEndpoint) {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get_mut(endpoint.id) {
             client.rate_limited_until = Some(Instant::now() + self.rate_limit_duration);
+            client.health.release_probe();
         }
     }
 
-    pub fn execute<F, T>(&self, operation: F, pin: Option<u64>) -> Result<T, RpcError>
+    pub async fn execute<F, Fut, T>(
+        &self,
+        method: RpcRequest,
+        operation: F,
+        pin: Option<u64>,
+    ) -> Result<T, RpcError>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        self.execute_with_endpoint(method, operation, pin)
+            .await
+            .map(|(result, _endpoint)| result)
+    }
+
+    pub async fn execute_with_endpoint<F, Fut, T>(
+        &self,
+        method: RpcRequest,
+        operation: F,
+        pin: Option<u64>,
+    ) -> Result<(T, RpcEndpoint), RpcError>
     where
-        F: FnOnce(&RpcClient) -> Result<T, ClientError> + Clone,
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
     {
-        let op = operation.clone();
+        self.execute_call(RpcCall::single(method), operation, pin)
+            .await
+    }
 
-        let client = self.get_available_client(pin);
+    // only routes to endpoints that accept JSON-RPC batches
+    pub async fn execute_batch<F, Fut, T>(
+        &self,
+        method: RpcRequest,
+        count: usize,
+        operation: F,
+        pin: Option<u64>,
+    ) -> Result<(T, RpcEndpoint), RpcError>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        self.execute_call(RpcCall::batch(method, count), operation, pin)
+            .await
+    }
 
-        let log_tag = format!("{} execute rpc op | ", log_time());
+    async fn execute_call<F, Fut, T>(
+        &self,
+        call: RpcCall,
+        operation: F,
+        pin: Option<u64>,
+    ) -> Result<(T, RpcEndpoint), RpcError>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let method = call.method;
 
-        if client.is_none() {
-            println!(
-                "{} No available clients at all. Sleeping until available...",
-                log_tag
-            );
-            self.sleep_until_available();
-            return self.execute(op, pin);
+        if self.offline {
+            return Err(RpcError::Offline(method.to_string()));
         }
-        let client_url = client.unwrap();
+        let started_at = Instant::now();
+        let deadline = started_at + self.retry_policy.deadline();
+        let mut attempt = 0;
 
-        let log_tag = format!("{} client {} | ", log_tag, client_url);
+        loop {
+            let log_tag = format!("{} execute rpc op {} | ", log_time(), method);
 
-        println!("{} Sending request...", log_tag);
-        match operation(&build_rpc_client(client_url.clone())) {
-            Ok(result) => Ok(result),
-            Err(error) => {
-                if let ClientError {
-                    request: _,
-                    kind: ClientErrorKind::Reqwest(ref reqwest_err),
-                } = error
-                {
-                    if let Some(status) = reqwest_err.status() {
-                        if status == StatusCode::TOO_MANY_REQUESTS {
-                            self.mark_rate_limited(&client_url);
-
-                            let next_available_client = self.get_available_client(pin);
-                            if next_available_client.is_some() {
-                                return self.execute(op, pin);
-                            }
-
-                            self.sleep_until_available();
-                            return self.execute(op, pin);
-                        }
+            let (endpoint, client, credits) = match self.get_available_client(pin, call) {
+                Some(selected) => selected,
+                None => {
+                    if call.batch && !self.has_batch_endpoint() {
+                        return Err(RpcError::NoBatchEndpoint);
                     }
+
+                    if !self.has_budget_for(call) {
+                        println!(
+                            "{} ERROR: every rpc endpoint has exhausted its credit budget",
+                            log_tag
+                        );
+                        return Err(RpcError::BudgetExhausted(method.to_string()));
+                    }
+
+                    if Instant::now() >= deadline {
+                        println!("{} No client became available before deadline", log_tag);
+                        return Err(RpcError::NoAvailableEndpoint);
+                    }
+
+                    self.sleep_until_available(call, deadline).await;
+                    continue;
                 }
+            };
 
-                Err(RpcError::ClientError(error))
+            attempt += 1;
+            let log_tag = format!(
+                "{} client {} attempt {} | ",
+                log_tag, endpoint.name, attempt
+            );
+
+            println!("{} Sending request...", log_tag);
+            let request_started_at = Instant::now();
+            let error = match operation(client).await {
+                Ok(result) => {
+                    self.record_success(&endpoint, request_started_at.elapsed());
+                    self.record_credit_usage(&endpoint, credits);
+                    return Ok((result, endpoint));
+                }
+                Err(error) => RpcError::from_client_error(error),
+            };
+            let latency = request_started_at.elapsed();
+
+            if error.is_billed() {
+                self.record_credit_usage(&endpoint, credits);
+            } else {
+                self.refund_credits(&endpoint, credits);
+            }
+
+            if let RpcError::RateLimited(_) = error {
+                self.mark_rate_limited(&endpoint);
+            } else if let RpcError::BatchRejected(_) = error {
+                self.disable_batching(&endpoint);
+            } else if error.is_endpoint_failure() {
+                self.record_failure(&endpoint, latency);
+            } else {
+                self.record_success(&endpoint, latency);
+            }
+
+            if !error.is_retryable() {
+                return Err(error);
             }
-        }
-    }
 
-    fn sleep_until_available(&self) {
-        let clients = self.clients.lock().unwrap();
+            if !self.retry_policy.has_attempts_left(attempt) {
+                println!(
+                    "{} Giving up after {} attempts: {}",
+                    log_tag, attempt, error
+                );
+                return Err(error);
+            }
 
-        let rate_limited_until = clients
-            .values()
-            .map(|client| client.rate_limited_until)
-            .min();
+            // a rate limited or rejected batch request can go straight to another endpoint
+            // if one is free
+            let backoff = match error {
+                RpcError::RateLimited(_) | RpcError::BatchRejected(_)
+                    if self.has_available_client(call) =>
+                {
+                    Duration::ZERO
+                }
+                _ => self.retry_policy.backoff(attempt),
+            };
+
+            if Instant::now() + backoff >= deadline {
+                println!("{} Retry deadline exceeded: {}", log_tag, error);
+                return Err(error);
+            }
 
-        if let Some(Some(rate_limited_until)) = rate_limited_until {
             println!(
-                "All clients are rate limited. Waiting for minimum limit duration of {} seconds...",
-                rate_limited_until.duration_since(Instant::now()).as_secs()
+                "{} Retryable error, retrying in {}ms: {}",
+                log_tag,
+                backoff.as_millis(),
+                error
             );
-            sleep(rate_limited_until - Instant::now());
-        } else {
-            let fallback_duration = Duration::from_secs(DEFAULT_RATE_LIMIT_COOLOFF_MS);
+            sleep(backoff).await;
+        }
+    }
+
+    // never past the deadline, so a caller waiting on open circuits still gets its error on time
+    async fn sleep_until_available(&self, call: RpcCall, deadline: Instant) {
+        let available_at = {
+            let mut clients = self.clients.lock().unwrap();
+
+            clients
+                .iter_mut()
+                .filter(|client| !call.batch || client.supports_batch)
+                .filter_map(|client| client.can_afford(call).then_some(client))
+                .map(|client| client.available_at(call))
+                .min()
+        };
+
+        let wait = available_at
+            .map(|available_at| available_at.saturating_duration_since(Instant::now()))
+            .unwrap_or(Duration::from_millis(DEFAULT_RATE_LIMIT_COOLOFF_MS))
+            .min(deadline.saturating_duration_since(Instant::now()));
+
+        if wait >= Duration::from_millis(DEFAULT_RATE_LIMIT_COOLOFF_MS) {
             println!(
-                "All clients are rate limited. Waiting for fall back {} seconds...",
-                fallback_duration.as_secs()
+                "{} All clients are rate limited or unhealthy. Waiting {} ms...",
+                log_time(),
+                wait.as_millis()
             );
-            sleep(fallback_duration);
         }
+
+        sleep(wait).await;
     }
 }
@@ -0,0 +1,48 @@
+use rand::Rng;
+use serde::Deserialize;
+use std::time::Duration;
+
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 8;
+pub const DEFAULT_BASE_DELAY_MS: u64 = 250;
+pub const DEFAULT_MAX_DELAY_MS: u64 = 30_000;
+pub const DEFAULT_DEADLINE_MS: u64 = 5 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub deadline_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay_ms: DEFAULT_BASE_DELAY_MS,
+            max_delay_ms: DEFAULT_MAX_DELAY_MS,
+            deadline_ms: DEFAULT_DEADLINE_MS,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn deadline(&self) -> Duration {
+        Duration::from_millis(self.deadline_ms)
+    }
+
+    pub fn has_attempts_left(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    // full jitter: uniform in [0, min(max_delay, base_delay * 2^attempt)]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self
+            .base_delay_ms
+            .saturating_mul(1u64 << attempt.min(32))
+            .min(self.max_delay_ms);
+
+        Duration::from_millis(rand::thread_rng().gen_range(0..=ceiling))
+    }
+}
@@ -3,7 +3,9 @@ use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
 use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
 use std::str::FromStr;
 
-use crate::crawl_status::queries::{get_oldest_seen_signature, CrawlStatusQueryError};
+use crate::crawl_status::queries::{
+    get_newest_seen_signature, get_oldest_seen_signature, CrawlStatusQueryError,
+};
 
 pub const DEFAULT_SIGNATURES_LIMIT: usize = 1000;
 
@@ -31,3 +33,15 @@ pub fn build_signatures_window_config(
 
     Ok((oldest_signature, limit))
 }
+
+pub fn build_signatures_forward_config(
+    client: &Client,
+    account_address: &str,
+    limit: Option<usize>,
+) -> Result<(Option<String>, usize), CrawlStatusQueryError> {
+    let limit = limit.unwrap_or(DEFAULT_SIGNATURES_LIMIT);
+
+    let newest_signature = get_newest_seen_signature(&client, &account_address)?;
+
+    Ok((newest_signature, limit))
+}
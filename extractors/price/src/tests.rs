@@ -1,9 +1,159 @@
+use crate::anchor::decoder::DecodedInstruction;
+use crate::anchor::decoder::{AnchorDecoder, EVENT_IX_TAG};
+use crate::anchor::hash::{
+    hash_anchor_account_discriminator, hash_anchor_discriminator, hash_anchor_event_discriminator,
+};
+use crate::anchor::value::IdlValue;
+use crate::compute_budget::program::COMPUTE_BUDGET_PROGRAM_ADDRESS;
+use crate::cpi::cpi::CpiLog;
+use crate::crawl_status::inserts::{claim_slot, insert_slot_crawl_status};
+use crate::crawl_status::queries::{get_crawled_slots, newest_succeeded_signature};
+use crate::crawl_status::table::{
+    CrawlStatus, CrawlStatusOperation, SlotCrawlStatusRow, REDIS_CRAWLED_SLOTS_KEY,
+    REDIS_SLOT_CLAIM_PREFIX, REDIS_SLOT_STATUS_PREFIX,
+};
 use crate::db::client::db_client;
+use crate::dragonfly::client::dragonfly_client;
+use crate::geyser::convert::geyser_transaction_from_update;
+use crate::geyser::errors::GeyserError;
+use crate::geyser::stream::stream_geyser_transactions;
+use crate::geyser::threads::{forward_geyser_transaction, BlockTimeCache};
+use crate::instructions::instruction::Instruction;
+use crate::instructions::parse::{InstructionWithLogs, ParsableInstruction};
+use crate::jupiter::cpi::jupiter_cpi_log_from_event_instruction;
+use crate::jupiter::instructions::parse_single_jupiter_instruction;
+use crate::live::errors::LiveLogsError;
+use crate::live::logs::{stream_program_logs, LiveSignature};
+use crate::meteora_dlmm::cpi::meteora_dlmm_cpi_log_from_event_instruction;
+use crate::meteora_dlmm::instructions::parse_single_meteora_dlmm_instruction;
+use crate::orca_whirlpool::instructions::parse_single_orca_whirlpool_instruction;
+use crate::orca_whirlpool::program::get_orca_whirlpool_program_address;
+use crate::orca_whirlpool::state::WHIRLPOOL_SIZE;
+use crate::pools::pool::Pool;
+use crate::pools::state::pool_from_account;
+use crate::pools::table::PoolRow;
+use crate::pools::trades::{resolve_pool_trade, trade_from_pool_swap};
+use crate::pump_fun::cpi::{
+    pump_fun_cpi_log_from_event_data, PumpFunSetParamsEvent, PumpFunTradeEvent,
+};
+use crate::pump_fun::curve::{
+    bonding_curve_progress, CurveParams, INITIAL_REAL_TOKEN_RESERVES,
+    INITIAL_VIRTUAL_TOKEN_RESERVES,
+};
+use crate::pump_fun::events::token_events_from_instructions;
+use crate::pump_fun::idl::pump_fun_decoder;
+use crate::pump_fun::instructions::PumpFunInstruction;
+use crate::pump_fun::program::program::get_pump_fun_program_address;
+use crate::pump_fun::tokens::pump_fun_tokens_from_instructions;
+use crate::pump_swap::instructions::parse_single_pump_swap_instruction;
+use crate::pump_swap::program::PUMP_SWAP_ADDRESS;
+use crate::raydium::amm::get_raydium_amm_program_address;
+use crate::raydium::instructions::{parse_single_raydium_instruction, RaydiumInstruction};
+use crate::raydium::state::{raydium_pool_from_state, AMM_INFO_SIZE};
+use crate::raydium_clmm::cpi::raydium_clmm_cpi_log_from_event_data;
+use crate::raydium_clmm::instructions::parse_single_raydium_clmm_instruction;
+use crate::raydium_cpmm::cpi::raydium_cpmm_cpi_log_from_event_data;
+use crate::raydium_cpmm::instructions::parse_single_raydium_cpmm_instruction;
+use crate::rpc::batch::BATCH_REJECTED_PREFIX;
+use crate::rpc::cache::TransactionCache;
+use crate::rpc::config::{RpcConfig, DEFAULT_PRIORITY, DEFAULT_REQUESTS_PER_SECOND};
+use crate::rpc::credits::{flush_credit_usage, get_credit_usage, CreditBudget, PendingCreditUsage};
+use crate::rpc::errors::{RpcConfigError, RpcError};
+use crate::rpc::health::{
+    CircuitState, EndpointHealth, CIRCUIT_FAILURE_THRESHOLD, HEALTH_EWMA_ALPHA,
+};
+use crate::rpc::pool::{RpcEndpoint, RpcPoolManager};
+use crate::rpc::retry::RetryPolicy;
+use crate::system::program::SYSTEM_PROGRAM_ADDRESS;
+use crate::termination::init as termination_init;
+use crate::token::events::table::TokenEventKind;
+use crate::token::program::{TOKEN_PROGRAM_ADDRESS, USDC_MINT_ADDRESS, WRAPPED_SOL_MINT_ADDRESS};
 use crate::token::queries::has_token;
-use crate::utils::blocking::blocking_call;
+use crate::trades::db::table::{TradeDirection, TradeRow, TradeVenue};
+use crate::trades::trades::{rows_from_instructions, trades_from_instructions};
+use crate::transactions::costs::{transaction_costs, TransactionCosts, JITO_TIP_ADDRESSES};
+use crate::transactions::fetch::get_transactions;
+use crate::transactions::logs::parse_program_logs;
+use crate::transactions::parse::{parse_transaction_with_logs, raw_message};
+use crate::utils::blocking::{blocking_call, runtime};
+use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
+use futures_util::future::join_all;
+use futures_util::{SinkExt, Stream, StreamExt};
+use serde_json::{json, Value};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_custom_error::{
+    JSON_RPC_SERVER_ERROR_BLOCK_CLEANED_UP, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+    JSON_RPC_SERVER_ERROR_SLOT_SKIPPED, JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
+};
+use solana_client::rpc_request::{RpcError as RpcRequestError, RpcRequest, RpcResponseErrorData};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::pin::Pin;
 use std::process::Command;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use time::{Date, Month, OffsetDateTime};
+use tokio::time::sleep;
+use tokio_stream::wrappers::TcpListenerStream;
+use tokio_tungstenite::tungstenite::Message;
+use tonic::{transport::Server, Request, Response, Status, Streaming};
+use yellowstone_grpc_proto::prelude::{
+    geyser_server::{Geyser, GeyserServer},
+    subscribe_update::UpdateOneof,
+    CompiledInstruction, GetBlockHeightRequest, GetBlockHeightResponse, GetLatestBlockhashRequest,
+    GetLatestBlockhashResponse, GetSlotRequest, GetSlotResponse, GetVersionRequest,
+    GetVersionResponse, IsBlockhashValidRequest, IsBlockhashValidResponse,
+    Message as GeyserMessage, MessageHeader, PingRequest, PongResponse, SubscribeRequest,
+    SubscribeUpdate, SubscribeUpdatePing, SubscribeUpdateTransaction,
+    SubscribeUpdateTransactionInfo, Transaction, TransactionStatusMeta,
+};
+
+fn unique_keys(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| Pubkey::new_unique().to_string())
+        .collect()
+}
+
+fn parsable(
+    data: Vec<u8>,
+    accounts: Vec<u8>,
+    instruction_index: usize,
+    inner_instructions: Option<Vec<ParsableInstruction>>,
+) -> ParsableInstruction {
+    ParsableInstruction {
+        data: bs58::encode(data).into_string(),
+        accounts,
+        program_id_index: 0,
+        inner_instructions,
+        instruction_index,
+        inner_instruction_index: None,
+        stack_height: None,
+    }
+}
+
+// emit_cpi! data of a dlmm Swap event on `pool` that swaps y for x
+fn dlmm_swap_event_data(pool: &Pubkey, amount_in: u64, amount_out: u64) -> Vec<u8> {
+    let mut event_data = EVENT_IX_TAG.to_vec();
+    event_data.extend(hash_anchor_event_discriminator("Swap"));
+    event_data.extend(pool.to_bytes());
+    event_data.extend(Pubkey::new_unique().to_bytes());
+    event_data.extend(10i32.to_le_bytes());
+    event_data.extend(12i32.to_le_bytes());
+    event_data.extend(amount_in.to_le_bytes());
+    event_data.extend(amount_out.to_le_bytes());
+    event_data.push(0);
+    event_data.extend(5u64.to_le_bytes());
+    event_data.extend(1u64.to_le_bytes());
+    event_data.extend(25u128.to_le_bytes());
+    event_data.extend(0u64.to_le_bytes());
+    event_data
+}
 
 #[test]
 fn test_specific_token_and_tx() {
@@ -37,3 +187,2949 @@ fn test_specific_token_and_tx() {
 
     println!("test tokens res {:?}", token);
 }
+
+#[test]
+fn test_live_logs_from_websocket_stand_in() {
+    let signature =
+        "5Q6Hn1osCBCWGuNxgHFmBS5NvN6gHJRrXZeDGapdD4VNb82kL6K1tB2VXQBrT92pVn92VZs4nY4vN7CRQ87yckTb";
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let ws_url = format!("ws://{}", listener.local_addr().unwrap());
+    listener.set_nonblocking(true).unwrap();
+
+    // stand-in node: accepts the logsSubscribe, pushes a failed and a successful
+    // notification, then hangs up
+    runtime().spawn(async move {
+        let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        while let Some(Ok(message)) = ws.next().await {
+            let Message::Text(text) = message else {
+                continue;
+            };
+            let request: Value = serde_json::from_str(&text).unwrap();
+            if request["method"] != "logsSubscribe" {
+                continue;
+            }
+
+            let subscribed = json!({"jsonrpc": "2.0", "result": 7, "id": request["id"]});
+            ws.send(Message::Text(subscribed.to_string()))
+                .await
+                .unwrap();
+
+            for (notification_signature, err) in [
+                ("failed", json!({"InstructionError": [0, {"Custom": 1}]})),
+                (signature, Value::Null),
+            ] {
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "logsNotification",
+                    "params": {
+                        "result": {
+                            "context": {"slot": 42},
+                            "value": {"signature": notification_signature, "err": err, "logs": []}
+                        },
+                        "subscription": 7
+                    }
+                });
+                ws.send(Message::Text(notification.to_string()))
+                    .await
+                    .unwrap();
+            }
+
+            ws.close(None).await.unwrap();
+            break;
+        }
+    });
+
+    let mut received = Vec::new();
+    let result = blocking_call(stream_program_logs(
+        &ws_url,
+        &get_pump_fun_program_address(),
+        None,
+        |live_signature| received.push(live_signature),
+    ));
+
+    assert!(matches!(result, Err(LiveLogsError::Disconnected)));
+    assert_eq!(
+        received,
+        vec![LiveSignature {
+            signature: signature.to_string(),
+            slot: 42,
+        }]
+    );
+}
+
+// Replays a fixed set of updates to the first subscriber, then ends the stream.
+struct RecordedGeyser {
+    updates: Vec<SubscribeUpdate>,
+    requests: Arc<Mutex<Vec<SubscribeRequest>>>,
+}
+
+#[tonic::async_trait]
+impl Geyser for RecordedGeyser {
+    type SubscribeStream = Pin<Box<dyn Stream<Item = Result<SubscribeUpdate, Status>> + Send>>;
+
+    async fn subscribe(
+        &self,
+        request: Request<Streaming<SubscribeRequest>>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let mut requests = request.into_inner();
+        if let Some(subscribe_request) = requests.message().await? {
+            self.requests.lock().unwrap().push(subscribe_request);
+        }
+
+        let updates = self.updates.clone().into_iter().map(Ok);
+        Ok(Response::new(Box::pin(futures_util::stream::iter(updates))))
+    }
+
+    async fn ping(&self, _: Request<PingRequest>) -> Result<Response<PongResponse>, Status> {
+        Err(Status::unimplemented("ping"))
+    }
+
+    async fn get_latest_blockhash(
+        &self,
+        _: Request<GetLatestBlockhashRequest>,
+    ) -> Result<Response<GetLatestBlockhashResponse>, Status> {
+        Err(Status::unimplemented("get_latest_blockhash"))
+    }
+
+    async fn get_block_height(
+        &self,
+        _: Request<GetBlockHeightRequest>,
+    ) -> Result<Response<GetBlockHeightResponse>, Status> {
+        Err(Status::unimplemented("get_block_height"))
+    }
+
+    async fn get_slot(
+        &self,
+        _: Request<GetSlotRequest>,
+    ) -> Result<Response<GetSlotResponse>, Status> {
+        Err(Status::unimplemented("get_slot"))
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        _: Request<IsBlockhashValidRequest>,
+    ) -> Result<Response<IsBlockhashValidResponse>, Status> {
+        Err(Status::unimplemented("is_blockhash_valid"))
+    }
+
+    async fn get_version(
+        &self,
+        _: Request<GetVersionRequest>,
+    ) -> Result<Response<GetVersionResponse>, Status> {
+        Err(Status::unimplemented("get_version"))
+    }
+}
+
+// A pump.fun buy as the node streams it: the instruction plus the trade event in the logs.
+fn recorded_pump_fun_buy(slot: u64, mint: &Pubkey, bonding_curve: &Pubkey) -> SubscribeUpdate {
+    let pump_fun_program_address = get_pump_fun_program_address();
+    let user = Pubkey::new_unique();
+    let signature = vec![7u8; 64];
+
+    let mut data = hash_anchor_discriminator("buy").to_vec();
+    data.extend(1_000_000u64.to_le_bytes());
+    data.extend(50_000u64.to_le_bytes());
+
+    let mut event = hash_anchor_event_discriminator("TradeEvent").to_vec();
+    event.extend(mint.to_bytes());
+    event.extend(40_000u64.to_le_bytes());
+    event.extend(1_000_000u64.to_le_bytes());
+    event.push(1);
+    event.extend(user.to_bytes());
+    event.extend(1_700_000_000i64.to_le_bytes());
+    event.extend(30_000_000_000u64.to_le_bytes());
+    event.extend(1_000_000_000_000_000u64.to_le_bytes());
+
+    let account_keys = [user, *mint, *bonding_curve, pump_fun_program_address];
+
+    SubscribeUpdate {
+        filters: vec!["programs".to_string()],
+        update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+            transaction: Some(SubscribeUpdateTransactionInfo {
+                signature: signature.clone(),
+                is_vote: false,
+                transaction: Some(Transaction {
+                    signatures: vec![signature],
+                    message: Some(GeyserMessage {
+                        header: Some(MessageHeader {
+                            num_required_signatures: 1,
+                            num_readonly_signed_accounts: 0,
+                            num_readonly_unsigned_accounts: 1,
+                        }),
+                        account_keys: account_keys
+                            .iter()
+                            .map(|account_key| account_key.to_bytes().to_vec())
+                            .collect(),
+                        recent_blockhash: vec![0u8; 32],
+                        // global, fee recipient, mint, bonding curve, ..., program
+                        instructions: vec![CompiledInstruction {
+                            program_id_index: 3,
+                            accounts: vec![0, 0, 1, 2, 0, 0, 0, 0, 0, 0, 0, 3],
+                            data,
+                        }],
+                        versioned: false,
+                        address_table_lookups: vec![],
+                    }),
+                }),
+                meta: Some(TransactionStatusMeta {
+                    pre_balances: vec![0; account_keys.len()],
+                    post_balances: vec![0; account_keys.len()],
+                    log_messages: vec![
+                        format!("Program {} invoke [1]", pump_fun_program_address),
+                        format!("Program data: {}", base64.encode(event)),
+                        format!("Program {} success", pump_fun_program_address),
+                    ],
+                    ..Default::default()
+                }),
+                index: 0,
+            }),
+            slot,
+        })),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_geyser_stream_from_mock_server() {
+    let mint = Pubkey::new_unique();
+    let bonding_curve = Pubkey::new_unique();
+    let pump_fun_program_address = get_pump_fun_program_address();
+    let raydium_amm_program_address = get_raydium_amm_program_address();
+
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let geyser = RecordedGeyser {
+        updates: vec![
+            SubscribeUpdate {
+                update_oneof: Some(UpdateOneof::Ping(SubscribeUpdatePing {})),
+                ..Default::default()
+            },
+            recorded_pump_fun_buy(42, &mint, &bonding_curve),
+        ],
+        requests: requests.clone(),
+    };
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    listener.set_nonblocking(true).unwrap();
+
+    runtime().spawn(async move {
+        let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+        Server::builder()
+            .add_service(GeyserServer::new(geyser))
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+
+    let mut received = Vec::new();
+    let result = blocking_call(stream_geyser_transactions(
+        &endpoint,
+        None,
+        &[pump_fun_program_address, raydium_amm_program_address],
+        |update| received.push(update),
+    ));
+
+    assert!(matches!(result, Err(GeyserError::Disconnected)));
+
+    let requests = requests.lock().unwrap();
+    let filter = requests[0].transactions.values().next().unwrap();
+    assert_eq!(filter.failed, Some(false));
+    assert_eq!(
+        filter.account_include,
+        vec![
+            pump_fun_program_address.to_string(),
+            raydium_amm_program_address.to_string()
+        ]
+    );
+
+    // the ping is dropped, the buy comes through as parsed instructions
+    assert_eq!(received.len(), 1);
+    let geyser_transaction = geyser_transaction_from_update(
+        received.remove(0),
+        &pump_fun_program_address,
+        &raydium_amm_program_address,
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(geyser_transaction.slot, 42);
+    assert_eq!(
+        geyser_transaction.signature,
+        bs58::encode([7u8; 64]).into_string()
+    );
+    assert_eq!(
+        pump_fun_tokens_from_instructions(&geyser_transaction.instructions_with_logs),
+        vec![(mint, bonding_curve)]
+    );
+
+    let trades = trades_from_instructions(
+        &geyser_transaction.instructions_with_logs,
+        geyser_transaction.slot,
+        1_700_000_000,
+        &geyser_transaction.signature,
+    )
+    .unwrap();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].coin_token_address, mint.to_string());
+    assert_eq!(trades[0].coin_token_amount, 1_000_000);
+    assert_eq!(trades[0].price_coin_token_amount, 40_000);
+}
+
+// Answers every JSON-RPC request with `result` and counts the requests it was sent.
+fn serve_rpc_stand_in(result: Value) -> (String, Arc<AtomicU32>) {
+    serve_rpc_stand_in_with(move |request| {
+        (
+            200,
+            json!({"jsonrpc": "2.0", "result": result, "id": request["id"]}),
+        )
+    })
+}
+
+// Answers every JSON-RPC request or batch with the status and body `respond` returns for it.
+fn serve_rpc_stand_in_with<F>(respond: F) -> (String, Arc<AtomicU32>)
+where
+    F: Fn(&Value) -> (u16, Value) + Send + 'static,
+{
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicU32::new(0));
+    let served = requests.clone();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+
+            // the headers, then as much of the body as they announce
+            let body_start = loop {
+                let read = stream.read(&mut buffer).unwrap();
+                assert!(read > 0, "connection closed mid request");
+                request.extend_from_slice(&buffer[..read]);
+
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|length| length.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= header_end + 4 + content_length {
+                        break header_end + 4;
+                    }
+                }
+            };
+
+            let request: Value = serde_json::from_slice(&request[body_start..]).unwrap();
+            served.fetch_add(1, Ordering::SeqCst);
+
+            let (status, body) = respond(&request);
+            let body = body.to_string();
+            let response = format!(
+                "HTTP/1.1 {} Stand In\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (url, requests)
+}
+
+#[test]
+fn test_geyser_transaction_is_forwarded_with_its_slot_block_time() {
+    let mint = Pubkey::new_unique();
+    let bonding_curve = Pubkey::new_unique();
+    let pump_fun_program_address = get_pump_fun_program_address();
+    let raydium_amm_program_address = get_raydium_amm_program_address();
+
+    let (url, rpc_requests) = serve_rpc_stand_in(json!(1_700_000_123));
+    let config = RpcConfig::parse(&format!(
+        r#"
+cluster = "mainnet"
+
+[[endpoints]]
+name = "geyser-block-time-test"
+cluster = "mainnet"
+url = "{}"
+requests_per_second = 1000
+"#,
+        url
+    ))
+    .unwrap();
+    let dragonfly = redis::Client::open("redis://127.0.0.1:1/").unwrap();
+    let rpc_pool_manager = RpcPoolManager::new(&config, Duration::from_millis(10), &dragonfly);
+
+    let (pump_fun_tokens_tx, pump_fun_tokens_rx) = crossbeam::channel::unbounded();
+    let (trades_tx, trades_rx) = crossbeam::channel::unbounded();
+    let (unresolved_trades_tx, _unresolved_trades_rx) = crossbeam::channel::unbounded();
+    let (token_events_tx, _token_events_rx) = crossbeam::channel::unbounded();
+    let (pools_tx, _pools_rx) = crossbeam::channel::unbounded();
+    let (jupiter_routes_tx, _jupiter_routes_rx) = crossbeam::channel::unbounded();
+    let (curve_params_tx, _curve_params_rx) = crossbeam::channel::unbounded();
+    let (crawl_status_tx, crawl_status_rx) = crossbeam::channel::unbounded();
+    let termination_flag = termination_init();
+
+    let mut seen_tokens = HashSet::new();
+    let mut block_time_cache = BlockTimeCache::new();
+
+    // forwarded from a plain thread, as the geyser forwarding thread does, so the block time
+    // lookup blocks on the shared runtime without nesting in it
+    for _ in 0..2 {
+        let update = match recorded_pump_fun_buy(42, &mint, &bonding_curve).update_oneof {
+            Some(UpdateOneof::Transaction(update)) => update,
+            other => panic!("expected a transaction update, got {:?}", other),
+        };
+        let geyser_transaction = geyser_transaction_from_update(
+            update,
+            &pump_fun_program_address,
+            &raydium_amm_program_address,
+        )
+        .unwrap()
+        .unwrap();
+
+        forward_geyser_transaction(
+            geyser_transaction,
+            &pump_fun_tokens_tx,
+            &trades_tx,
+            &unresolved_trades_tx,
+            &token_events_tx,
+            &pools_tx,
+            &jupiter_routes_tx,
+            &curve_params_tx,
+            &crawl_status_tx,
+            &mut seen_tokens,
+            &mut block_time_cache,
+            &rpc_pool_manager,
+            &termination_flag,
+        )
+        .unwrap();
+    }
+
+    // the block time is looked up once for the slot and the token is only sent once
+    assert_eq!(rpc_requests.load(Ordering::SeqCst), 1);
+    assert_eq!(block_time_cache.get(&42), Some(&1_700_000_123));
+    assert_eq!(
+        pump_fun_tokens_rx.try_iter().collect::<Vec<_>>(),
+        vec![(mint, bonding_curve)]
+    );
+
+    let trades = trades_rx.try_iter().collect::<Vec<TradeRow>>();
+    assert_eq!(trades.len(), 2);
+    for trade in trades {
+        assert_eq!(trade.slot, 42);
+        assert_eq!(
+            trade.block_time,
+            OffsetDateTime::from_unix_timestamp(1_700_000_123).unwrap()
+        );
+        assert_eq!(trade.coin_token_address, mint.to_string());
+    }
+
+    let crawl_statuses = crawl_status_rx.try_iter().collect::<Vec<_>>();
+    assert_eq!(crawl_statuses.len(), 2);
+    assert!(crawl_statuses.iter().all(|crawl_status| matches!(
+        crawl_status,
+        CrawlStatusOperation::MarkAsSucceeded(signature)
+            if *signature == bs58::encode([7u8; 64]).into_string()
+    )));
+}
+
+#[test]
+fn test_v0_raydium_swap_resolves_loaded_addresses() {
+    let pump_fun_program_address = get_pump_fun_program_address();
+    let raydium_amm_program_address = get_raydium_amm_program_address();
+
+    let user = Pubkey::new_unique().to_string();
+    let amm = Pubkey::new_unique().to_string();
+    let coin_vault = Pubkey::new_unique().to_string();
+    let pc_vault = Pubkey::new_unique().to_string();
+    let user_source = Pubkey::new_unique().to_string();
+    let user_destination = Pubkey::new_unique().to_string();
+    let amm_authority = Pubkey::new_unique().to_string();
+
+    let mut swap_data = vec![9u8];
+    swap_data.extend(500u64.to_le_bytes());
+    swap_data.extend(1_900u64.to_le_bytes());
+    let transfer_data = |amount: u64| {
+        let mut data = vec![3u8];
+        data.extend(amount.to_le_bytes());
+        bs58::encode(data).into_string()
+    };
+
+    // static keys: user, raydium, token program (0..=2)
+    // loaded writable: amm, coin vault, pc vault, user source, user destination (3..=7)
+    // loaded readonly: amm authority (8)
+    let tx: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_value(json!({
+        "slot": 42,
+        "blockTime": 1_700_000_000,
+        "transaction": {
+            "version": 0,
+            "transaction": {
+                "signatures": [bs58::encode([7u8; 64]).into_string()],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 2
+                    },
+                    "accountKeys": [user, raydium_amm_program_address.to_string(), TOKEN_PROGRAM_ADDRESS],
+                    "recentBlockhash": bs58::encode([0u8; 32]).into_string(),
+                    "instructions": [{
+                        "programIdIndex": 1,
+                        "accounts": [2, 3, 8, 3, 3, 4, 5, 3, 3, 3, 3, 3, 3, 3, 3, 6, 7, 0],
+                        "data": bs58::encode(swap_data).into_string(),
+                        "stackHeight": null
+                    }],
+                    "addressTableLookups": [{
+                        "accountKey": Pubkey::new_unique().to_string(),
+                        "writableIndexes": [0, 1, 2, 3, 4],
+                        "readonlyIndexes": [5]
+                    }]
+                }
+            },
+            "meta": {
+                "err": null,
+                "status": {"Ok": null},
+                "fee": 5000,
+                "preBalances": [0, 0, 0, 0, 0, 0, 0, 0, 0],
+                "postBalances": [0, 0, 0, 0, 0, 0, 0, 0, 0],
+                "innerInstructions": [{
+                    "index": 0,
+                    "instructions": [
+                        {"programIdIndex": 2, "accounts": [6, 4, 0], "data": transfer_data(500), "stackHeight": 2},
+                        {"programIdIndex": 2, "accounts": [5, 7, 8], "data": transfer_data(2_000), "stackHeight": 2}
+                    ]
+                }],
+                "logMessages": [
+                    format!("Program {} invoke [1]", raydium_amm_program_address),
+                    format!("Program {} invoke [2]", TOKEN_PROGRAM_ADDRESS),
+                    format!("Program {} success", TOKEN_PROGRAM_ADDRESS),
+                    format!("Program {} invoke [2]", TOKEN_PROGRAM_ADDRESS),
+                    format!("Program {} success", TOKEN_PROGRAM_ADDRESS),
+                    format!("Program {} success", raydium_amm_program_address)
+                ],
+                "loadedAddresses": {
+                    "writable": [amm, &coin_vault, &pc_vault, user_source, user_destination],
+                    "readonly": [amm_authority]
+                }
+            }
+        }
+    }))
+    .unwrap();
+
+    let (_signature, message) = raw_message(&tx.transaction.transaction).unwrap();
+    let instructions_with_logs = parse_transaction_with_logs(
+        &tx.transaction.meta,
+        message,
+        &pump_fun_program_address,
+        &raydium_amm_program_address,
+    );
+
+    let swap =
+        instructions_with_logs.iter().find_map(
+            |instruction_with_logs| match &instruction_with_logs.instruction {
+                Some(Instruction::Raydium(_, RaydiumInstruction::SwapBaseIn((_, swap)))) => {
+                    Some(*swap)
+                }
+                _ => None,
+            },
+        );
+
+    let swap = swap.unwrap();
+    assert_eq!(swap.pool.to_string(), amm);
+    assert_eq!(swap.coin_vault.to_string(), coin_vault);
+    assert_eq!(swap.pc_vault.to_string(), pc_vault);
+    assert_eq!((swap.coin_amount, swap.pc_amount), (500, 2_000));
+    assert_eq!(swap.direction, TradeDirection::Sell);
+}
+
+#[test]
+fn test_program_logs_build_invocation_tree() {
+    let aggregator = Pubkey::new_unique().to_string();
+    let pump_fun = get_pump_fun_program_address().to_string();
+    let logs = [
+        format!("Program {} invoke [1]", aggregator),
+        // program output that looks like invokes and results
+        "Program log: Program fake invoke [1]".to_string(),
+        "Program log: success".to_string(),
+        format!("Program {} invoke [2]", pump_fun),
+        "Program log: Instruction: Buy".to_string(),
+        format!("Program {} invoke [3]", TOKEN_PROGRAM_ADDRESS),
+        format!(
+            "Program {} consumed 4645 of 180000 compute units",
+            TOKEN_PROGRAM_ADDRESS
+        ),
+        format!("Program {} success", TOKEN_PROGRAM_ADDRESS),
+        "Program data: vdt/007mYe4=".to_string(),
+        format!(
+            "Program {} consumed 30000 of 190000 compute units",
+            pump_fun
+        ),
+        format!("Program {} success", pump_fun),
+        format!("Program return: {} AQID", aggregator),
+        format!("Program {} failed: custom program error: 0x1", aggregator),
+        "Program ComputeBudget111111111111111111111111111111 invoke [1]".to_string(),
+        "Log truncated".to_string(),
+    ];
+
+    let invocations = parse_program_logs(&logs);
+    assert_eq!(invocations.len(), 2);
+
+    let aggregator_invocation = &invocations[0];
+    assert_eq!(aggregator_invocation.program_id, aggregator);
+    assert_eq!(aggregator_invocation.succeeded, Some(false));
+    assert_eq!(aggregator_invocation.return_data.as_deref(), Some("AQID"));
+    assert_eq!(
+        aggregator_invocation.logs,
+        vec!["Program fake invoke [1]", "success"]
+    );
+    assert!(aggregator_invocation.data.is_empty());
+
+    // pre-order, the same order as the inner instructions
+    let descendants = aggregator_invocation.descendants();
+    assert_eq!(descendants.len(), 2);
+    assert_eq!(descendants[0].program_id, pump_fun);
+    assert_eq!(descendants[0].depth, 2);
+    assert_eq!(descendants[0].data, vec!["vdt/007mYe4="]);
+    assert_eq!(descendants[0].compute_units_consumed, Some(30_000));
+    assert_eq!(descendants[1].program_id, TOKEN_PROGRAM_ADDRESS);
+    assert_eq!(descendants[1].compute_units_consumed, Some(4_645));
+    assert_eq!(descendants[1].succeeded, Some(true));
+
+    // the compute budget invocation never got a result line
+    assert_eq!(invocations[1].succeeded, None);
+}
+
+#[test]
+fn test_anchor_idl_decodes_instructions_and_events() {
+    let decoder = pump_fun_decoder();
+    let mint = Pubkey::new_unique();
+    let bonding_curve = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+
+    let mut data = hash_anchor_discriminator("buy").to_vec();
+    data.extend(1_000_000u64.to_le_bytes());
+    data.extend(50_000u64.to_le_bytes());
+    let accounts: Vec<String> = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        mint,
+        bonding_curve,
+    ]
+    .iter()
+    .map(|account| account.to_string())
+    .collect();
+
+    let buy = decoder
+        .decode_instruction(&data, &accounts)
+        .unwrap()
+        .unwrap();
+    assert_eq!(buy.name, "buy");
+    assert_eq!(buy.account("mint"), Some(mint.to_string().as_str()));
+    assert_eq!(
+        buy.account("bonding_curve"),
+        Some(bonding_curve.to_string().as_str())
+    );
+    // fewer accounts than the IDL lists
+    assert_eq!(buy.account("user"), None);
+    // args cut short
+    assert!(decoder.decode_instruction(&data[..12], &accounts).is_err());
+
+    let mut event = hash_anchor_event_discriminator("TradeEvent").to_vec();
+    event.extend(mint.to_bytes());
+    event.extend(40_000u64.to_le_bytes());
+    event.extend(1_000_000u64.to_le_bytes());
+    event.push(0);
+    event.extend(user.to_bytes());
+    event.extend((-5i64).to_le_bytes());
+    event.extend(30_000_000_000u64.to_le_bytes());
+    event.extend(1_000_000_000_000_000u64.to_le_bytes());
+
+    let trade = decoder.decode_event(&event).unwrap().unwrap();
+    assert_eq!(trade.name, "TradeEvent");
+    assert_eq!(trade.fields.get("mint").unwrap().as_pubkey(), Some(mint));
+    assert_eq!(trade.fields.get("is_buy").unwrap().as_bool(), Some(false));
+    assert_eq!(trade.fields.get("timestamp").unwrap().as_i64(), Some(-5));
+
+    assert!(decoder.decode_event(&event[..40]).is_err());
+    assert!(decoder.decode_event(&[0u8; 16]).unwrap().is_none());
+
+    // 0.30 layout: explicit discriminators and events described in types
+    let decoder = AnchorDecoder::from_json(
+        &json!({
+            "address": "11111111111111111111111111111111",
+            "metadata": { "name": "example", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [{
+                "name": "set_side",
+                "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                "accounts": [
+                    { "name": "pool", "accounts": [{ "name": "state" }, { "name": "vault" }] },
+                    { "name": "owner", "signer": true }
+                ],
+                "args": [
+                    { "name": "side", "type": { "defined": { "name": "Side" } } },
+                    { "name": "limit", "type": { "option": "u16" } }
+                ]
+            }],
+            "events": [{ "name": "SideSet", "discriminator": [8, 7, 6, 5, 4, 3, 2, 1] }],
+            "types": [
+                {
+                    "name": "Side",
+                    "type": { "kind": "enum", "variants": [{ "name": "Bid" }, { "name": "Ask", "fields": ["u8"] }] }
+                },
+                {
+                    "name": "SideSet",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "label", "type": "string" },
+                            { "name": "side", "type": { "defined": { "name": "Side" } } },
+                            { "name": "limit", "type": { "option": "u16" } }
+                        ]
+                    }
+                }
+            ]
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let data = [1, 2, 3, 4, 5, 6, 7, 8, 1, 9, 1, 0xff, 0xff];
+    let accounts = vec![
+        "state".to_string(),
+        "vault".to_string(),
+        "owner".to_string(),
+    ];
+    let set_side = decoder
+        .decode_instruction(&data, &accounts)
+        .unwrap()
+        .unwrap();
+    assert_eq!(set_side.account("vault"), Some("vault"));
+    assert_eq!(set_side.account("owner"), Some("owner"));
+
+    // an enum variant the IDL does not have
+    let mut data = data.to_vec();
+    data[8] = 2;
+    assert!(decoder.decode_instruction(&data, &accounts).is_err());
+
+    let mut event = vec![8, 7, 6, 5, 4, 3, 2, 1];
+    event.extend(2u32.to_le_bytes());
+    event.extend(b"ok");
+    event.extend([1, 9, 1, 0xff, 0xff]);
+    let side_set = decoder.decode_event(&event).unwrap().unwrap();
+    assert_eq!(side_set.fields.get("label").unwrap().as_str(), Some("ok"));
+    assert_eq!(
+        side_set.fields.get("side"),
+        Some(&IdlValue::Enum(
+            "Ask".to_string(),
+            Some(Box::new(IdlValue::Tuple(vec![IdlValue::Unsigned(9)])))
+        ))
+    );
+    assert_eq!(
+        side_set.fields.get("limit"),
+        Some(&IdlValue::Option(Some(Box::new(IdlValue::Unsigned(
+            65_535
+        )))))
+    );
+}
+
+#[test]
+fn test_pump_fun_events_are_checked_by_discriminator() {
+    let mint = Pubkey::new_unique();
+    let bonding_curve = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+
+    let mut create = hash_anchor_event_discriminator("CreateEvent").to_vec();
+    for text in ["Moneybags", "MB", "https://example.com/mb.json"] {
+        create.extend((text.len() as u32).to_le_bytes());
+        create.extend(text.as_bytes());
+    }
+    create.extend(mint.to_bytes());
+    create.extend(bonding_curve.to_bytes());
+    create.extend(user.to_bytes());
+
+    let mut complete = hash_anchor_event_discriminator("CompleteEvent").to_vec();
+    complete.extend(user.to_bytes());
+    complete.extend(mint.to_bytes());
+    complete.extend(bonding_curve.to_bytes());
+    complete.extend(1_700_000_000i64.to_le_bytes());
+
+    // a trade event body behind an unknown discriminator used to be read as a trade
+    let mut unknown = vec![0u8; 8];
+    unknown.extend(mint.to_bytes());
+    unknown.extend([0u8; 89]);
+    assert!(pump_fun_cpi_log_from_event_data(&base64.encode(unknown)).is_none());
+
+    let cpi_logs = [create, complete]
+        .into_iter()
+        .map(|event| pump_fun_cpi_log_from_event_data(&base64.encode(event)).unwrap())
+        .collect::<Vec<_>>();
+    match &cpi_logs[0] {
+        CpiLog::PumpFunCreate(create_event) => {
+            assert_eq!(create_event.symbol, "MB");
+            assert_eq!(create_event.bonding_curve, bonding_curve);
+        }
+        other => panic!("Expected a create event, got {:?}", other),
+    }
+    assert!(
+        matches!(&cpi_logs[1], CpiLog::PumpFunComplete(complete_event) if complete_event.mint == mint)
+    );
+
+    let instructions_with_logs = [InstructionWithLogs {
+        instruction: None,
+        cpi_logs,
+    }];
+    let token_events =
+        token_events_from_instructions(&instructions_with_logs, 42, 1_700_000_000, "sig").unwrap();
+    assert_eq!(token_events.len(), 2);
+    assert_eq!(token_events[0].kind, TokenEventKind::Create);
+    assert_eq!(token_events[0].name, "Moneybags");
+    assert_eq!(token_events[0].uri, "https://example.com/mb.json");
+    assert_eq!(token_events[1].kind, TokenEventKind::Complete);
+    assert_eq!(token_events[1].mint_address, mint.to_string());
+    assert_eq!(token_events[1].user_address, user.to_string());
+    assert_eq!(token_events[1].block_time.unix_timestamp(), 1_700_000_000);
+}
+
+#[test]
+fn test_pump_fun_migration_links_raydium_pool() {
+    let mint = Pubkey::new_unique();
+    let bonding_curve = Pubkey::new_unique();
+    let migration_account = Pubkey::new_unique();
+    let pool = Pubkey::new_unique();
+    let coin_vault = Pubkey::new_unique();
+    let pc_vault = Pubkey::new_unique();
+
+    // global, last withdraw, mint, bonding curve, associated bonding curve, associated user, user
+    let withdraw_accounts = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        mint,
+        bonding_curve,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        migration_account,
+    ]
+    .iter()
+    .map(|account| account.to_string())
+    .collect::<Vec<_>>();
+    let withdraw = pump_fun_decoder()
+        .decode_instruction(&hash_anchor_discriminator("withdraw"), &withdraw_accounts)
+        .unwrap()
+        .unwrap();
+
+    // amm at 4, coin and pc mints at 8 and 9, their vaults at 10 and 11
+    let mut account_keys = unique_keys(21);
+    account_keys[4] = pool.to_string();
+    account_keys[8] = WRAPPED_SOL_MINT_ADDRESS.to_string();
+    account_keys[9] = mint.to_string();
+    account_keys[10] = coin_vault.to_string();
+    account_keys[11] = pc_vault.to_string();
+    account_keys.push(get_raydium_amm_program_address().to_string());
+
+    let mut data = vec![1u8, 254];
+    data.extend(0u64.to_le_bytes());
+    data.extend(79_005_359_147u64.to_le_bytes());
+    data.extend(206_900_000_000_000u64.to_le_bytes());
+    let initialize2 = ParsableInstruction {
+        data: bs58::encode(data).into_string(),
+        accounts: (0..21).collect(),
+        program_id_index: 21,
+        inner_instructions: None,
+        instruction_index: 1,
+        inner_instruction_index: None,
+        stack_height: None,
+    };
+
+    let instructions_with_logs = [
+        InstructionWithLogs {
+            instruction: Some(Instruction::PumpFun(
+                0,
+                PumpFunInstruction::Withdraw((withdraw, (mint, bonding_curve))),
+            )),
+            cpi_logs: vec![],
+        },
+        InstructionWithLogs {
+            instruction: parse_single_raydium_instruction(&initialize2, &account_keys, 1, &vec![]),
+            cpi_logs: vec![],
+        },
+    ];
+
+    let rows = rows_from_instructions(
+        &instructions_with_logs,
+        &TransactionCosts::default(),
+        42,
+        1_700_000_000,
+        "sig",
+    )
+    .unwrap();
+    assert!(rows.trades.is_empty());
+
+    assert_eq!(rows.pools.len(), 1);
+    let raydium_pool = &rows.pools[0];
+    assert_eq!(raydium_pool.pool_address, pool.to_string());
+    assert_eq!(raydium_pool.mint_address, mint.to_string());
+    assert_eq!(raydium_pool.coin_mint_address, WRAPPED_SOL_MINT_ADDRESS);
+    assert_eq!(raydium_pool.coin_vault_address, coin_vault.to_string());
+    assert_eq!(raydium_pool.pc_vault_address, pc_vault.to_string());
+
+    assert_eq!(rows.token_events.len(), 1);
+    let migration = &rows.token_events[0];
+    assert_eq!(migration.kind, TokenEventKind::Migrate);
+    assert_eq!(migration.mint_address, mint.to_string());
+    assert_eq!(migration.pool_address, pool.to_string());
+    assert_eq!(migration.user_address, migration_account.to_string());
+    assert_eq!(migration.slot, 42);
+}
+
+#[test]
+fn test_raydium_pool_state_resolves_mints() {
+    let pool = Pubkey::new_unique();
+    let coin_vault = Pubkey::new_unique();
+    let pc_vault = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let wrapped_sol = Pubkey::from_str(WRAPPED_SOL_MINT_ADDRESS).unwrap();
+
+    let mut data = vec![0u8; AMM_INFO_SIZE];
+    data[336..368].copy_from_slice(coin_vault.as_ref());
+    data[368..400].copy_from_slice(pc_vault.as_ref());
+    data[400..432].copy_from_slice(mint.as_ref());
+    data[432..464].copy_from_slice(wrapped_sol.as_ref());
+
+    let raydium_pool = raydium_pool_from_state(&pool, &data).unwrap();
+    let row = PoolRow::from_pool(&raydium_pool, TradeVenue::RaydiumAmm);
+    assert_eq!(row.pool_address, pool.to_string());
+    assert_eq!(row.mint_address, mint.to_string());
+    assert_eq!(row.coin_mint_address, mint.to_string());
+    assert_eq!(row.pc_mint_address, WRAPPED_SOL_MINT_ADDRESS);
+    assert_eq!(row.coin_vault_address, coin_vault.to_string());
+    assert_eq!(row.pc_vault_address, pc_vault.to_string());
+
+    // anything but a v4 pool is rejected rather than read at the wrong offsets
+    assert!(raydium_pool_from_state(&pool, &data[..AMM_INFO_SIZE - 1]).is_none());
+}
+
+#[test]
+fn test_raydium_swap_direction_follows_vault_transfers() {
+    // user, raydium, token program, amm, authority, open orders, target orders, coin vault,
+    // pc vault, serum, user coin, user pc, fee account, system program, pc mint
+    let mut account_keys = unique_keys(15);
+    account_keys[1] = get_raydium_amm_program_address().to_string();
+    account_keys[2] = TOKEN_PROGRAM_ADDRESS.to_string();
+    account_keys[13] = SYSTEM_PROGRAM_ADDRESS.to_string();
+
+    let inner = |program_id_index: usize, accounts: Vec<u8>, data: Vec<u8>, stack_height| {
+        ParsableInstruction {
+            data: bs58::encode(data).into_string(),
+            accounts,
+            program_id_index,
+            inner_instructions: None,
+            instruction_index: 0,
+            inner_instruction_index: None,
+            stack_height,
+        }
+    };
+    let transfer = |source: u8, destination: u8, amount: u64| {
+        let mut data = vec![3u8];
+        data.extend(amount.to_le_bytes());
+        inner(2, vec![source, destination, 0], data, Some(2))
+    };
+    let transfer_checked = |source: u8, destination: u8, amount: u64| {
+        let mut data = vec![12u8];
+        data.extend(amount.to_le_bytes());
+        data.push(9);
+        inner(2, vec![source, 14, destination, 0], data, Some(2))
+    };
+    let wrap_sol = |amount: u64| {
+        let mut data = vec![2u8, 0, 0, 0];
+        data.extend(amount.to_le_bytes());
+        inner(13, vec![0, 11], data, Some(2))
+    };
+    let swap = |tag: u8, inner_instructions: Vec<ParsableInstruction>| {
+        let mut data = vec![tag];
+        data.extend(500u64.to_le_bytes());
+        data.extend(2_000u64.to_le_bytes());
+        ParsableInstruction {
+            data: bs58::encode(data).into_string(),
+            accounts: vec![2, 3, 4, 5, 6, 7, 8, 9, 9, 9, 9, 9, 9, 9, 9, 10, 11, 0],
+            program_id_index: 1,
+            inner_instructions: Some(inner_instructions),
+            instruction_index: 0,
+            inner_instruction_index: None,
+            stack_height: None,
+        }
+    };
+    let parse = |parsable: &ParsableInstruction, inner_instructions: &Vec<ParsableInstruction>| {
+        match parse_single_raydium_instruction(parsable, &account_keys, 0, inner_instructions) {
+            Some(Instruction::Raydium(_, RaydiumInstruction::SwapBaseIn((_, swap))))
+            | Some(Instruction::Raydium(_, RaydiumInstruction::SwapBaseOut((_, swap)))) => {
+                (swap.coin_amount, swap.pc_amount, swap.direction)
+            }
+            _ => panic!("swap was not parsed"),
+        }
+    };
+
+    // swap base in, paying pc for coin and coin for pc
+    let buy = swap(9, vec![transfer(11, 8, 2_000), transfer(7, 10, 500)]);
+    assert_eq!(parse(&buy, &vec![]), (500, 2_000, TradeDirection::Buy));
+    let sell = swap(9, vec![transfer(10, 7, 500), transfer(8, 11, 2_000)]);
+    assert_eq!(parse(&sell, &vec![]), (500, 2_000, TradeDirection::Sell));
+
+    // swap base out, around a fee, a SOL wrap and token-2022 transfers
+    let buy = swap(
+        11,
+        vec![
+            transfer(11, 12, 30),
+            wrap_sol(2_000),
+            transfer_checked(11, 8, 2_000),
+            transfer(7, 10, 500),
+        ],
+    );
+    assert_eq!(parse(&buy, &vec![]), (500, 2_000, TradeDirection::Buy));
+    let sell = swap(
+        11,
+        vec![transfer(10, 7, 500), transfer_checked(8, 11, 2_000)],
+    );
+    assert_eq!(parse(&sell, &vec![]), (500, 2_000, TradeDirection::Sell));
+
+    // an inner swap only counts the transfers below it, not the caller's next transfer
+    let mut inner_swap = swap(9, vec![]);
+    inner_swap.inner_instructions = None;
+    inner_swap.inner_instruction_index = Some(0);
+    inner_swap.stack_height = Some(2);
+    let mut swap_transfers = vec![transfer(11, 8, 2_000), transfer(7, 10, 500)];
+    swap_transfers
+        .iter_mut()
+        .for_each(|transfer| transfer.stack_height = Some(3));
+    let mut parent_instructions = vec![inner_swap.clone()];
+    parent_instructions.extend(swap_transfers);
+    parent_instructions.push(transfer(10, 7, 499));
+    assert_eq!(
+        parse(&inner_swap, &parent_instructions),
+        (500, 2_000, TradeDirection::Buy)
+    );
+}
+
+#[test]
+fn test_raydium_trade_is_rebased_on_the_pool_token() {
+    let mint = Pubkey::new_unique().to_string();
+    let pool_address = Pubkey::new_unique().to_string();
+    let trade = TradeRow {
+        coin_token_address: String::new(),
+        price_coin_token_address: String::new(),
+        transaction_signature: "sig".to_string(),
+        slot: 42,
+        instruction_index: 0,
+        trade_ordinal: 0,
+        block_time: OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap(),
+        coin_token_amount: 500,
+        price_coin_token_amount: 2_000,
+        direction: TradeDirection::Buy,
+        pool_address: pool_address.clone(),
+        venue: TradeVenue::RaydiumAmm,
+        route_id: None,
+        route_hop_index: None,
+        trader_address: Pubkey::new_unique().to_string(),
+        costs: TransactionCosts::default(),
+        virtual_sol_reserves: None,
+        virtual_token_reserves: None,
+        bonding_curve_progress: None,
+    };
+    let raydium_pool = |coin_mint_address: &str, pc_mint_address: &str| PoolRow {
+        pool_address: pool_address.clone(),
+        mint_address: mint.clone(),
+        coin_mint_address: coin_mint_address.to_string(),
+        pc_mint_address: pc_mint_address.to_string(),
+        coin_vault_address: String::new(),
+        pc_vault_address: String::new(),
+        venue: TradeVenue::RaydiumAmm,
+    };
+
+    let resolved = resolve_pool_trade(
+        trade.clone(),
+        &raydium_pool(&mint, WRAPPED_SOL_MINT_ADDRESS),
+    );
+    assert_eq!(resolved.coin_token_address, mint);
+    assert_eq!(resolved.price_coin_token_address, WRAPPED_SOL_MINT_ADDRESS);
+    assert_eq!(resolved.coin_token_amount, 500);
+    assert_eq!(resolved.direction, TradeDirection::Buy);
+
+    // wrapped SOL left the pool, so the token was sold into it
+    let resolved = resolve_pool_trade(trade, &raydium_pool(WRAPPED_SOL_MINT_ADDRESS, &mint));
+    assert_eq!(resolved.coin_token_address, mint);
+    assert_eq!(resolved.price_coin_token_address, WRAPPED_SOL_MINT_ADDRESS);
+    assert_eq!(resolved.coin_token_amount, 2_000);
+    assert_eq!(resolved.price_coin_token_amount, 500);
+    assert_eq!(resolved.direction, TradeDirection::Sell);
+}
+
+#[test]
+fn test_raydium_cpmm_and_clmm_swaps_become_trades() {
+    let mint = Pubkey::new_unique();
+    let wrapped_sol = Pubkey::from_str(WRAPPED_SOL_MINT_ADDRESS).unwrap();
+    let pool = Pubkey::new_unique();
+    let account_keys = unique_keys(13)
+        .into_iter()
+        .chain([
+            pool.to_string(),
+            mint.to_string(),
+            WRAPPED_SOL_MINT_ADDRESS.to_string(),
+        ])
+        .collect::<Vec<_>>();
+    let (pool_index, mint_index, wrapped_sol_index) = (13u8, 14u8, 15u8);
+
+    let event_data = |fields: Vec<u8>| {
+        let mut data = hash_anchor_event_discriminator("SwapEvent").to_vec();
+        data.extend(fields);
+        format!("Program data: {}", base64.encode(data))
+    };
+
+    // cpmm swap base input, paying 2_000 lamports of wrapped SOL for 500 of the token
+    let mut data = hash_anchor_discriminator("swap_base_input").to_vec();
+    data.extend(2_000u64.to_le_bytes());
+    data.extend(400u64.to_le_bytes());
+    let mut accounts = vec![0, 1, 2, pool_index, 4, 5, 6, 7, 8, 9];
+    accounts.extend([wrapped_sol_index, mint_index, 12]);
+    let cpmm_swap =
+        parse_single_raydium_cpmm_instruction(&parsable(data, accounts, 0, None), &account_keys, 0);
+
+    let mut fields = pool.to_bytes().to_vec();
+    [1_000_000u64, 4_000_000, 2_000, 500, 0, 0]
+        .iter()
+        .for_each(|amount| fields.extend(amount.to_le_bytes()));
+    fields.push(1);
+    let cpmm_logs = raydium_cpmm_cpi_log_from_event_data(&event_data(fields))
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    // clmm swaps selling 500 of the token for 2_000 lamports, one of them without mints
+    let clmm_event = |zero_for_one: bool| {
+        let (amount_0, amount_1) = if mint < wrapped_sol {
+            (500u64, 2_000u64)
+        } else {
+            (2_000, 500)
+        };
+        let mut fields = pool.to_bytes().to_vec();
+        (0..3).for_each(|_| fields.extend(Pubkey::new_unique().to_bytes()));
+        [amount_0, 0, amount_1, 0]
+            .iter()
+            .for_each(|amount| fields.extend(amount.to_le_bytes()));
+        fields.push(zero_for_one as u8);
+        fields.extend(1u128.to_le_bytes());
+        fields.extend(1u128.to_le_bytes());
+        fields.extend((-10i32).to_le_bytes());
+        raydium_clmm_cpi_log_from_event_data(&event_data(fields))
+            .into_iter()
+            .collect::<Vec<_>>()
+    };
+    let clmm_args = || {
+        let mut args = 500u64.to_le_bytes().to_vec();
+        args.extend(1_900u64.to_le_bytes());
+        args.extend(0u128.to_le_bytes());
+        args.push(1);
+        args
+    };
+
+    let mut data = hash_anchor_discriminator("swap_v2").to_vec();
+    data.extend(clmm_args());
+    let mut accounts = vec![0, 1, pool_index, 3, 4, 5, 6, 7, 8, 9, 10];
+    accounts.extend([mint_index, wrapped_sol_index]);
+    let clmm_swap_v2 =
+        parse_single_raydium_clmm_instruction(&parsable(data, accounts, 0, None), &account_keys, 1);
+
+    let mut data = hash_anchor_discriminator("swap").to_vec();
+    data.extend(clmm_args());
+    let clmm_swap = parse_single_raydium_clmm_instruction(
+        &parsable(data, vec![0, 1, pool_index, 3, 4, 5, 6, 7, 8, 9], 0, None),
+        &account_keys,
+        2,
+    );
+
+    let instructions_with_logs = [
+        InstructionWithLogs {
+            instruction: cpmm_swap,
+            cpi_logs: cpmm_logs,
+        },
+        InstructionWithLogs {
+            instruction: clmm_swap_v2,
+            cpi_logs: clmm_event(mint < wrapped_sol),
+        },
+        InstructionWithLogs {
+            instruction: clmm_swap,
+            cpi_logs: clmm_event(mint < wrapped_sol),
+        },
+    ];
+
+    let rows = rows_from_instructions(
+        &instructions_with_logs,
+        &TransactionCosts::default(),
+        42,
+        1_700_000_000,
+        "sig",
+    )
+    .unwrap();
+    assert_eq!(rows.trades.len(), 2);
+
+    for (trade, direction) in rows
+        .trades
+        .iter()
+        .zip([TradeDirection::Buy, TradeDirection::Sell])
+    {
+        assert_eq!(trade.coin_token_address, mint.to_string());
+        assert_eq!(trade.price_coin_token_address, WRAPPED_SOL_MINT_ADDRESS);
+        assert_eq!(trade.coin_token_amount, 500);
+        assert_eq!(trade.price_coin_token_amount, 2_000);
+        assert_eq!(trade.direction, direction);
+        assert_eq!(trade.pool_address, pool.to_string());
+    }
+
+    // the first clmm swap waits for its pool, priced in token 0 like an amm swap
+    assert_eq!(rows.unresolved_trades.len(), 1);
+    let unresolved = &rows.unresolved_trades[0];
+    assert!(unresolved.coin_token_address.is_empty());
+    assert_eq!(unresolved.pool_address, pool.to_string());
+    let raydium_pool = PoolRow {
+        pool_address: pool.to_string(),
+        mint_address: mint.to_string(),
+        coin_mint_address: mint.min(wrapped_sol).to_string(),
+        pc_mint_address: mint.max(wrapped_sol).to_string(),
+        coin_vault_address: String::new(),
+        pc_vault_address: String::new(),
+        venue: TradeVenue::RaydiumClmm,
+    };
+    let resolved = resolve_pool_trade(unresolved.clone(), &raydium_pool);
+    assert_eq!(resolved.coin_token_address, mint.to_string());
+    assert_eq!(resolved.coin_token_amount, 500);
+    assert_eq!(resolved.price_coin_token_amount, 2_000);
+    assert_eq!(resolved.direction, TradeDirection::Sell);
+}
+
+#[test]
+fn test_pump_swap_buy_event_from_self_invocation() {
+    let pump_fun_program_address = get_pump_fun_program_address();
+    let raydium_amm_program_address = get_raydium_amm_program_address();
+
+    let user = Pubkey::new_unique();
+    let pool = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let event_authority = Pubkey::new_unique().to_string();
+    let filler = Pubkey::new_unique().to_string();
+
+    let mut buy_data = hash_anchor_discriminator("buy").to_vec();
+    buy_data.extend(500u64.to_le_bytes());
+    buy_data.extend(2_100u64.to_le_bytes());
+
+    // emit_cpi! event, buying 500 of the token for 2_000 lamports before fees
+    let mut event_data = EVENT_IX_TAG.to_vec();
+    event_data.extend(hash_anchor_event_discriminator("BuyEvent"));
+    event_data.extend(1_700_000_000i64.to_le_bytes());
+    [
+        500u64, 2_100, 0, 10_000, 1_000_000, 4_000_000, 2_000, 20, 4, 5, 1, 2_004, 2_005,
+    ]
+    .iter()
+    .for_each(|amount| event_data.extend(amount.to_le_bytes()));
+    event_data.extend(pool.to_bytes());
+    event_data.extend(user.to_bytes());
+    (0..4).for_each(|_| event_data.extend(Pubkey::new_unique().to_bytes()));
+
+    // keys: user, pump swap, pool, mint, wrapped SOL, filler, event authority, token program
+    let tx: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_value(json!({
+        "slot": 42,
+        "blockTime": 1_700_000_000,
+        "transaction": {
+            "transaction": {
+                "signatures": [bs58::encode([7u8; 64]).into_string()],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 2
+                    },
+                    "accountKeys": [
+                        user.to_string(), PUMP_SWAP_ADDRESS, pool.to_string(), mint.to_string(),
+                        WRAPPED_SOL_MINT_ADDRESS, filler, event_authority, TOKEN_PROGRAM_ADDRESS
+                    ],
+                    "recentBlockhash": bs58::encode([0u8; 32]).into_string(),
+                    "instructions": [{
+                        "programIdIndex": 1,
+                        "accounts": [2, 0, 5, 3, 4, 5, 5, 5, 5, 5, 5, 7, 7, 5, 5, 6, 1],
+                        "data": bs58::encode(buy_data).into_string(),
+                        "stackHeight": null
+                    }]
+                }
+            },
+            "meta": {
+                "err": null,
+                "status": {"Ok": null},
+                "fee": 5000,
+                "preBalances": [0, 0, 0, 0, 0, 0, 0, 0],
+                "postBalances": [0, 0, 0, 0, 0, 0, 0, 0],
+                "innerInstructions": [{
+                    "index": 0,
+                    "instructions": [
+                        {"programIdIndex": 7, "accounts": [5, 5, 2], "data": bs58::encode([3u8; 9]).into_string(), "stackHeight": 2},
+                        {"programIdIndex": 1, "accounts": [6], "data": bs58::encode(event_data).into_string(), "stackHeight": 2}
+                    ]
+                }],
+                "logMessages": [
+                    format!("Program {} invoke [1]", PUMP_SWAP_ADDRESS),
+                    format!("Program {} invoke [2]", TOKEN_PROGRAM_ADDRESS),
+                    format!("Program {} success", TOKEN_PROGRAM_ADDRESS),
+                    format!("Program {} invoke [2]", PUMP_SWAP_ADDRESS),
+                    format!("Program {} success", PUMP_SWAP_ADDRESS),
+                    format!("Program {} success", PUMP_SWAP_ADDRESS)
+                ]
+            }
+        }
+    }))
+    .unwrap();
+
+    let (_signature, message) = raw_message(&tx.transaction.transaction).unwrap();
+    let instructions_with_logs = parse_transaction_with_logs(
+        &tx.transaction.meta,
+        message,
+        &pump_fun_program_address,
+        &raydium_amm_program_address,
+    );
+
+    // the event invocation itself is not an instruction
+    assert!(matches!(
+        instructions_with_logs[0].instruction,
+        Some(Instruction::PumpSwap(0, _))
+    ));
+    assert!(instructions_with_logs[2].instruction.is_none());
+
+    let trades =
+        trades_from_instructions(&instructions_with_logs, 42, 1_700_000_000, "sig").unwrap();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].coin_token_address, mint.to_string());
+    assert_eq!(trades[0].price_coin_token_address, WRAPPED_SOL_MINT_ADDRESS);
+    assert_eq!(trades[0].coin_token_amount, 500);
+    assert_eq!(trades[0].price_coin_token_amount, 2_000);
+    assert_eq!(trades[0].direction, TradeDirection::Buy);
+    assert_eq!(trades[0].pool_address, pool.to_string());
+    assert_eq!(trades[0].trader_address, user.to_string());
+}
+
+#[test]
+fn test_pump_swap_create_pool_is_stored_as_a_pool() {
+    let pool = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let base_vault = Pubkey::new_unique();
+    let quote_vault = Pubkey::new_unique();
+
+    // pool, base and quote mints at 3 and 4, their pool token accounts at 9 and 10
+    let mut account_keys = unique_keys(18);
+    account_keys[0] = pool.to_string();
+    account_keys[3] = mint.to_string();
+    account_keys[4] = WRAPPED_SOL_MINT_ADDRESS.to_string();
+    account_keys[9] = base_vault.to_string();
+    account_keys[10] = quote_vault.to_string();
+
+    let mut data = hash_anchor_discriminator("create_pool").to_vec();
+    data.extend(0u16.to_le_bytes());
+    data.extend(206_900_000_000_000u64.to_le_bytes());
+    data.extend(84_990_359_007u64.to_le_bytes());
+    let create_pool = parsable(data, (0..18).collect(), 0, None);
+
+    let instructions_with_logs = [InstructionWithLogs {
+        instruction: parse_single_pump_swap_instruction(&create_pool, &account_keys, 0),
+        cpi_logs: vec![],
+    }];
+
+    let rows = rows_from_instructions(
+        &instructions_with_logs,
+        &TransactionCosts::default(),
+        42,
+        1_700_000_000,
+        "sig",
+    )
+    .unwrap();
+    assert!(rows.trades.is_empty());
+
+    assert_eq!(rows.pools.len(), 1);
+    let pump_swap_pool = &rows.pools[0];
+    assert_eq!(pump_swap_pool.pool_address, pool.to_string());
+    assert_eq!(pump_swap_pool.mint_address, mint.to_string());
+    assert_eq!(pump_swap_pool.coin_mint_address, mint.to_string());
+    assert_eq!(pump_swap_pool.pc_mint_address, WRAPPED_SOL_MINT_ADDRESS);
+    assert_eq!(pump_swap_pool.coin_vault_address, base_vault.to_string());
+    assert_eq!(pump_swap_pool.pc_vault_address, quote_vault.to_string());
+    assert_eq!(pump_swap_pool.venue, TradeVenue::PumpSwap);
+}
+
+#[test]
+fn test_orca_whirlpool_and_meteora_dlmm_swaps_become_trades() {
+    let mint = Pubkey::new_unique();
+    let pool = Pubkey::new_unique();
+    // user, token program, whirlpool, mint, wrapped SOL, vault a, vault b, user a, user b
+    let mut account_keys = unique_keys(9);
+    account_keys[1] = TOKEN_PROGRAM_ADDRESS.to_string();
+    account_keys[2] = pool.to_string();
+    account_keys[3] = mint.to_string();
+    account_keys[4] = WRAPPED_SOL_MINT_ADDRESS.to_string();
+
+    let transfer_checked = |source: u8, mint: u8, destination: u8, amount: u64| {
+        let mut data = vec![12u8];
+        data.extend(amount.to_le_bytes());
+        data.push(9);
+        ParsableInstruction {
+            data: bs58::encode(data).into_string(),
+            accounts: vec![source, mint, destination, 0],
+            program_id_index: 1,
+            inner_instructions: None,
+            instruction_index: 0,
+            inner_instruction_index: None,
+            stack_height: Some(2),
+        }
+    };
+
+    // whirlpool swap v2 selling 500 of the token, token a, for 2_000 lamports
+    let mut data = hash_anchor_discriminator("swap_v2").to_vec();
+    data.extend(500u64.to_le_bytes());
+    data.extend(1_900u64.to_le_bytes());
+    data.extend(0u128.to_le_bytes());
+    data.extend([1, 1]);
+    let accounts = vec![1, 1, 1, 0, 2, 3, 4, 7, 5, 8, 6, 0, 0, 0, 0];
+    let transfers = vec![
+        transfer_checked(7, 3, 5, 500),
+        transfer_checked(6, 4, 8, 2_000),
+    ];
+    let whirlpool_swap = parse_single_orca_whirlpool_instruction(
+        &parsable(data, accounts, 0, Some(transfers)),
+        &account_keys,
+        0,
+        &vec![],
+    );
+
+    // dlmm swap of y, wrapped SOL, for x, buying 500 of the token for 2_000 lamports
+    let mut data = hash_anchor_discriminator("swap").to_vec();
+    data.extend(2_000u64.to_le_bytes());
+    data.extend(450u64.to_le_bytes());
+    let accounts = vec![2, 0, 5, 6, 8, 7, 3, 4, 0, 0, 0, 1, 1, 0, 0];
+    let dlmm_swap = parse_single_meteora_dlmm_instruction(
+        &parsable(data, accounts, 0, Some(vec![])),
+        &account_keys,
+        1,
+    );
+
+    let event_data = dlmm_swap_event_data(&pool, 2_000, 500);
+    let dlmm_logs = meteora_dlmm_cpi_log_from_event_instruction(&event_data)
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let instructions_with_logs = [
+        InstructionWithLogs {
+            instruction: whirlpool_swap,
+            cpi_logs: vec![],
+        },
+        InstructionWithLogs {
+            instruction: dlmm_swap,
+            cpi_logs: dlmm_logs,
+        },
+    ];
+
+    let trades =
+        trades_from_instructions(&instructions_with_logs, 42, 1_700_000_000, "sig").unwrap();
+    assert_eq!(trades.len(), 2);
+
+    for (trade, (direction, venue)) in trades.iter().zip([
+        (TradeDirection::Sell, TradeVenue::OrcaWhirlpool),
+        (TradeDirection::Buy, TradeVenue::MeteoraDlmm),
+    ]) {
+        assert_eq!(trade.coin_token_address, mint.to_string());
+        assert_eq!(trade.price_coin_token_address, WRAPPED_SOL_MINT_ADDRESS);
+        assert_eq!(trade.coin_token_amount, 500);
+        assert_eq!(trade.price_coin_token_amount, 2_000);
+        assert_eq!(trade.direction, direction);
+        assert_eq!(trade.venue, venue);
+        assert_eq!(trade.pool_address, pool.to_string());
+    }
+
+    // swaps without mints resolve them from the whirlpool state
+    let mut state = vec![0u8; WHIRLPOOL_SIZE];
+    state[..8].copy_from_slice(&hash_anchor_account_discriminator("Whirlpool"));
+    state[101..133].copy_from_slice(&mint.to_bytes());
+    state[181..213].copy_from_slice(
+        &Pubkey::from_str(WRAPPED_SOL_MINT_ADDRESS)
+            .unwrap()
+            .to_bytes(),
+    );
+    let whirlpool =
+        pool_from_account(&pool, &get_orca_whirlpool_program_address(), &state).unwrap();
+    assert_eq!(whirlpool.coin_mint_address, mint.to_string());
+    assert_eq!(whirlpool.pc_mint_address, WRAPPED_SOL_MINT_ADDRESS);
+    assert_eq!(whirlpool.venue, TradeVenue::OrcaWhirlpool);
+
+    // other accounts of the program, or short ones, are not read as whirlpools
+    assert!(pool_from_account(
+        &pool,
+        &get_orca_whirlpool_program_address(),
+        &state[..WHIRLPOOL_SIZE - 1]
+    )
+    .is_none());
+    state[..8].copy_from_slice(&hash_anchor_account_discriminator("WhirlpoolsConfig"));
+    assert!(pool_from_account(&pool, &get_orca_whirlpool_program_address(), &state).is_none());
+}
+
+#[test]
+fn test_jupiter_route_links_legs_and_nets_amounts() {
+    let mint = Pubkey::new_unique();
+    let pool = Pubkey::new_unique();
+    let usdc = Pubkey::new_unique();
+    let wrapped_sol = Pubkey::from_str(WRAPPED_SOL_MINT_ADDRESS).unwrap();
+    // user, token program, dlmm pair, mint, wrapped SOL, reserve x, reserve y, user x, user y
+    let mut account_keys = unique_keys(9);
+    account_keys[1] = TOKEN_PROGRAM_ADDRESS.to_string();
+    account_keys[2] = pool.to_string();
+    account_keys[3] = mint.to_string();
+    account_keys[4] = WRAPPED_SOL_MINT_ADDRESS.to_string();
+
+    let swap_event = |amm: &Pubkey, input: (&Pubkey, u64), output: (&Pubkey, u64)| {
+        let mut event_data = EVENT_IX_TAG.to_vec();
+        event_data.extend(hash_anchor_event_discriminator("SwapEvent"));
+        event_data.extend(amm.to_bytes());
+        event_data.extend(input.0.to_bytes());
+        event_data.extend(input.1.to_le_bytes());
+        event_data.extend(output.0.to_bytes());
+        event_data.extend(output.1.to_le_bytes());
+        jupiter_cpi_log_from_event_instruction(&event_data).unwrap()
+    };
+
+    // route paying 2_000 lamports for the token, then selling the token for 30 of another
+    let accounts = vec![1, 0, 7, 8, 8, 4, 0, 0, 0];
+    let route = parse_single_jupiter_instruction(
+        &parsable(
+            hash_anchor_discriminator("route").to_vec(),
+            accounts,
+            3,
+            Some(vec![]),
+        ),
+        &account_keys,
+        3,
+    );
+    let route_logs = vec![
+        swap_event(&pool, (&wrapped_sol, 2_000), (&mint, 500)),
+        swap_event(&Pubkey::new_unique(), (&mint, 500), (&usdc, 30)),
+    ];
+
+    // the first hop is a dlmm swap made by the route, recorded under its instruction index
+    let mut data = hash_anchor_discriminator("swap").to_vec();
+    data.extend(2_000u64.to_le_bytes());
+    data.extend(450u64.to_le_bytes());
+    // its user is the route's authority, stood in for by reserve x
+    let accounts = vec![2, 0, 5, 6, 8, 7, 3, 4, 0, 0, 5, 1, 1, 0, 0];
+    let dlmm_swap = parse_single_meteora_dlmm_instruction(
+        &parsable(data, accounts, 3, Some(vec![])),
+        &account_keys,
+        3,
+    );
+
+    let event_data = dlmm_swap_event_data(&pool, 2_000, 500);
+    let dlmm_logs = meteora_dlmm_cpi_log_from_event_instruction(&event_data)
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let instructions_with_logs = [
+        InstructionWithLogs {
+            instruction: route,
+            cpi_logs: route_logs,
+        },
+        InstructionWithLogs {
+            instruction: dlmm_swap,
+            cpi_logs: dlmm_logs,
+        },
+    ];
+
+    let rows = rows_from_instructions(
+        &instructions_with_logs,
+        &TransactionCosts::default(),
+        42,
+        1_700_000_000,
+        "sig",
+    )
+    .unwrap();
+
+    assert_eq!(rows.trades.len(), 1);
+    assert_eq!(rows.trades[0].direction, TradeDirection::Buy);
+    assert_eq!(rows.trades[0].route_id, Some(3));
+    assert_eq!(rows.trades[0].route_hop_index, Some(0));
+    // the dex saw the route's authority, the leg is still the user's
+    assert_eq!(rows.trades[0].trader_address, account_keys[0]);
+
+    assert_eq!(rows.jupiter_routes.len(), 1);
+    let jupiter_route = &rows.jupiter_routes[0];
+    assert_eq!(jupiter_route.route_id, 3);
+    assert_eq!(jupiter_route.user_address, account_keys[0]);
+    assert_eq!(jupiter_route.input_mint_address, WRAPPED_SOL_MINT_ADDRESS);
+    assert_eq!(jupiter_route.input_amount, 2_000);
+    assert_eq!(jupiter_route.output_mint_address, usdc.to_string());
+    assert_eq!(jupiter_route.output_amount, 30);
+    assert_eq!(jupiter_route.hop_count, 2);
+}
+
+#[test]
+fn test_legs_sharing_an_instruction_and_pool_are_numbered() {
+    let mint = Pubkey::new_unique();
+    let pool = Pubkey::new_unique();
+    // user, token program, dlmm pair, mint, wrapped SOL, reserve x, reserve y, user x, user y
+    let mut account_keys = unique_keys(9);
+    account_keys[1] = TOKEN_PROGRAM_ADDRESS.to_string();
+    account_keys[2] = pool.to_string();
+    account_keys[3] = mint.to_string();
+    account_keys[4] = WRAPPED_SOL_MINT_ADDRESS.to_string();
+
+    // two buys through the same pair, both made by the top-level instruction 0
+    let dlmm_swap = || {
+        let mut data = hash_anchor_discriminator("swap").to_vec();
+        data.extend(2_000u64.to_le_bytes());
+        data.extend(450u64.to_le_bytes());
+        let accounts = vec![2, 0, 5, 6, 8, 7, 3, 4, 0, 0, 0, 1, 1, 0, 0];
+        InstructionWithLogs {
+            instruction: parse_single_meteora_dlmm_instruction(
+                &parsable(data, accounts, 0, Some(vec![])),
+                &account_keys,
+                0,
+            ),
+            cpi_logs: meteora_dlmm_cpi_log_from_event_instruction(&dlmm_swap_event_data(
+                &pool, 2_000, 500,
+            ))
+            .into_iter()
+            .collect(),
+        }
+    };
+    let instructions_with_logs = [dlmm_swap(), dlmm_swap()];
+
+    let rows = rows_from_instructions(
+        &instructions_with_logs,
+        &TransactionCosts::default(),
+        42,
+        1_700_000_000,
+        "sig",
+    )
+    .unwrap();
+
+    // identical legs keep distinct sorting keys, and get the same ones on a reparse
+    assert_eq!(rows.trades.len(), 2);
+    assert!(rows.trades.iter().all(|trade| trade.instruction_index == 0));
+    assert_eq!(rows.trades[0].trade_ordinal, 0);
+    assert_eq!(rows.trades[1].trade_ordinal, 1);
+
+    let reparsed = rows_from_instructions(
+        &instructions_with_logs,
+        &TransactionCosts::default(),
+        42,
+        1_700_000_000,
+        "sig",
+    )
+    .unwrap();
+    assert_eq!(
+        reparsed
+            .trades
+            .iter()
+            .map(|trade| trade.trade_ordinal)
+            .collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+}
+
+#[test]
+fn test_transaction_costs_from_compute_budget_and_jito_tip() {
+    let fee_payer = Pubkey::new_unique();
+
+    let mut set_limit = vec![2u8];
+    set_limit.extend(100_000u32.to_le_bytes());
+    let mut set_price = vec![3u8];
+    set_price.extend(50_000u64.to_le_bytes());
+    let mut tip = vec![2u8, 0, 0, 0];
+    tip.extend(1_000_000u64.to_le_bytes());
+
+    // keys: fee payer, jito tip account, compute budget, system program
+    let tx: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_value(json!({
+        "slot": 42,
+        "blockTime": 1_700_000_000,
+        "transaction": {
+            "transaction": {
+                "signatures": [bs58::encode([7u8; 64]).into_string()],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 2
+                    },
+                    "accountKeys": [
+                        fee_payer.to_string(), JITO_TIP_ADDRESSES[3],
+                        COMPUTE_BUDGET_PROGRAM_ADDRESS, SYSTEM_PROGRAM_ADDRESS
+                    ],
+                    "recentBlockhash": bs58::encode([0u8; 32]).into_string(),
+                    "instructions": [
+                        {"programIdIndex": 2, "accounts": [], "data": bs58::encode(set_limit).into_string(), "stackHeight": null},
+                        {"programIdIndex": 2, "accounts": [], "data": bs58::encode(set_price).into_string(), "stackHeight": null},
+                        {"programIdIndex": 3, "accounts": [0, 1], "data": bs58::encode(tip).into_string(), "stackHeight": null}
+                    ]
+                }
+            },
+            "meta": {
+                "err": null,
+                "status": {"Ok": null},
+                "fee": 10_000,
+                "preBalances": [0, 0, 0, 0],
+                "postBalances": [0, 0, 0, 0],
+                "innerInstructions": [],
+                "logMessages": [],
+                "computeUnitsConsumed": 60_000
+            }
+        }
+    }))
+    .unwrap();
+
+    let (_signature, message) = raw_message(&tx.transaction.transaction).unwrap();
+    let costs = transaction_costs(message, &tx.transaction.meta);
+
+    // 50_000 micro lamports on each of the 100_000 requested units
+    assert_eq!(
+        costs,
+        TransactionCosts {
+            fee_payer_address: fee_payer.to_string(),
+            base_fee: 5_000,
+            priority_fee: 5_000,
+            compute_unit_price: 50_000,
+            compute_unit_limit: 100_000,
+            compute_units_consumed: Some(60_000),
+            jito_tip: 1_000_000,
+        }
+    );
+}
+
+#[test]
+fn test_transaction_costs_default_limit_gives_builtins_their_allocation() {
+    let fee_payer = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+
+    let mut set_price = vec![3u8];
+    set_price.extend(1_000_000u64.to_le_bytes());
+    let mut transfer = vec![2u8, 0, 0, 0];
+    transfer.extend(1u64.to_le_bytes());
+
+    // keys: fee payer, recipient, compute budget, system program, some program
+    let tx: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_value(json!({
+        "slot": 42,
+        "blockTime": 1_700_000_000,
+        "transaction": {
+            "transaction": {
+                "signatures": [bs58::encode([7u8; 64]).into_string()],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 3
+                    },
+                    "accountKeys": [
+                        fee_payer.to_string(), Pubkey::new_unique().to_string(),
+                        COMPUTE_BUDGET_PROGRAM_ADDRESS, SYSTEM_PROGRAM_ADDRESS, program.to_string()
+                    ],
+                    "recentBlockhash": bs58::encode([0u8; 32]).into_string(),
+                    "instructions": [
+                        {"programIdIndex": 2, "accounts": [], "data": bs58::encode(set_price).into_string(), "stackHeight": null},
+                        {"programIdIndex": 3, "accounts": [0, 1], "data": bs58::encode(transfer).into_string(), "stackHeight": null},
+                        {"programIdIndex": 4, "accounts": [], "data": "", "stackHeight": null}
+                    ]
+                }
+            },
+            "meta": {
+                "err": null,
+                "status": {"Ok": null},
+                "fee": 211_000,
+                "preBalances": [0, 0, 0, 0, 0],
+                "postBalances": [0, 0, 0, 0, 0],
+                "innerInstructions": [],
+                "logMessages": [],
+                "computeUnitsConsumed": 10_000
+            }
+        }
+    }))
+    .unwrap();
+
+    let (_signature, message) = raw_message(&tx.transaction.transaction).unwrap();
+    let costs = transaction_costs(message, &tx.transaction.meta);
+
+    // the program gets the default limit, compute budget and system only the builtin allocation
+    assert_eq!(costs.compute_unit_limit, 206_000);
+    assert_eq!(costs.priority_fee, 206_000);
+    assert_eq!(costs.base_fee, 5_000);
+}
+
+#[test]
+fn test_pump_fun_trade_carries_curve_reserves_and_progress() {
+    let mint = Pubkey::new_unique();
+    let bonding_curve = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+
+    // a quarter of the sellable tokens are already bought off the curve
+    let virtual_token_reserves = INITIAL_VIRTUAL_TOKEN_RESERVES - INITIAL_REAL_TOKEN_RESERVES / 4;
+    let instructions_with_logs = [InstructionWithLogs {
+        instruction: Some(Instruction::PumpFun(
+            0,
+            PumpFunInstruction::Buy((
+                DecodedInstruction {
+                    name: "buy".to_string(),
+                    accounts: vec![],
+                },
+                (mint, bonding_curve),
+            )),
+        )),
+        cpi_logs: vec![CpiLog::PumpFunTrade(PumpFunTradeEvent {
+            sol_amount: 2_000,
+            token_amount: 500,
+            is_buy: true,
+            mint,
+            user,
+            timestamp: 1_700_000_000,
+            virtual_sol_reserves: 40_000_000_000,
+            virtual_token_reserves,
+        })],
+    }];
+
+    let trades =
+        trades_from_instructions(&instructions_with_logs, 42, 1_700_000_000, "sig").unwrap();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].trader_address, user.to_string());
+    assert_eq!(trades[0].virtual_sol_reserves, Some(40_000_000_000));
+    assert_eq!(
+        trades[0].virtual_token_reserves,
+        Some(virtual_token_reserves)
+    );
+    assert_eq!(trades[0].bonding_curve_progress, Some(25.0));
+
+    // the curve starts empty, completes once its real token reserves are sold, and never
+    // goes past either end
+    let curve_params = CurveParams::initial();
+    assert_eq!(
+        bonding_curve_progress(INITIAL_VIRTUAL_TOKEN_RESERVES, &curve_params),
+        0.0
+    );
+    assert_eq!(
+        bonding_curve_progress(
+            INITIAL_VIRTUAL_TOKEN_RESERVES - INITIAL_REAL_TOKEN_RESERVES,
+            &curve_params
+        ),
+        100.0
+    );
+    assert_eq!(bonding_curve_progress(0, &curve_params), 100.0);
+    assert_eq!(bonding_curve_progress(u64::MAX, &curve_params), 0.0);
+
+    // curves started from different SetParams reserves are measured against those
+    let curve_params = CurveParams::from(&PumpFunSetParamsEvent {
+        initial_virtual_token_reserves: 1_000_000,
+        initial_real_token_reserves: 800_000,
+    });
+    assert_eq!(bonding_curve_progress(600_000, &curve_params), 50.0);
+}
+
+#[test]
+fn test_pump_fun_curve_progress_uses_the_params_in_effect_at_the_trade_slot() {
+    let mint = Pubkey::new_unique();
+    let bonding_curve = Pubkey::new_unique();
+    let buy = |virtual_token_reserves: u64| InstructionWithLogs {
+        instruction: Some(Instruction::PumpFun(
+            0,
+            PumpFunInstruction::Buy((
+                DecodedInstruction {
+                    name: "buy".to_string(),
+                    accounts: vec![],
+                },
+                (mint, bonding_curve),
+            )),
+        )),
+        cpi_logs: vec![CpiLog::PumpFunTrade(PumpFunTradeEvent {
+            sol_amount: 2_000,
+            token_amount: 500,
+            is_buy: true,
+            mint,
+            user: Pubkey::new_unique(),
+            timestamp: 1_700_000_000,
+            virtual_sol_reserves: 40_000_000_000,
+            virtual_token_reserves,
+        })],
+    };
+
+    // far past the slots of the other tests, which keep the initial params
+    let set_params_slot = 9_000_000;
+    let set_params = [InstructionWithLogs {
+        instruction: None,
+        cpi_logs: vec![CpiLog::PumpFunSetParams(PumpFunSetParamsEvent {
+            initial_virtual_token_reserves: 1_000_000,
+            initial_real_token_reserves: 800_000,
+        })],
+    }];
+    let rows = rows_from_instructions(
+        &set_params,
+        &TransactionCosts::default(),
+        set_params_slot,
+        1_700_000_000,
+        "set_params",
+    )
+    .unwrap();
+    assert_eq!(rows.curve_params.len(), 1);
+    assert_eq!(rows.curve_params[0].slot, set_params_slot);
+    assert_eq!(
+        rows.curve_params[0].initial_virtual_token_reserves,
+        1_000_000
+    );
+    assert_eq!(rows.curve_params[0].initial_real_token_reserves, 800_000);
+
+    // a later slot is measured against the new params
+    let trades =
+        trades_from_instructions(&[buy(600_000)], set_params_slot + 1, 1_700_000_000, "later")
+            .unwrap();
+    assert_eq!(trades[0].bonding_curve_progress, Some(50.0));
+
+    // an earlier slot crawled afterwards still gets the params that were in effect then
+    let virtual_token_reserves = INITIAL_VIRTUAL_TOKEN_RESERVES - INITIAL_REAL_TOKEN_RESERVES / 4;
+    let trades = trades_from_instructions(
+        &[buy(virtual_token_reserves)],
+        set_params_slot - 1,
+        1_700_000_000,
+        "earlier",
+    )
+    .unwrap();
+    assert_eq!(trades[0].bonding_curve_progress, Some(25.0));
+}
+
+#[test]
+fn test_newest_seen_signature_is_lowest_index_of_highest_succeeded_slot() {
+    let batch_size = 1_000.0;
+    let score = |slot: u64, relative_index: u64| slot as f64 + relative_index as f64 / batch_size;
+    let signatures_with_scores = vec![
+        ("older_slot".to_string(), score(100, 0)),
+        ("oldest_in_slot".to_string(), score(101, 2)),
+        ("newest_in_slot".to_string(), score(101, 0)),
+        ("middle_in_slot".to_string(), score(101, 1)),
+    ];
+
+    let newest =
+        newest_succeeded_signature(signatures_with_scores.clone(), |_signature| Ok(true)).unwrap();
+    assert_eq!(newest, Some("newest_in_slot".to_string()));
+
+    // pending and failed signatures were not crawled, even in a newer slot
+    let mut with_pending = signatures_with_scores.clone();
+    with_pending.push(("pending".to_string(), score(102, 0)));
+    let newest = newest_succeeded_signature(with_pending, |signature| {
+        Ok(signature != "pending" && signature != "newest_in_slot")
+    })
+    .unwrap();
+    assert_eq!(newest, Some("middle_in_slot".to_string()));
+
+    let newest = newest_succeeded_signature(signatures_with_scores, |_signature| Ok(false));
+    assert_eq!(newest, Ok(None));
+    assert_eq!(
+        newest_succeeded_signature(vec![], |_signature| Ok(true)),
+        Ok(None)
+    );
+}
+
+#[test]
+fn test_pools_trade_the_side_that_is_not_a_quote_mint() {
+    let usdc = Pubkey::from_str(USDC_MINT_ADDRESS).unwrap();
+    let wrapped_sol = Pubkey::from_str(WRAPPED_SOL_MINT_ADDRESS).unwrap();
+    // sorts after usdc, so the first mint of the pair is the quote one
+    let token = Pubkey::new_from_array([u8::MAX; 32]);
+    assert!(usdc < token);
+
+    let trade = trade_from_pool_swap(
+        &Pubkey::new_unique(),
+        TradeVenue::RaydiumCpmm,
+        "trader",
+        (&usdc, 2_000),
+        (&token, 500),
+        &0,
+        42,
+        1_700_000_000,
+        "sig",
+    );
+    assert_eq!(trade.coin_token_address, token.to_string());
+    assert_eq!(trade.price_coin_token_address, usdc.to_string());
+    assert_eq!(trade.coin_token_amount, 500);
+    assert_eq!(trade.direction, TradeDirection::Buy);
+
+    // sol is the token of a sol and stablecoin pair
+    let trade = trade_from_pool_swap(
+        &Pubkey::new_unique(),
+        TradeVenue::RaydiumClmm,
+        "trader",
+        (&wrapped_sol, 1_000),
+        (&usdc, 150_000),
+        &0,
+        42,
+        1_700_000_000,
+        "sig",
+    );
+    assert_eq!(trade.coin_token_address, wrapped_sol.to_string());
+    assert_eq!(trade.direction, TradeDirection::Sell);
+
+    // amm pools pick the same side whichever of the two is their coin
+    let raydium_pool = PoolRow::from_pool(
+        &Pool {
+            pool: Pubkey::new_unique(),
+            coin_mint: usdc,
+            pc_mint: token,
+            coin_vault: Pubkey::new_unique(),
+            pc_vault: Pubkey::new_unique(),
+        },
+        TradeVenue::RaydiumAmm,
+    );
+    assert_eq!(raydium_pool.mint_address, token.to_string());
+}
+
+fn write_rpc_config(name: &str, raw: &str) -> String {
+    let path = std::env::temp_dir().join(format!("{}-{}.toml", name, std::process::id()));
+    std::fs::write(&path, raw).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+#[test]
+fn test_rpc_config_env_overrides_file_and_file_overrides_defaults() {
+    let path = write_rpc_config(
+        "rpc-config-precedence",
+        r#"
+cluster = "mainnet"
+
+[[endpoints]]
+name = "precedence-test"
+cluster = "mainnet"
+url = "https://file.example"
+priority = 3
+burst = 4
+"#,
+    );
+    std::env::set_var("RPC_ENDPOINT_PRECEDENCE_TEST_PRIORITY", "2");
+    std::env::set_var("RPC_ENDPOINT_PRECEDENCE_TEST_URL", "https://env.example");
+
+    let config = RpcConfig::load(&path).unwrap();
+    let endpoint = &config.endpoints[0];
+
+    // env beats the file
+    assert_eq!(endpoint.priority, 2);
+    assert_eq!(endpoint.url.as_deref(), Some("https://env.example"));
+    assert_eq!(endpoint.ws_url(), "wss://env.example");
+    // the file beats the defaults
+    assert_eq!(endpoint.burst(), 4);
+    // and the defaults fill in the rest
+    assert_eq!(endpoint.requests_per_second, DEFAULT_REQUESTS_PER_SECOND);
+    assert!(endpoint.supports_batch);
+    assert!(endpoint.enabled);
+
+    let defaults = RpcConfig::parse(
+        r#"
+cluster = "mainnet"
+
+[[endpoints]]
+name = "defaults-test"
+cluster = "mainnet"
+"#,
+    )
+    .unwrap();
+    assert_eq!(defaults.endpoints[0].priority, DEFAULT_PRIORITY);
+    assert_eq!(defaults.endpoints[0].burst(), DEFAULT_REQUESTS_PER_SECOND);
+}
+
+#[test]
+fn test_rpc_config_rejects_invalid_override_values() {
+    let path = write_rpc_config(
+        "rpc-config-invalid",
+        r#"
+cluster = "mainnet"
+
+[[endpoints]]
+name = "invalid-override-test"
+cluster = "mainnet"
+url = "https://file.example"
+"#,
+    );
+    std::env::set_var("RPC_ENDPOINT_INVALID_OVERRIDE_TEST_RPS", "fast");
+
+    match RpcConfig::load(&path) {
+        Err(RpcConfigError::InvalidOverride(key, value)) => {
+            assert_eq!(key, "RPC_ENDPOINT_INVALID_OVERRIDE_TEST_RPS");
+            assert_eq!(value, "fast");
+        }
+        other => panic!("expected an invalid override, got {:?}", other),
+    }
+
+    // an endpoint without any url to fall back on is rejected too
+    let path = write_rpc_config(
+        "rpc-config-missing-url",
+        r#"
+cluster = "mainnet"
+
+[[endpoints]]
+name = "missing-url-test"
+cluster = "mainnet"
+url_env = "MISSING_URL_TEST_RPC_URL"
+"#,
+    );
+    assert!(matches!(
+        RpcConfig::load(&path),
+        Err(RpcConfigError::MissingUrl(name)) if name == "missing-url-test"
+    ));
+}
+
+#[test]
+fn test_endpoint_circuit_opens_half_opens_and_closes() {
+    let latency = Duration::from_millis(100);
+    let mut health = EndpointHealth::new();
+
+    // stays closed until the threshold of consecutive failures
+    for _ in 1..CIRCUIT_FAILURE_THRESHOLD {
+        health.record_failure(latency);
+    }
+    assert_eq!(health.circuit, CircuitState::Closed);
+    assert!(health.can_accept());
+
+    health.record_failure(latency);
+    assert!(health.open_until().is_some());
+    assert!(!health.can_accept());
+
+    // once the open period is over a single probe goes through
+    health.circuit = CircuitState::Open(Instant::now() - Duration::from_millis(1));
+    assert!(health.can_accept());
+    assert_eq!(health.circuit, CircuitState::HalfOpen);
+    health.on_selected();
+    assert!(!health.can_accept());
+
+    // a failed probe opens the circuit again straight away
+    health.record_failure(latency);
+    assert!(health.open_until().is_some());
+
+    // a successful probe closes it and resets the failure count
+    health.circuit = CircuitState::Open(Instant::now() - Duration::from_millis(1));
+    assert!(health.can_accept());
+    health.on_selected();
+    health.record_success(latency);
+    assert_eq!(health.circuit, CircuitState::Closed);
+    assert_eq!(health.consecutive_failures, 0);
+    assert!(health.can_accept());
+}
+
+#[test]
+fn test_endpoint_health_ewma_decays_towards_recent_samples() {
+    let mut health = EndpointHealth::new();
+
+    // the first latency sample seeds the average
+    health.record_success(Duration::from_millis(100));
+    assert_eq!(health.latency_ewma_ms, 100.0);
+    assert_eq!(health.error_rate_ewma, 0.0);
+
+    health.record_failure(Duration::from_millis(600));
+    assert!((health.latency_ewma_ms - (100.0 + HEALTH_EWMA_ALPHA * 500.0)).abs() < 1e-9);
+    assert!((health.error_rate_ewma - HEALTH_EWMA_ALPHA).abs() < 1e-9);
+    let failed_score = health.score();
+
+    // successes decay the error rate geometrically and win the score back
+    for _ in 0..10 {
+        health.record_success(Duration::from_millis(100));
+    }
+    let expected_error_rate = HEALTH_EWMA_ALPHA * (1.0 - HEALTH_EWMA_ALPHA).powi(10);
+    assert!((health.error_rate_ewma - expected_error_rate).abs() < 1e-9);
+    assert!(health.score() > failed_score);
+
+    // a dead endpoint keeps the minimum score instead of dropping out
+    for _ in 0..100 {
+        health.record_failure(Duration::from_secs(10));
+    }
+    assert!(health.score() > 0.0);
+}
+
+fn rpc_response_error(code: i64, message: &str) -> ClientError {
+    ClientError::from(RpcRequestError::RpcResponseError {
+        code,
+        message: message.to_string(),
+        data: RpcResponseErrorData::Empty,
+    })
+}
+
+#[test]
+fn test_rpc_errors_are_classified_as_retryable_or_fatal() {
+    let timed_out = ClientError::from(ClientErrorKind::Io(std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        "timed out",
+    )));
+    let error = RpcError::from_client_error(timed_out);
+    assert!(matches!(error, RpcError::TransientNetwork(_)));
+    assert!(error.is_retryable() && error.is_endpoint_failure() && !error.is_fatal());
+
+    let error = RpcError::from_client_error(rpc_response_error(
+        JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+        "Node is unhealthy",
+    ));
+    assert!(matches!(error, RpcError::NodeBehind(_)));
+    assert!(error.is_retryable() && error.is_endpoint_failure());
+
+    // unknown codes fall back on the message before being treated as permanent
+    let error = RpcError::from_client_error(rpc_response_error(-32000, "Slot skipped"));
+    assert!(matches!(error, RpcError::NodeBehind(_)));
+
+    let error = RpcError::from_client_error(ClientError::from(ClientErrorKind::Custom(format!(
+        "{}: http status 400",
+        BATCH_REJECTED_PREFIX
+    ))));
+    assert!(matches!(error, RpcError::BatchRejected(_)));
+    assert!(error.is_retryable() && !error.is_endpoint_failure());
+
+    let error = RpcError::from_client_error(rpc_response_error(
+        JSON_RPC_SERVER_ERROR_BLOCK_CLEANED_UP,
+        "Block cleaned up",
+    ));
+    assert!(matches!(error, RpcError::NotFound(_)));
+    assert!(!error.is_retryable() && !error.is_fatal());
+
+    let error = RpcError::from_client_error(rpc_response_error(-32602, "Invalid params"));
+    assert!(matches!(error, RpcError::Permanent(_)));
+    assert!(!error.is_retryable() && error.is_fatal());
+
+    let error = RpcError::BudgetExhausted("getTransaction".to_string());
+    assert!(!error.is_retryable() && error.is_fatal());
+}
+
+#[test]
+fn test_retry_backoff_is_capped_and_attempts_are_bounded() {
+    let retry_policy = RetryPolicy {
+        max_attempts: 3,
+        base_delay_ms: 100,
+        max_delay_ms: 1_000,
+        deadline_ms: 10_000,
+    };
+
+    for attempt in 0..64 {
+        let ceiling = (100u64 << attempt.min(10)).min(1_000);
+        for _ in 0..20 {
+            assert!(retry_policy.backoff(attempt) <= Duration::from_millis(ceiling));
+        }
+    }
+
+    assert!(retry_policy.has_attempts_left(2));
+    assert!(!retry_policy.has_attempts_left(3));
+    assert_eq!(retry_policy.deadline(), Duration::from_secs(10));
+}
+
+#[test]
+fn test_non_retryable_rpc_errors_reach_the_caller_without_retries() {
+    let config = RpcConfig::parse(
+        r#"
+cluster = "mainnet"
+
+[retry]
+max_attempts = 3
+base_delay_ms = 1
+max_delay_ms = 1
+
+[[endpoints]]
+name = "no-retry-test"
+cluster = "mainnet"
+url = "http://127.0.0.1:1"
+requests_per_second = 1000
+"#,
+    )
+    .unwrap();
+    // nothing listens there, credit usage just starts from zero
+    let dragonfly = redis::Client::open("redis://127.0.0.1:1/").unwrap();
+    let rpc_pool_manager = RpcPoolManager::new(&config, Duration::from_millis(10), &dragonfly);
+
+    let calls_until = |error: fn() -> ClientError| {
+        let calls = AtomicU32::new(0);
+        let result = blocking_call(rpc_pool_manager.execute(
+            RpcRequest::GetSlot,
+            |_client| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async move { Err::<u64, ClientError>(error()) }
+            },
+            Some(0),
+        ));
+        (result, calls.load(Ordering::SeqCst))
+    };
+
+    let (result, calls) = calls_until(|| rpc_response_error(-32602, "Invalid params"));
+    assert!(matches!(result, Err(RpcError::Permanent(_))));
+    assert_eq!(calls, 1);
+
+    let (result, calls) = calls_until(|| {
+        rpc_response_error(JSON_RPC_SERVER_ERROR_BLOCK_CLEANED_UP, "Block cleaned up")
+    });
+    assert!(matches!(result, Err(RpcError::NotFound(_))));
+    assert_eq!(calls, 1);
+
+    // retryable errors use up every attempt before they are returned
+    let (result, calls) = calls_until(|| rpc_response_error(-32000, "Slot skipped"));
+    assert!(matches!(result, Err(RpcError::NodeBehind(_))));
+    assert_eq!(calls, 3);
+}
+
+// The endpoints are never dialled, the operations under test stand in for them. Nothing
+// listens at the dragonfly url either, credit usage just starts from zero.
+fn stand_in_rpc_pool_manager(raw: &str) -> RpcPoolManager {
+    let config = RpcConfig::parse(raw).unwrap();
+    let dragonfly = redis::Client::open("redis://127.0.0.1:1/").unwrap();
+    RpcPoolManager::new(&config, Duration::from_millis(10), &dragonfly)
+}
+
+const TWO_STAND_IN_ENDPOINTS: &str = r#"
+cluster = "mainnet"
+
+[[endpoints]]
+name = "stand-in-a"
+cluster = "mainnet"
+url = "http://127.0.0.1:1"
+requests_per_second = 1000
+
+[[endpoints]]
+name = "stand-in-b"
+cluster = "mainnet"
+url = "http://127.0.0.1:2"
+requests_per_second = 1000
+"#;
+
+#[test]
+fn test_concurrent_executes_run_together_on_their_pinned_endpoints() {
+    let rpc_pool_manager = stand_in_rpc_pool_manager(TWO_STAND_IN_ENDPOINTS);
+    let in_flight = &AtomicU32::new(0);
+    let most_in_flight = &AtomicU32::new(0);
+
+    let started_at = Instant::now();
+    let results = blocking_call(join_all((0..8u64).map(|pin| {
+        rpc_pool_manager.execute_with_endpoint(
+            RpcRequest::GetSlot,
+            move |_client| async move {
+                let now_in_flight = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                most_in_flight.fetch_max(now_in_flight, Ordering::SeqCst);
+                sleep(Duration::from_millis(100)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok::<u64, ClientError>(pin)
+            },
+            Some(pin),
+        )
+    })));
+
+    // every request was in flight at once on the one runtime, not one after another
+    assert_eq!(most_in_flight.load(Ordering::SeqCst), 8);
+    assert!(started_at.elapsed() < Duration::from_millis(800));
+
+    // and each stayed on the endpoint its pin maps to
+    for (pin, result) in (0..8u64).zip(results) {
+        let (result, endpoint) = result.unwrap();
+        assert_eq!(result, pin);
+        assert_eq!(endpoint.id, pin as usize % 2);
+    }
+}
+
+#[test]
+fn test_pinned_execute_fails_over_while_its_endpoint_circuit_is_open() {
+    let rpc_pool_manager = stand_in_rpc_pool_manager(TWO_STAND_IN_ENDPOINTS);
+    let execute_pinned = || {
+        blocking_call(rpc_pool_manager.execute_with_endpoint(
+            RpcRequest::GetSlot,
+            |_client| async move { Ok::<u64, ClientError>(42) },
+            Some(0),
+        ))
+        .unwrap()
+        .1
+    };
+
+    let pinned = execute_pinned();
+    assert_eq!(pinned.id, 0);
+
+    for _ in 0..CIRCUIT_FAILURE_THRESHOLD {
+        rpc_pool_manager.record_failure(&pinned, Duration::from_millis(100));
+    }
+
+    assert_eq!(
+        execute_pinned(),
+        RpcEndpoint {
+            id: 1,
+            name: "stand-in-b".to_string(),
+            url: "http://127.0.0.1:2".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_execute_gives_up_at_its_deadline() {
+    let rpc_pool_manager = stand_in_rpc_pool_manager(
+        r#"
+cluster = "mainnet"
+
+[retry]
+max_attempts = 1000
+base_delay_ms = 1
+max_delay_ms = 1
+deadline_ms = 200
+
+[[endpoints]]
+name = "deadline-test"
+cluster = "mainnet"
+url = "http://127.0.0.1:1"
+requests_per_second = 1000
+"#,
+    );
+    let calls = &AtomicU32::new(0);
+
+    let started_at = Instant::now();
+    let result = blocking_call(rpc_pool_manager.execute(
+        RpcRequest::GetSlot,
+        move |_client| async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<u64, ClientError>(rpc_response_error(-32000, "Slot skipped"))
+        },
+        Some(0),
+    ));
+
+    // the failures open the only endpoint's circuit long before the attempts run out, and
+    // the wait for it ends at the deadline rather than when the circuit closes
+    assert!(matches!(result, Err(RpcError::NoAvailableEndpoint)));
+    assert_eq!(calls.load(Ordering::SeqCst), CIRCUIT_FAILURE_THRESHOLD);
+    assert!(started_at.elapsed() >= Duration::from_millis(200));
+    assert!(started_at.elapsed() < Duration::from_millis(1_000));
+}
+
+#[test]
+fn test_endpoint_without_credits_left_is_skipped() {
+    let rpc_pool_manager = stand_in_rpc_pool_manager(
+        r#"
+cluster = "mainnet"
+
+[[endpoints]]
+name = "stand-in-a"
+cluster = "mainnet"
+url = "http://127.0.0.1:1"
+requests_per_second = 1000
+daily_credit_budget = 10
+
+[endpoints.credits]
+getTransaction = 10
+
+[[endpoints]]
+name = "stand-in-b"
+cluster = "mainnet"
+url = "http://127.0.0.1:2"
+requests_per_second = 1000
+"#,
+    );
+    let execute_pinned = |method| {
+        blocking_call(rpc_pool_manager.execute_with_endpoint(
+            method,
+            |_client| async move { Ok::<u64, ClientError>(42) },
+            Some(0),
+        ))
+        .unwrap()
+        .1
+        .id
+    };
+
+    // the pinned endpoint is used until its budget runs out, then the call fails over
+    assert_eq!(execute_pinned(RpcRequest::GetTransaction), 0);
+    assert_eq!(execute_pinned(RpcRequest::GetTransaction), 1);
+    assert_eq!(execute_pinned(RpcRequest::GetSlot), 1);
+
+    let rpc_pool_manager = stand_in_rpc_pool_manager(
+        r#"
+cluster = "mainnet"
+
+[[endpoints]]
+name = "stand-in-a"
+cluster = "mainnet"
+url = "http://127.0.0.1:1"
+requests_per_second = 1000
+monthly_credit_budget = 1
+"#,
+    );
+    let calls = &AtomicU32::new(0);
+    let execute = || {
+        blocking_call(rpc_pool_manager.execute(
+            RpcRequest::GetSlot,
+            move |_client| async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok::<u64, ClientError>(42)
+            },
+            Some(0),
+        ))
+    };
+
+    // with no endpoint left to fail over to the call is refused without being sent
+    assert_eq!(execute().unwrap(), 42);
+    assert!(matches!(execute(), Err(RpcError::BudgetExhausted(_))));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_unbilled_errors_refund_their_credits() {
+    let rpc_pool_manager = stand_in_rpc_pool_manager(
+        r#"
+cluster = "mainnet"
+
+[retry]
+max_attempts = 1
+
+[[endpoints]]
+name = "refund-test"
+cluster = "mainnet"
+url = "http://127.0.0.1:1"
+requests_per_second = 1000
+daily_credit_budget = 1
+"#,
+    );
+    let execute = |error: fn() -> ClientError| {
+        blocking_call(rpc_pool_manager.execute(
+            RpcRequest::GetSlot,
+            move |_client| async move { Err::<u64, ClientError>(error()) },
+            Some(0),
+        ))
+    };
+
+    // a timed out request never reached the provider, so its credit is handed back
+    let result = execute(|| {
+        ClientError::from(ClientErrorKind::Io(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            "timed out",
+        )))
+    });
+    assert!(matches!(result, Err(RpcError::TransientNetwork(_))));
+
+    // a node that answered, even with an error, was billed for it
+    let result = execute(|| rpc_response_error(-32000, "Slot skipped"));
+    assert!(matches!(result, Err(RpcError::NodeBehind(_))));
+
+    let result = execute(|| rpc_response_error(-32000, "Slot skipped"));
+    assert!(matches!(result, Err(RpcError::BudgetExhausted(_))));
+}
+
+#[test]
+fn test_credit_budget_rolls_over_with_the_day_and_month() {
+    let january_30 = Date::from_calendar_date(2026, Month::January, 30).unwrap();
+    let mut budget = CreditBudget {
+        daily_limit: Some(10),
+        monthly_limit: Some(15),
+        daily_used: 10,
+        monthly_used: 12,
+        day: january_30,
+    };
+
+    budget.roll_over_to(january_30);
+    assert_eq!((budget.daily_used, budget.monthly_used), (10, 12));
+
+    // a new day frees the daily budget, the month keeps counting
+    budget.roll_over_to(january_30.next_day().unwrap());
+    assert_eq!((budget.daily_used, budget.monthly_used), (0, 12));
+
+    budget.roll_over_to(Date::from_calendar_date(2026, Month::February, 1).unwrap());
+    assert_eq!((budget.daily_used, budget.monthly_used), (0, 0));
+
+    // a budget exhausted yesterday is spendable again today
+    let mut budget = CreditBudget::new(Some(10), None, 10, 10);
+    assert!(!budget.can_afford(1));
+    budget.day = budget.day.previous_day().unwrap();
+    assert!(budget.can_afford(10));
+    assert_eq!(budget.daily_used, 0);
+}
+
+#[test]
+fn test_credit_usage_flush_persists_to_dragonfly() {
+    let dragonfly = dragonfly_client();
+    let endpoint_name = format!(
+        "flush-test-{}",
+        OffsetDateTime::now_utc().unix_timestamp_nanos()
+    );
+    let pending: PendingCreditUsage = Arc::new(Mutex::new(HashMap::new()));
+    pending.lock().unwrap().insert(endpoint_name.clone(), 7);
+
+    let mut conn = None;
+    flush_credit_usage(&dragonfly, &mut conn, &pending);
+    assert!(pending.lock().unwrap().is_empty());
+    assert_eq!(
+        get_credit_usage(&dragonfly, &endpoint_name).unwrap(),
+        (7, 7)
+    );
+
+    // later flushes add to what is already stored
+    pending.lock().unwrap().insert(endpoint_name.clone(), 3);
+    flush_credit_usage(&dragonfly, &mut conn, &pending);
+    assert_eq!(
+        get_credit_usage(&dragonfly, &endpoint_name).unwrap(),
+        (10, 10)
+    );
+
+    // usage that could not be written waits for the next flush
+    let unreachable = redis::Client::open("redis://127.0.0.1:1/").unwrap();
+    pending.lock().unwrap().insert(endpoint_name.clone(), 5);
+    flush_credit_usage(&unreachable, &mut None, &pending);
+    assert_eq!(pending.lock().unwrap().get(&endpoint_name), Some(&5));
+}
+
+// A transaction with nothing in it but its signature and slot.
+fn stand_in_transaction(signature: &str, slot: u64) -> Value {
+    json!({
+        "slot": slot,
+        "blockTime": 1_700_000_000,
+        "transaction": {
+            "transaction": {
+                "signatures": [signature],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 0
+                    },
+                    "accountKeys": [Pubkey::new_unique().to_string()],
+                    "recentBlockhash": bs58::encode([0u8; 32]).into_string(),
+                    "instructions": []
+                }
+            },
+            "meta": {
+                "err": null,
+                "status": {"Ok": null},
+                "fee": 5_000,
+                "preBalances": [0],
+                "postBalances": [0],
+                "innerInstructions": [],
+                "logMessages": []
+            }
+        }
+    })
+}
+
+// Answers getTransaction at slot 42, and rejects batches with a 400 when `reject_batches`.
+// Counts every request it was sent and how many of them were batches.
+fn serve_transactions_stand_in(reject_batches: bool) -> (String, Arc<AtomicU32>, Arc<AtomicU32>) {
+    let batches = Arc::new(AtomicU32::new(0));
+    let counted = batches.clone();
+
+    let answer = |request: &Value| {
+        let signature = request["params"][0].as_str().unwrap();
+        json!({
+            "jsonrpc": "2.0",
+            "result": stand_in_transaction(signature, 42),
+            "id": request["id"]
+        })
+    };
+    let (url, requests) = serve_rpc_stand_in_with(move |request| match request {
+        Value::Array(_) if reject_batches => {
+            counted.fetch_add(1, Ordering::SeqCst);
+            (400, json!({"error": "batch requests are not supported"}))
+        }
+        Value::Array(items) => {
+            counted.fetch_add(1, Ordering::SeqCst);
+            (200, Value::Array(items.iter().map(answer).collect()))
+        }
+        request => (200, answer(request)),
+    });
+
+    (url, requests, batches)
+}
+
+#[test]
+fn test_batch_results_line_up_with_their_signatures() {
+    let signatures = (0..4).map(|_| Signature::new_unique()).collect::<Vec<_>>();
+    let served = signatures
+        .iter()
+        .map(|signature| signature.to_string())
+        .collect::<Vec<_>>();
+
+    // one item is gone for good, one is only missing until it is asked for on its own and
+    // one comes back empty, and the batch answers in reverse so only the ids line up
+    let (url, requests) = serve_rpc_stand_in_with(move |request| {
+        let answer = |item: &Value, batched: bool| {
+            let signature = item["params"][0].as_str().unwrap();
+            let index = served
+                .iter()
+                .position(|served| served == signature)
+                .unwrap();
+            let error = |code: i64, message: &str| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "error": {"code": code, "message": message},
+                    "id": item["id"]
+                })
+            };
+
+            match (index, batched) {
+                (1, _) => error(
+                    JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
+                    "Transaction history is not available",
+                ),
+                (2, true) => error(JSON_RPC_SERVER_ERROR_SLOT_SKIPPED, "Slot skipped"),
+                (3, _) => json!({"jsonrpc": "2.0", "result": null, "id": item["id"]}),
+                _ => json!({
+                    "jsonrpc": "2.0",
+                    "result": stand_in_transaction(signature, 10 + index as u64),
+                    "id": item["id"]
+                }),
+            }
+        };
+
+        match request {
+            Value::Array(items) => (
+                200,
+                Value::Array(items.iter().rev().map(|item| answer(item, true)).collect()),
+            ),
+            item => (200, answer(item, false)),
+        }
+    });
+    let rpc_pool_manager = stand_in_rpc_pool_manager(&format!(
+        r#"
+cluster = "mainnet"
+
+[[endpoints]]
+name = "batch-test"
+cluster = "mainnet"
+url = "{}"
+requests_per_second = 1000
+"#,
+        url
+    ));
+
+    let results = blocking_call(get_transactions(&rpc_pool_manager, &signatures, Some(0)));
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap().slot, 10);
+    assert!(matches!(results[1], Err(RpcError::NotFound(_))));
+    assert_eq!(results[2].as_ref().unwrap().slot, 12);
+    assert!(matches!(results[3], Err(RpcError::NotFound(_))));
+
+    // the batch, and the one item worth retrying on its own
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_batches_fall_back_to_single_requests() {
+    let stand_in_pool = |url: &str, supports_batch: bool| {
+        stand_in_rpc_pool_manager(&format!(
+            r#"
+cluster = "mainnet"
+
+[[endpoints]]
+name = "fallback-test"
+cluster = "mainnet"
+url = "{}"
+requests_per_second = 1000
+supports_batch = {}
+"#,
+            url, supports_batch
+        ))
+    };
+    let signatures = (0..3).map(|_| Signature::new_unique()).collect::<Vec<_>>();
+    let fetch_all = |rpc_pool_manager: &RpcPoolManager| {
+        let results = blocking_call(get_transactions(rpc_pool_manager, &signatures, Some(0)));
+        for (signature, result) in signatures.iter().zip(results) {
+            let tx = result.unwrap();
+            let (fetched, _message) = raw_message(&tx.transaction.transaction).unwrap();
+            assert_eq!(fetched, &signature.to_string());
+        }
+    };
+
+    // an endpoint configured without batch support is only sent single requests
+    let (url, requests, batches) = serve_transactions_stand_in(false);
+    fetch_all(&stand_in_pool(&url, false));
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+    assert_eq!(batches.load(Ordering::SeqCst), 0);
+
+    // an endpoint that rejects a batch gets its items one by one, and no batches after that
+    let (url, requests, batches) = serve_transactions_stand_in(true);
+    let rpc_pool_manager = stand_in_pool(&url, true);
+    fetch_all(&rpc_pool_manager);
+    assert_eq!(requests.load(Ordering::SeqCst), 4);
+    assert_eq!(batches.load(Ordering::SeqCst), 1);
+
+    fetch_all(&rpc_pool_manager);
+    assert_eq!(requests.load(Ordering::SeqCst), 7);
+    assert_eq!(batches.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_every_batched_request_takes_a_limiter_token() {
+    let (url, requests, batches) = serve_transactions_stand_in(false);
+    let rpc_pool_manager = stand_in_rpc_pool_manager(&format!(
+        r#"
+cluster = "mainnet"
+batch_size = 5
+
+[[endpoints]]
+name = "limiter-test"
+cluster = "mainnet"
+url = "{}"
+requests_per_second = 10
+"#,
+        url
+    ));
+    let signatures = (0..15).map(|_| Signature::new_unique()).collect::<Vec<_>>();
+
+    let started_at = Instant::now();
+    let results = blocking_call(get_transactions(&rpc_pool_manager, &signatures, Some(0)));
+
+    assert!(results.iter().all(|result| result.is_ok()));
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+    assert_eq!(batches.load(Ordering::SeqCst), 3);
+
+    // two batches of five empty the bucket of ten, the third waits for five more tokens
+    assert!(started_at.elapsed() >= Duration::from_millis(450));
+    assert!(started_at.elapsed() < Duration::from_millis(2_000));
+}
+
+fn stand_in_transaction_cache() -> TransactionCache {
+    let dir = std::env::temp_dir().join(format!(
+        "tx-cache-{}-{}",
+        std::process::id(),
+        OffsetDateTime::now_utc().unix_timestamp_nanos()
+    ));
+    TransactionCache::new(dir).unwrap()
+}
+
+#[test]
+fn test_transaction_cache_round_trips_compressed_transactions() {
+    let tx_cache = stand_in_transaction_cache();
+    let signature = Signature::new_unique();
+    let tx: EncodedConfirmedTransactionWithStatusMeta =
+        serde_json::from_value(stand_in_transaction(&signature.to_string(), 42)).unwrap();
+
+    assert!(tx_cache.get(&signature).unwrap().is_none());
+
+    tx_cache.put(&signature, &tx).unwrap();
+    let cached = tx_cache.get(&signature).unwrap().unwrap();
+    assert_eq!(
+        serde_json::to_value(&cached).unwrap(),
+        serde_json::to_value(&tx).unwrap()
+    );
+
+    // a second put of the same signature is a no op
+    tx_cache.put(&signature, &tx).unwrap();
+    assert_eq!(tx_cache.get(&signature).unwrap().unwrap().slot, 42);
+}
+
+#[test]
+fn test_offline_cache_miss_is_an_offline_error() {
+    let tx_cache = stand_in_transaction_cache();
+    let cached = Signature::new_unique();
+    let tx: EncodedConfirmedTransactionWithStatusMeta =
+        serde_json::from_value(stand_in_transaction(&cached.to_string(), 7)).unwrap();
+    tx_cache.put(&cached, &tx).unwrap();
+
+    let rpc_pool_manager =
+        stand_in_rpc_pool_manager(TWO_STAND_IN_ENDPOINTS).with_transaction_cache(tx_cache, true);
+    let results = blocking_call(get_transactions(
+        &rpc_pool_manager,
+        &[cached, Signature::new_unique()],
+        Some(0),
+    ));
+
+    assert_eq!(results[0].as_ref().unwrap().slot, 7);
+    assert!(matches!(results[1], Err(RpcError::Offline(_))));
+}
+
+#[test]
+fn test_cached_transactions_are_served_before_the_network() {
+    let tx_cache = stand_in_transaction_cache();
+    let cached = Signature::new_unique();
+    let missing = Signature::new_unique();
+    let tx: EncodedConfirmedTransactionWithStatusMeta =
+        serde_json::from_value(stand_in_transaction(&cached.to_string(), 7)).unwrap();
+    tx_cache.put(&cached, &tx).unwrap();
+
+    let (url, requests, _batches) = serve_transactions_stand_in(false);
+    let rpc_pool_manager = stand_in_rpc_pool_manager(&format!(
+        r#"
+cluster = "mainnet"
+
+[[endpoints]]
+name = "cache-test"
+cluster = "mainnet"
+url = "{}"
+requests_per_second = 1000
+"#,
+        url
+    ))
+    .with_transaction_cache(tx_cache.clone(), false);
+
+    // only the miss goes out, and what it fetched is cached for the next run
+    let results = blocking_call(get_transactions(
+        &rpc_pool_manager,
+        &[cached, missing],
+        Some(0),
+    ));
+    assert_eq!(results[0].as_ref().unwrap().slot, 7);
+    assert_eq!(results[1].as_ref().unwrap().slot, 42);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+    assert_eq!(tx_cache.get(&missing).unwrap().unwrap().slot, 42);
+
+    let results = blocking_call(get_transactions(
+        &rpc_pool_manager,
+        &[cached, missing],
+        Some(0),
+    ));
+    assert!(results.iter().all(|result| result.is_ok()));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_slot_claims_are_exclusive_and_expire_after_a_crash() {
+    let dragonfly = dragonfly_client();
+    // far past any real slot so the test never collides with a crawl, and still exact as a
+    // sorted set score
+    let slot = (1 << 50) + OffsetDateTime::now_utc().unix_timestamp_nanos() as u64 % 1_000_000;
+    let crashed_slot = slot + 1;
+
+    // the first worker gets the slot, an overlapping range on another worker does not
+    assert!(claim_slot(&dragonfly, slot, 60_000).unwrap());
+    assert!(!claim_slot(&dragonfly, slot, 60_000).unwrap());
+
+    // a worker that dies holding a claim frees the slot once the claim expires
+    assert!(claim_slot(&dragonfly, crashed_slot, 50).unwrap());
+    assert!(!claim_slot(&dragonfly, crashed_slot, 50).unwrap());
+    thread::sleep(Duration::from_millis(100));
+    assert!(claim_slot(&dragonfly, crashed_slot, 60_000).unwrap());
+
+    // only slots that succeeded are skipped when the range is resumed
+    insert_slot_crawl_status(
+        &dragonfly,
+        &SlotCrawlStatusRow {
+            slot,
+            status: CrawlStatus::Succeeded,
+            transaction_count: 3,
+            error: None,
+        },
+    )
+    .unwrap();
+    insert_slot_crawl_status(
+        &dragonfly,
+        &SlotCrawlStatusRow {
+            slot: crashed_slot,
+            status: CrawlStatus::Failed,
+            transaction_count: 0,
+            error: Some("block fetch failed".to_string()),
+        },
+    )
+    .unwrap();
+    let crawled_slots = get_crawled_slots(&dragonfly, slot, crashed_slot).unwrap();
+    assert!(crawled_slots.contains(&slot));
+    assert!(!crawled_slots.contains(&crashed_slot));
+
+    let mut conn = dragonfly.get_connection().unwrap();
+    let _: () = redis::pipe()
+        .cmd("ZREM")
+        .arg(REDIS_CRAWLED_SLOTS_KEY)
+        .arg(slot)
+        .ignore()
+        .cmd("DEL")
+        .arg(format!("{}:{}", REDIS_SLOT_CLAIM_PREFIX, slot))
+        .arg(format!("{}:{}", REDIS_SLOT_CLAIM_PREFIX, crashed_slot))
+        .arg(format!("{}:{}", REDIS_SLOT_STATUS_PREFIX, slot))
+        .arg(format!("{}:{}", REDIS_SLOT_STATUS_PREFIX, crashed_slot))
+        .ignore()
+        .query(&mut conn)
+        .unwrap();
+}
@@ -1,11 +1,12 @@
 use crate::{
     db::client::db_client,
     pump_fun::tokens::PumpFunToken,
-    rpc::pool::{RpcError, RpcPoolManager},
+    rpc::pool::RpcPoolManager,
     token::{queries::has_token, table::TokenRow},
     utils::{blocking::blocking_call, log::log_time},
 };
 use crossbeam::channel::{Receiver, Sender};
+use solana_client::rpc_request::RpcRequest;
 use solana_sdk::program_pack::Pack;
 use std::thread;
 
@@ -32,13 +33,14 @@ pub fn get_token_accounts_meta(
 
         let handle = thread::spawn(move || {
             while let Ok((mint_address, bonding_curve_address)) = pump_fun_tokens_rx.recv() {
-                let mint_account = rpc_pool_manager.execute(
-                    |client| client.get_account(&mint_address),
+                let mint_account = blocking_call(rpc_pool_manager.execute(
+                    RpcRequest::GetAccountInfo,
+                    |client| async move { client.get_account(&mint_address).await },
                     Some(thread_index as u64),
-                );
+                ));
 
                 match mint_account {
-                    Err(RpcError::ClientError(error)) => {
+                    Err(error) => {
                         println!(
                             "{} Error getting mint account info.\n{:?}\nSkipping",
                             log_tag, error
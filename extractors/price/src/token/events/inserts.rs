@@ -0,0 +1,46 @@
+use super::table::{TokenEventRow, CLICKHOUSE_TOKEN_EVENTS_TABLE_NAME};
+use clickhouse::Client;
+
+pub async fn insert_token_event(
+    client: &Client,
+    token_event: &TokenEventRow,
+) -> Result<(), clickhouse::error::Error> {
+    // names, symbols and uris are chosen by whoever launched the token, so they are bound
+    client
+        .query(
+            format!(
+                "
+            INSERT INTO {} (
+                mint_address,
+                bonding_curve_address,
+                kind,
+                transaction_signature,
+                slot,
+                block_time,
+                user_address,
+                name,
+                symbol,
+                uri,
+                pool_address
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ",
+                CLICKHOUSE_TOKEN_EVENTS_TABLE_NAME,
+            )
+            .as_str(),
+        )
+        .bind(&token_event.mint_address)
+        .bind(&token_event.bonding_curve_address)
+        .bind(token_event.kind.as_str())
+        .bind(&token_event.transaction_signature)
+        .bind(token_event.slot)
+        .bind(token_event.block_time.unix_timestamp())
+        .bind(&token_event.user_address)
+        .bind(&token_event.name)
+        .bind(&token_event.symbol)
+        .bind(&token_event.uri)
+        .bind(&token_event.pool_address)
+        .execute()
+        .await?;
+
+    Ok(())
+}
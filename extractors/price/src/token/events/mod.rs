@@ -0,0 +1,3 @@
+pub mod inserts;
+pub mod store;
+pub mod table;
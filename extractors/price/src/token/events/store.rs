@@ -0,0 +1,48 @@
+use super::{inserts::insert_token_event, table::TokenEventRow};
+use crate::{
+    constants::STORE_CONCURRENCY,
+    db::client::db_client,
+    utils::{blocking::blocking_call, log::log_time},
+};
+use crossbeam::channel::Receiver;
+use std::thread;
+
+const CONCURRENCY: usize = STORE_CONCURRENCY;
+
+pub fn store_token_events(
+    token_events_rx: &Receiver<TokenEventRow>,
+) -> Vec<thread::JoinHandle<()>> {
+    let mut handles = Vec::with_capacity(CONCURRENCY);
+
+    for thread_index in 0..CONCURRENCY {
+        let log_tag = format!(
+            "                 {} store token events #{} | ",
+            log_time(),
+            thread_index
+        );
+
+        let token_events_rx = token_events_rx.clone();
+
+        let handle = thread::spawn(move || {
+            let db_client = db_client();
+
+            while let Ok(token_event) = token_events_rx.recv() {
+                println!(
+                    "{} Storing {} event for {}",
+                    log_tag,
+                    token_event.kind.as_str(),
+                    token_event.mint_address
+                );
+
+                let client = db_client.clone();
+                blocking_call(
+                    async move { insert_token_event(&client, &token_event).await.unwrap() },
+                );
+            }
+        });
+
+        handles.push(handle);
+    }
+
+    handles
+}
@@ -0,0 +1,151 @@
+use crate::{db::migrate::add_columns, pools::table::CLICKHOUSE_POOLS_TABLE_NAME};
+use clickhouse::Client;
+use time::OffsetDateTime;
+
+pub const CLICKHOUSE_TOKEN_EVENTS_TABLE_NAME: &str = "token_events";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenEventKind {
+    Create = 1,
+    Complete = 2,
+    Migrate = 3,
+}
+
+impl TokenEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Complete => "complete",
+            Self::Migrate => "migrate",
+        }
+    }
+}
+
+// Launch, bonding curve completion and raydium migration of a token. Name, symbol and uri are
+// only known on create, the pool address only on migrate.
+#[derive(Debug, Clone)]
+pub struct TokenEventRow {
+    pub mint_address: String,
+    pub bonding_curve_address: String,
+    pub kind: TokenEventKind,
+    pub transaction_signature: String,
+    pub slot: u64,
+    pub block_time: OffsetDateTime,
+    pub user_address: String,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub pool_address: String,
+}
+
+pub async fn create_token_events_table(client: &Client) -> Result<(), clickhouse::error::Error> {
+    client
+        .query(
+            format!(
+                "
+        CREATE TABLE IF NOT EXISTS {} (
+            mint_address String,
+            bonding_curve_address String,
+            kind Enum8('create' = {}, 'complete' = {}, 'migrate' = {}),
+            transaction_signature String,
+            slot UInt64,
+            block_time DateTime,
+            user_address String,
+            name String,
+            symbol String,
+            uri String,
+            pool_address String,
+        )
+        ENGINE = ReplacingMergeTree()
+        ORDER BY (mint_address, kind, transaction_signature)
+    ",
+                CLICKHOUSE_TOKEN_EVENTS_TABLE_NAME,
+                TokenEventKind::Create as u8,
+                TokenEventKind::Complete as u8,
+                TokenEventKind::Migrate as u8,
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+
+    // migrations were added after create and complete
+    client
+        .query(
+            format!(
+                "ALTER TABLE {} MODIFY COLUMN kind Enum8('create' = {}, 'complete' = {}, 'migrate' = {})",
+                CLICKHOUSE_TOKEN_EVENTS_TABLE_NAME,
+                TokenEventKind::Create as u8,
+                TokenEventKind::Complete as u8,
+                TokenEventKind::Migrate as u8,
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+    add_columns(
+        client,
+        CLICKHOUSE_TOKEN_EVENTS_TABLE_NAME,
+        &["pool_address String"],
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub const CLICKHOUSE_TOKEN_LIFECYCLES_VIEW_NAME: &str = "token_lifecycles";
+
+// One row per token with when it launched, completed its curve and moved to raydium. Only
+// migrate events name the pool, tokens that were never seen migrating fall back to a pool
+// stored for their mint. Replaced on start so existing views pick up changes.
+pub async fn create_token_lifecycles_view(client: &Client) -> Result<(), clickhouse::error::Error> {
+    client
+        .query(
+            format!(
+                "
+        CREATE OR REPLACE VIEW {} AS
+        SELECT
+            lifecycles.mint_address AS mint_address,
+            lifecycles.bonding_curve_address AS bonding_curve_address,
+            lifecycles.created_slot AS created_slot,
+            lifecycles.created_at AS created_at,
+            lifecycles.completed_slot AS completed_slot,
+            lifecycles.completed_at AS completed_at,
+            lifecycles.migrated_slot AS migrated_slot,
+            lifecycles.migrated_at AS migrated_at,
+            if(
+                lifecycles.pool_address != '',
+                lifecycles.pool_address,
+                pools.pool_address
+            ) AS pool_address
+        FROM (
+            SELECT
+                mint_address,
+                any(bonding_curve_address) AS bonding_curve_address,
+                minOrNullIf(slot, kind = 'create') AS created_slot,
+                minOrNullIf(block_time, kind = 'create') AS created_at,
+                minOrNullIf(slot, kind = 'complete') AS completed_slot,
+                minOrNullIf(block_time, kind = 'complete') AS completed_at,
+                minOrNullIf(slot, kind = 'migrate') AS migrated_slot,
+                minOrNullIf(block_time, kind = 'migrate') AS migrated_at,
+                anyIf(pool_address, pool_address != '') AS pool_address
+            FROM {}
+            GROUP BY mint_address
+        ) AS lifecycles
+        LEFT JOIN (
+            SELECT mint_address, any(pool_address) AS pool_address
+            FROM {} FINAL
+            GROUP BY mint_address
+        ) AS pools ON pools.mint_address = lifecycles.mint_address
+    ",
+                CLICKHOUSE_TOKEN_LIFECYCLES_VIEW_NAME,
+                CLICKHOUSE_TOKEN_EVENTS_TABLE_NAME,
+                CLICKHOUSE_POOLS_TABLE_NAME,
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+
+    Ok(())
+}
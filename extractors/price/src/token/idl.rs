@@ -13,3 +13,19 @@ pub enum TokenTransferAccountsOrder {
     Destination = 1,
     Authority = 2,
 }
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize, BorshSchema, Debug)]
+pub struct TokenTransferCheckedInstructionData {
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub enum TokenTransferCheckedAccountsOrder {
+    Source = 0,
+    Mint = 1,
+    Destination = 2,
+    Authority = 3,
+}
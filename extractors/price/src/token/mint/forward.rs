@@ -0,0 +1,218 @@
+use crate::{
+    crawl_status::{
+        queries::has_crawled_signature,
+        table::{CrawlStatus, CrawlStatusOperation, CrawlStatusRow},
+    },
+    dragonfly::client::dragonfly_client,
+    pump_fun::tokens::{MintAddress, PumpFunToken},
+    rpc::pool::RpcPoolManager,
+    signatures::config::{build_signatures_config, build_signatures_forward_config},
+    trades::signatures::TradeSignature,
+    utils::{blocking::blocking_call, log::log_time},
+};
+use crossbeam::channel::{Receiver, Sender};
+use dashmap::DashSet;
+use solana_client::{
+    rpc_request::RpcRequest, rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::time::sleep;
+
+pub const DEFAULT_FORWARD_POLL_INTERVAL_MS: u64 = 10_000;
+
+pub type ScheduledToken = (PumpFunToken, Instant);
+
+// Tokens whose history is complete and are now polled at the head. A token is only ever
+// scheduled once, however often it comes back through the pipeline.
+#[derive(Clone)]
+pub struct ForwardSchedule {
+    tx: Sender<ScheduledToken>,
+    following: Arc<DashSet<MintAddress>>,
+}
+
+impl ForwardSchedule {
+    pub fn new(tx: Sender<ScheduledToken>) -> Self {
+        Self {
+            tx,
+            following: Arc::new(DashSet::new()),
+        }
+    }
+
+    pub fn follow(&self, token: PumpFunToken) {
+        let (mint_address, _bonding_curve_address) = token;
+        if !self.following.insert(mint_address) {
+            return;
+        }
+
+        println!("{} Following {} at the head", log_time(), mint_address);
+        self.tx.send((token, Instant::now())).unwrap();
+    }
+}
+
+// Newest first, from the head down to (excluding) `until`.
+async fn get_signatures_since(
+    rpc_pool_manager: &RpcPoolManager,
+    mint_address: &MintAddress,
+    until: Option<String>,
+    limit: usize,
+    pin: Option<u64>,
+) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, String> {
+    let mut before: Option<String> = None;
+    let mut signatures = Vec::new();
+
+    loop {
+        let page = rpc_pool_manager
+            .execute(
+                RpcRequest::GetSignaturesForAddress,
+                |client| {
+                    let config =
+                        build_signatures_config(before.clone(), until.clone(), Some(limit));
+                    async move {
+                        client
+                            .get_signatures_for_address_with_config(mint_address, config)
+                            .await
+                    }
+                },
+                pin,
+            )
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let page_count = page.len();
+        before = page.last().map(|signature| signature.signature.clone());
+        signatures.extend(page);
+
+        // without a cursor there is nothing to page towards, the head is enough
+        if page_count < limit || until.is_none() {
+            break;
+        }
+    }
+
+    Ok(signatures)
+}
+
+pub fn token_mint_forward_threads(
+    token_pump_fun_signatures_tx: &Sender<TradeSignature>,
+    crawl_status_tx: &Sender<CrawlStatusOperation>,
+    forward_tokens_rx: &Receiver<ScheduledToken>,
+    forward_tokens_tx: &Sender<ScheduledToken>,
+    rpc_pool_manager: &RpcPoolManager,
+    poll_interval: Duration,
+) -> Vec<thread::JoinHandle<()>> {
+    let concurrency = rpc_pool_manager.get_rpc_nodes_count();
+    let mut handles = Vec::with_capacity(concurrency);
+
+    for thread_index in 0..concurrency {
+        let log_tag = format!(
+            "         {} token forward signatures #{} | ",
+            log_time(),
+            thread_index
+        );
+
+        let token_pump_fun_signatures_tx = token_pump_fun_signatures_tx.clone();
+        let crawl_status_tx = crawl_status_tx.clone();
+        let forward_tokens_rx = forward_tokens_rx.clone();
+        let forward_tokens_tx = forward_tokens_tx.clone();
+        let rpc_pool_manager = rpc_pool_manager.clone();
+
+        let handle = thread::spawn(move || {
+            let dragonfly_client = dragonfly_client();
+
+            blocking_call(async move {
+                // tokens are requeued with the same interval, so the queue stays in due order
+                while let Ok((pump_fun_token, due_at)) = forward_tokens_rx.recv() {
+                    sleep(due_at.saturating_duration_since(Instant::now())).await;
+
+                    let (mint_address, _bonding_curve_address) = pump_fun_token;
+                    let config = build_signatures_forward_config(
+                        &dragonfly_client,
+                        &mint_address.to_string(),
+                        None,
+                    );
+                    let (newest_signature, limit) = match config {
+                        Ok(config) => config,
+                        Err(error) => {
+                            println!(
+                                "{} Failed to read newest signature for {}: {}",
+                                log_tag, mint_address, error
+                            );
+                            forward_tokens_tx
+                                .send((pump_fun_token, Instant::now() + poll_interval))
+                                .unwrap();
+                            continue;
+                        }
+                    };
+
+                    let signatures = get_signatures_since(
+                        &rpc_pool_manager,
+                        &mint_address,
+                        newest_signature,
+                        limit,
+                        Some(thread_index as u64),
+                    )
+                    .await;
+
+                    match signatures {
+                        Err(error) => {
+                            println!(
+                                "{} Error getting new token signatures for {}: {}",
+                                log_tag, mint_address, error
+                            );
+                        }
+                        Ok(signatures) => {
+                            if !signatures.is_empty() {
+                                println!(
+                                    "{} Got {} new token signatures for {}",
+                                    log_tag,
+                                    signatures.len(),
+                                    mint_address
+                                );
+                            }
+
+                            for (signature_index, signature) in signatures.iter().enumerate() {
+                                if let Ok(has_crawled) =
+                                    has_crawled_signature(&dragonfly_client, &signature.signature)
+                                {
+                                    if has_crawled {
+                                        continue;
+                                    }
+                                }
+
+                                let crawl_status = CrawlStatusRow {
+                                    account_address: mint_address.to_string(),
+                                    transaction_signature: signature.signature.clone(),
+                                    slot: signature.slot,
+                                    relative_transaction_index: signature_index as u64,
+                                    status: CrawlStatus::Pending,
+                                    is_first_account_signature: false,
+                                    error: None,
+                                };
+                                crawl_status_tx
+                                    .send(CrawlStatusOperation::Create(crawl_status))
+                                    .unwrap();
+                                token_pump_fun_signatures_tx
+                                    .send(TradeSignature::TokenMint(
+                                        mint_address,
+                                        signature.signature.clone(),
+                                    ))
+                                    .unwrap();
+                            }
+                        }
+                    }
+
+                    forward_tokens_tx
+                        .send((pump_fun_token, Instant::now() + poll_interval))
+                        .unwrap();
+                }
+            })
+        });
+
+        handles.push(handle);
+    }
+
+    handles
+}
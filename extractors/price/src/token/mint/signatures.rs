@@ -1,33 +1,32 @@
+use super::forward::ForwardSchedule;
 use crate::{
     crawl_status::{
         queries::{has_crawled_signature, CrawlStatusQueryError},
         table::{CrawlStatus, CrawlStatusOperation, CrawlStatusRow},
     },
     dragonfly::client::dragonfly_client,
-    pump_fun::tokens::{MintAddress, PumpFunToken},
-    rpc::{
-        clients::get_rpc_nodes_count,
-        pool::{RpcError, RpcPoolManager},
-    },
+    pump_fun::tokens::PumpFunToken,
+    rpc::pool::RpcPoolManager,
     signatures::config::{build_signatures_config, build_signatures_window_config},
-    utils::log::log_time,
+    trades::signatures::TradeSignature,
+    utils::{blocking::blocking_call, log::log_time},
 };
 use crossbeam::channel::{Receiver, Sender};
+use solana_client::rpc_request::RpcRequest;
 use std::thread;
 
-pub type TokenMintSignatures = (MintAddress, String);
-
 // TODO: whilst we are crawling for mint addresses in signatures from the pump fun program,
 // we will come across various trades that we should send to trades_tx channel sender
-// als we will find raydium pools that we should send to raydium_pools_tx channel sender
+// als we will find raydium pools that we should send to pools_tx channel sender
 pub fn get_token_mint_signatures(
-    token_pump_fun_signatures_tx: &Sender<TokenMintSignatures>,
+    token_pump_fun_signatures_tx: &Sender<TradeSignature>,
     crawl_status_tx: &Sender<CrawlStatusOperation>,
     pump_fun_tokens_rx: &Receiver<PumpFunToken>,
     pump_fun_tokens_tx: &Sender<PumpFunToken>,
     rpc_pool_manager: &RpcPoolManager,
+    forward_schedule: Option<&ForwardSchedule>,
 ) -> Vec<thread::JoinHandle<()>> {
-    let concurrency = get_rpc_nodes_count();
+    let concurrency = rpc_pool_manager.get_rpc_nodes_count();
     let mut handles = Vec::with_capacity(concurrency);
 
     for thread_index in 0..concurrency {
@@ -43,6 +42,7 @@ pub fn get_token_mint_signatures(
         let pump_fun_tokens_tx = pump_fun_tokens_tx.clone();
 
         let rpc_pool_manager = rpc_pool_manager.clone();
+        let forward_schedule = forward_schedule.cloned();
 
         let handle = thread::spawn(move || {
             let dragonfly_client = dragonfly_client();
@@ -61,6 +61,9 @@ pub fn get_token_mint_signatures(
                             " {} Token mint history complete for {}",
                             log_tag, mint_address
                         );
+                        if let Some(forward_schedule) = &forward_schedule {
+                            forward_schedule.follow(pump_fun_token);
+                        }
                         continue;
                     }
                 }
@@ -71,18 +74,22 @@ pub fn get_token_mint_signatures(
                     log_tag, oldest_signature, limit
                 );
 
-                let signatures = rpc_pool_manager.execute(
+                let signatures = blocking_call(rpc_pool_manager.execute(
+                    RpcRequest::GetSignaturesForAddress,
                     |client| {
-                        client.get_signatures_for_address_with_config(
-                            &mint_address,
-                            build_signatures_config(oldest_signature, None, Some(limit)),
-                        )
+                        let config =
+                            build_signatures_config(oldest_signature.clone(), None, Some(limit));
+                        async move {
+                            client
+                                .get_signatures_for_address_with_config(&mint_address, config)
+                                .await
+                        }
                     },
                     Some(thread_index as u64),
-                );
+                ));
 
                 match signatures {
-                    Err(RpcError::ClientError(error)) => {
+                    Err(error) => {
                         println!(
                             "{} Error getting token signatures.\n{:?}\nSkipping",
                             log_tag, error
@@ -140,7 +147,10 @@ pub fn get_token_mint_signatures(
                                 .send(CrawlStatusOperation::Create(crawl_status))
                                 .unwrap();
                             token_pump_fun_signatures_tx
-                                .send((mint_address, signature.signature.clone()))
+                                .send(TradeSignature::TokenMint(
+                                    mint_address,
+                                    signature.signature.clone(),
+                                ))
                                 .unwrap();
                         }
 
@@ -150,6 +160,8 @@ pub fn get_token_mint_signatures(
                                 log_tag
                             );
                             pump_fun_tokens_tx.send(pump_fun_token).unwrap();
+                        } else if let Some(forward_schedule) = &forward_schedule {
+                            forward_schedule.follow(pump_fun_token);
                         }
                     }
                 }
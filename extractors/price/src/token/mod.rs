@@ -1,7 +1,9 @@
 pub mod accounts;
+pub mod events;
 pub mod idl;
 pub mod inserts;
 pub mod mint;
+pub mod program;
 pub mod queries;
 pub mod store;
 pub mod table;
@@ -0,0 +1,27 @@
+pub const TOKEN_PROGRAM_ADDRESS: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM_ADDRESS: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+pub fn is_token_program(program_address: &str) -> bool {
+    program_address == TOKEN_PROGRAM_ADDRESS || program_address == TOKEN_2022_PROGRAM_ADDRESS
+}
+
+// Mint of wrapped SOL, the quote side of most pools
+pub const WRAPPED_SOL_MINT_ADDRESS: &str = "So11111111111111111111111111111111111111112";
+
+// Stablecoins quote wrapped SOL as well as tokens
+pub const USDC_MINT_ADDRESS: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+pub const USDT_MINT_ADDRESS: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BJEYenB";
+
+fn quote_rank(mint_address: &str) -> u8 {
+    match mint_address {
+        USDC_MINT_ADDRESS | USDT_MINT_ADDRESS => 2,
+        WRAPPED_SOL_MINT_ADDRESS => 1,
+        _ => 0,
+    }
+}
+
+// The traded side of a pair is the one that isn't a quote mint, or the one quoted in the
+// other when both are. Pairs of two tokens, or of two stablecoins, trade the first side.
+pub fn is_traded_mint(mint_address: &str, other_mint_address: &str) -> bool {
+    quote_rank(mint_address) <= quote_rank(other_mint_address)
+}
@@ -1,6 +1,10 @@
 use crate::trades::db::table::{TradeDirection, TradeRow, CLICKHOUSE_TRADES_TABLE_NAME};
 use clickhouse::Client;
 
+fn nullable<T: ToString>(value: Option<T>) -> String {
+    value.map_or("NULL".to_string(), |value| value.to_string())
+}
+
 pub async fn insert_trade(
     client: &Client,
     trade: &TradeRow,
@@ -15,10 +19,26 @@ pub async fn insert_trade(
                 transaction_signature,
                 slot,
                 instruction_index,
+                trade_ordinal,
                 block_time,
                 coin_token_amount,
                 price_coin_token_amount,
-                direction
+                direction,
+                pool_address,
+                venue,
+                route_id,
+                route_hop_index,
+                trader_address,
+                fee_payer_address,
+                base_fee,
+                priority_fee,
+                compute_unit_price,
+                compute_unit_limit,
+                compute_units_consumed,
+                jito_tip,
+                virtual_sol_reserves,
+                virtual_token_reserves,
+                bonding_curve_progress
             ) VALUES (
                '{}',
                '{}',
@@ -28,7 +48,23 @@ pub async fn insert_trade(
                 {},
                 {},
                 {},
+                {},
+               '{}',
+               '{}',
+               '{}',
+                {},
+                {},
+               '{}',
                '{}',
+                {},
+                {},
+                {},
+                {},
+                {},
+                {},
+                {},
+                {},
+                {},
             )
             ",
                 CLICKHOUSE_TRADES_TABLE_NAME,
@@ -37,6 +73,7 @@ pub async fn insert_trade(
                 trade.transaction_signature,
                 trade.slot,
                 trade.instruction_index,
+                trade.trade_ordinal,
                 trade.block_time.unix_timestamp(),
                 trade.coin_token_amount,
                 trade.price_coin_token_amount,
@@ -45,6 +82,21 @@ pub async fn insert_trade(
                 } else {
                     "sell"
                 },
+                trade.pool_address,
+                trade.venue.as_str(),
+                nullable(trade.route_id),
+                nullable(trade.route_hop_index),
+                trade.trader_address,
+                trade.costs.fee_payer_address,
+                trade.costs.base_fee,
+                trade.costs.priority_fee,
+                trade.costs.compute_unit_price,
+                trade.costs.compute_unit_limit,
+                nullable(trade.costs.compute_units_consumed),
+                trade.costs.jito_tip,
+                nullable(trade.virtual_sol_reserves),
+                nullable(trade.virtual_token_reserves),
+                nullable(trade.bonding_curve_progress),
             )
             .as_str(),
         )
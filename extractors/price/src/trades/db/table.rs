@@ -1,7 +1,19 @@
+use crate::{
+    db::migrate::add_columns,
+    pump_fun::{
+        curve::{INITIAL_REAL_TOKEN_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES, TOKEN_TOTAL_SUPPLY},
+        params::table::CLICKHOUSE_PUMP_FUN_CURVE_PARAMS_TABLE_NAME,
+    },
+    token::table::CLICKHOUSE_TOKENS_TABLE_NAME,
+    transactions::costs::TransactionCosts,
+};
 use clickhouse::Client;
+use serde_repr::Deserialize_repr;
 use time::OffsetDateTime;
 
 pub const CLICKHOUSE_TRADES_TABLE_NAME: &str = "trades";
+const TRADES_SORTING_KEY: &str =
+    "slot, transaction_signature, instruction_index, coin_token_address, pool_address, direction, trade_ordinal";
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TradeDirection {
@@ -9,6 +21,58 @@ pub enum TradeDirection {
     Sell = 2,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize_repr)]
+#[repr(u8)]
+pub enum TradeVenue {
+    PumpFun = 1,
+    PumpSwap = 2,
+    RaydiumAmm = 3,
+    RaydiumCpmm = 4,
+    RaydiumClmm = 5,
+    OrcaWhirlpool = 6,
+    MeteoraDlmm = 7,
+}
+
+impl TradeVenue {
+    pub const ALL: [TradeVenue; 7] = [
+        TradeVenue::PumpFun,
+        TradeVenue::PumpSwap,
+        TradeVenue::RaydiumAmm,
+        TradeVenue::RaydiumCpmm,
+        TradeVenue::RaydiumClmm,
+        TradeVenue::OrcaWhirlpool,
+        TradeVenue::MeteoraDlmm,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PumpFun => "pump_fun",
+            Self::PumpSwap => "pump_swap",
+            Self::RaydiumAmm => "raydium_amm",
+            Self::RaydiumCpmm => "raydium_cpmm",
+            Self::RaydiumClmm => "raydium_clmm",
+            Self::OrcaWhirlpool => "orca_whirlpool",
+            Self::MeteoraDlmm => "meteora_dlmm",
+        }
+    }
+
+    pub fn column_type() -> String {
+        format!(
+            "Enum8({})",
+            Self::ALL
+                .iter()
+                .map(|venue| format!("'{}' = {}", venue.as_str(), *venue as u8))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+// Legs of a jupiter route carry the route's id and the hop they made, none otherwise. The
+// trader is the wallet swapping, or the route's user for legs. Costs are the transaction's,
+// repeated on each of its trades. Pump.fun trades carry their curve's reserves after the
+// trade and how far it is to completing. Trades of one instruction on the same pool in the same
+// direction, such as two legs of a route through one pool, are told apart by their ordinal.
 #[derive(Debug, Clone)]
 pub struct TradeRow {
     pub coin_token_address: String,
@@ -16,13 +80,73 @@ pub struct TradeRow {
     pub transaction_signature: String,
     pub slot: u64,
     pub instruction_index: u64,
+    pub trade_ordinal: u64,
     pub block_time: OffsetDateTime,
     pub coin_token_amount: u64,
     pub price_coin_token_amount: u64,
     pub direction: TradeDirection,
+    pub pool_address: String,
+    pub venue: TradeVenue,
+    pub route_id: Option<u64>,
+    pub route_hop_index: Option<u64>,
+    pub trader_address: String,
+    pub costs: TransactionCosts,
+    pub virtual_sol_reserves: Option<u64>,
+    pub virtual_token_reserves: Option<u64>,
+    pub bonding_curve_progress: Option<f64>,
 }
 
+// Reparsing a transaction inserts its trades again, they are merged back into one row each.
+// Inner instructions share their top-level instruction's index, so a trade is also told apart
+// by its coin, pool, direction and ordinal.
 pub async fn create_trades_table(client: &Client) -> Result<(), clickhouse::error::Error> {
+    create_trades_table_named(client, CLICKHOUSE_TRADES_TABLE_NAME).await?;
+
+    let venue_column = format!("venue {}", TradeVenue::column_type());
+    add_columns(
+        client,
+        CLICKHOUSE_TRADES_TABLE_NAME,
+        &[
+            "pool_address String",
+            venue_column.as_str(),
+            "route_id Nullable(UInt64)",
+            "route_hop_index Nullable(UInt64)",
+            "trader_address String",
+            "fee_payer_address String",
+            "base_fee UInt64",
+            "priority_fee UInt64",
+            "compute_unit_price UInt64",
+            "compute_unit_limit UInt64",
+            "compute_units_consumed Nullable(UInt64)",
+            "jito_tip UInt64",
+            "virtual_sol_reserves Nullable(UInt64)",
+            "virtual_token_reserves Nullable(UInt64)",
+            "bonding_curve_progress Nullable(Float64)",
+        ],
+    )
+    .await?;
+
+    // venues added since the column was created
+    client
+        .query(
+            format!(
+                "ALTER TABLE {} MODIFY COLUMN {}",
+                CLICKHOUSE_TRADES_TABLE_NAME, venue_column
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+
+    migrate_trades_sorting_key(client).await?;
+
+    Ok(())
+}
+
+async fn create_trades_table_named(
+    client: &Client,
+    table_name: &str,
+) -> Result<(), clickhouse::error::Error> {
     client
         .query(
             format!(
@@ -33,17 +157,190 @@ pub async fn create_trades_table(client: &Client) -> Result<(), clickhouse::erro
             transaction_signature String,
             slot UInt64,
             instruction_index UInt64,
+            trade_ordinal UInt64,
             block_time DateTime,
             coin_token_amount UInt64,
             price_coin_token_amount UInt64,
             direction Enum8('buy' = {}, 'sell' = {}),
+            pool_address String,
+            venue {},
+            route_id Nullable(UInt64),
+            route_hop_index Nullable(UInt64),
+            trader_address String,
+            fee_payer_address String,
+            base_fee UInt64,
+            priority_fee UInt64,
+            compute_unit_price UInt64,
+            compute_unit_limit UInt64,
+            compute_units_consumed Nullable(UInt64),
+            jito_tip UInt64,
+            virtual_sol_reserves Nullable(UInt64),
+            virtual_token_reserves Nullable(UInt64),
+            bonding_curve_progress Nullable(Float64),
         )
-        ENGINE = MergeTree()
-        ORDER BY (slot, transaction_signature, coin_token_address, instruction_index)
+        ENGINE = ReplacingMergeTree()
+        ORDER BY ({})
     ",
-                CLICKHOUSE_TRADES_TABLE_NAME,
+                table_name,
                 TradeDirection::Buy as u8,
                 TradeDirection::Sell as u8,
+                TradeVenue::column_type(),
+                TRADES_SORTING_KEY,
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+
+    Ok(())
+}
+
+// Tables created before trades had an ordinal are a plain MergeTree, or merge the legs of an
+// instruction through one pool into one row. Neither engine nor sorting key can be altered,
+// so the rows are copied into a new table, numbered from zero within their old sorting key,
+// and the tables are swapped.
+async fn migrate_trades_sorting_key(client: &Client) -> Result<(), clickhouse::error::Error> {
+    let sorting_keys = client
+        .query(
+            format!(
+                "
+        SELECT sorting_key FROM system.tables
+        WHERE database = currentDatabase() AND name = '{}' AND engine = 'ReplacingMergeTree'
+    ",
+                CLICKHOUSE_TRADES_TABLE_NAME,
+            )
+            .as_str(),
+        )
+        .fetch_all::<String>()
+        .await?;
+
+    if sorting_keys
+        .iter()
+        .any(|sorting_key| sorting_key == TRADES_SORTING_KEY)
+    {
+        return Ok(());
+    }
+
+    // left over from an interrupted migration, the old table is still in place
+    let migration_table_name = format!("{}_migration", CLICKHOUSE_TRADES_TABLE_NAME);
+    client
+        .query(format!("DROP TABLE IF EXISTS {}", migration_table_name).as_str())
+        .execute()
+        .await?;
+    create_trades_table_named(client, &migration_table_name).await?;
+
+    let columns = client
+        .query(
+            format!(
+                "
+        SELECT name FROM system.columns
+        WHERE database = currentDatabase() AND table = '{}' AND name != 'trade_ordinal'
+    ",
+                CLICKHOUSE_TRADES_TABLE_NAME,
+            )
+            .as_str(),
+        )
+        .fetch_all::<String>()
+        .await?
+        .join(", ");
+
+    client
+        .query(
+            format!(
+                "
+        INSERT INTO {} ({}, trade_ordinal)
+        SELECT
+            {},
+            row_number() OVER (
+                PARTITION BY slot, transaction_signature, instruction_index, coin_token_address, pool_address, direction
+            ) - 1
+        FROM {}
+    ",
+                migration_table_name, columns, columns, CLICKHOUSE_TRADES_TABLE_NAME,
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+
+    client
+        .query(
+            format!(
+                "EXCHANGE TABLES {} AND {}",
+                CLICKHOUSE_TRADES_TABLE_NAME, migration_table_name
+            )
+            .as_str(),
+        )
+        .execute()
+        .await?;
+    client
+        .query(format!("DROP TABLE {}", migration_table_name).as_str())
+        .execute()
+        .await?;
+
+    Ok(())
+}
+
+pub const CLICKHOUSE_PUMP_FUN_CURVE_PRICES_VIEW_NAME: &str = "pump_fun_curve_prices";
+
+// Curve price of a token after each of its pump.fun trades, in SOL per whole token, and the
+// market cap of its whole supply at that price. Trades of tokens without decimals yet are
+// left out. Curve progress is measured against the SetParams reserves in effect at the
+// trade's slot, or the initial ones before any, so it holds however slots were crawled.
+// Replaced on start so existing views pick up changes.
+pub async fn create_pump_fun_curve_prices_view(
+    client: &Client,
+) -> Result<(), clickhouse::error::Error> {
+    client
+        .query(
+            format!(
+                "
+        CREATE OR REPLACE VIEW {} AS
+        SELECT
+            tra.coin_token_address AS mint_address,
+            tra.transaction_signature AS transaction_signature,
+            tra.instruction_index AS instruction_index,
+            tra.slot AS slot,
+            tra.block_time AS block_time,
+            tra.virtual_sol_reserves AS virtual_sol_reserves,
+            tra.virtual_token_reserves AS virtual_token_reserves,
+            if(par.initial_real_token_reserves > 0, par.initial_virtual_token_reserves, {}) AS curve_initial_virtual_token_reserves,
+            if(par.initial_real_token_reserves > 0, par.initial_real_token_reserves, {}) AS curve_initial_real_token_reserves,
+            least(
+                greatest(
+                    toInt128(tra.virtual_token_reserves)
+                        - (toInt128(curve_initial_virtual_token_reserves) - toInt128(curve_initial_real_token_reserves)),
+                    0
+                ),
+                curve_initial_real_token_reserves
+            ) AS curve_real_token_reserves,
+            (curve_initial_real_token_reserves - curve_real_token_reserves) * 100
+                / greatest(curve_initial_real_token_reserves, 1) AS bonding_curve_progress,
+            (tra.virtual_sol_reserves / 1e9) / (tra.virtual_token_reserves / pow(10, tok.decimals)) AS spot_price,
+            spot_price * ({} / pow(10, tok.decimals)) AS market_cap
+        FROM (
+            SELECT *, 0 AS curve_params_key
+            FROM {}
+            WHERE venue = '{}' AND virtual_token_reserves > 0
+        ) AS tra
+        INNER JOIN (
+            SELECT mint_address, any(decimals) AS decimals
+            FROM {}
+            GROUP BY mint_address
+        ) AS tok ON tok.mint_address = tra.coin_token_address
+        ASOF LEFT JOIN (
+            SELECT 0 AS curve_params_key, slot, initial_virtual_token_reserves, initial_real_token_reserves
+            FROM {} FINAL
+        ) AS par ON par.curve_params_key = tra.curve_params_key AND tra.slot >= par.slot
+    ",
+                CLICKHOUSE_PUMP_FUN_CURVE_PRICES_VIEW_NAME,
+                INITIAL_VIRTUAL_TOKEN_RESERVES,
+                INITIAL_REAL_TOKEN_RESERVES,
+                TOKEN_TOTAL_SUPPLY,
+                CLICKHOUSE_TRADES_TABLE_NAME,
+                TradeVenue::PumpFun.as_str(),
+                CLICKHOUSE_TOKENS_TABLE_NAME,
+                CLICKHOUSE_PUMP_FUN_CURVE_PARAMS_TABLE_NAME,
             )
             .as_str(),
         )
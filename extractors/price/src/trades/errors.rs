@@ -1,4 +1,4 @@
-use crate::crawl_status::table::CrawlStatusOperation;
+use crate::{crawl_status::table::CrawlStatusOperation, rpc::errors::RpcError};
 use crossbeam::channel::SendError;
 use time::error::ComponentRange;
 
@@ -7,7 +7,7 @@ pub enum TradeCrawlError {
     AlreadyCrawled,
     CrawlStatusSend(SendError<CrawlStatusOperation>),
     TransactionFailed,
-    TransactionFetchFailed,
+    TransactionFetchFailed(RpcError),
     TransactionMessageParseFailed,
     BlockTimeParseError(ComponentRange),
 }
@@ -18,7 +18,7 @@ impl std::fmt::Display for TradeCrawlError {
             Self::AlreadyCrawled => write!(f, "Transaction already crawled"),
             Self::CrawlStatusSend(err) => write!(f, "Failed to send crawl status: {}", err),
             Self::TransactionFailed => write!(f, "Transaction failed"),
-            Self::TransactionFetchFailed => write!(f, "Failed to fetch transaction"),
+            Self::TransactionFetchFailed(err) => write!(f, "Failed to fetch transaction: {}", err),
             Self::TransactionMessageParseFailed => write!(f, "Failed to parse transaction message"),
             Self::BlockTimeParseError(err) => write!(f, "Failed to parse block time: {}", err),
         }
@@ -29,6 +29,7 @@ impl std::error::Error for TradeCrawlError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::CrawlStatusSend(err) => Some(err),
+            Self::TransactionFetchFailed(err) => Some(err),
             Self::BlockTimeParseError(err) => Some(err),
             _ => None,
         }
@@ -1,4 +1,5 @@
 pub mod db;
 pub mod errors;
+pub mod signatures;
 pub mod trades;
 pub mod trades_threads;
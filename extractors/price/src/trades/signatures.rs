@@ -0,0 +1,21 @@
+use crate::pump_fun::{program::signatures::TransactionSignature, tokens::MintAddress};
+use solana_sdk::pubkey::Pubkey;
+
+pub type ProgramAddress = Pubkey;
+
+// Signatures to extract trades from, with the account they were listed for. Live
+// subscriptions list them for a whole program rather than for a token's mint.
+#[derive(Debug, Clone)]
+pub enum TradeSignature {
+    TokenMint(MintAddress, TransactionSignature),
+    Program(ProgramAddress, TransactionSignature),
+}
+
+impl TradeSignature {
+    pub fn into_signature(self) -> TransactionSignature {
+        match self {
+            Self::TokenMint(_, signature) => signature,
+            Self::Program(_, signature) => signature,
+        }
+    }
+}
@@ -1,13 +1,37 @@
 use super::errors::TradeCrawlError;
 use crate::{
     crawl_status::{queries::has_crawled_signature, table::CrawlStatusOperation},
-    instructions::instruction::Instruction,
-    pump_fun::trades::trade_from_pump_fun_instruction,
-    raydium::trades::trade_from_raydium_instruction,
-    rpc::pool::{RpcError, RpcPoolManager},
-    trades::db::table::TradeRow,
+    instructions::{instruction::Instruction, parse::InstructionWithLogs},
+    jupiter::{
+        routes::{jupiter_routes_from_instructions, link_route_legs},
+        table::JupiterRouteRow,
+    },
+    meteora_dlmm::trades::trade_from_meteora_dlmm_instruction,
+    orca_whirlpool::{
+        instructions::OrcaWhirlpoolInstruction, trades::trade_from_orca_whirlpool_instruction,
+    },
+    pools::{table::PoolRow, trades::unresolved_trades_from_instructions},
+    pump_fun::{
+        curve::{set_params_events, update_curve_params},
+        events::token_events_from_instructions,
+        params::table::CurveParamsRow,
+        trades::trade_from_pump_fun_instruction,
+    },
+    pump_swap::{
+        pools::pump_swap_pools_from_instructions, trades::trade_from_pump_swap_instruction,
+    },
+    raydium::pools::raydium_pools_from_instructions,
+    raydium_clmm::{
+        instructions::RaydiumClmmInstruction, trades::trade_from_raydium_clmm_instruction,
+    },
+    raydium_cpmm::trades::trade_from_raydium_cpmm_instruction,
+    rpc::pool::RpcPoolManager,
+    token::events::table::TokenEventRow,
+    trades::db::table::{TradeRow, TradeVenue},
     transactions::{
-        config::TRANSACTION_CONFIG, parse::parse_transaction_with_logs,
+        costs::{transaction_costs, TransactionCosts},
+        fetch::get_transactions,
+        parse::parse_transaction_with_logs,
         status::is_failed_transaction,
     },
     utils::log::log_time,
@@ -18,7 +42,7 @@ use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use solana_transaction_status::{
     EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiMessage,
 };
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
 
 fn parse_transaction(
     tx: &EncodedConfirmedTransactionWithStatusMeta,
@@ -26,7 +50,7 @@ fn parse_transaction(
     crawl_status_tx: &Sender<CrawlStatusOperation>,
     pump_fun_program_address: &Pubkey,
     raydium_amm_program_address: &Pubkey,
-) -> Result<Vec<TradeRow>, TradeCrawlError> {
+) -> TokenTradesResult {
     if is_failed_transaction(&tx) {
         return Err(TradeCrawlError::TransactionFailed);
     }
@@ -61,6 +85,126 @@ fn parse_transaction(
         &raydium_amm_program_address,
     );
 
+    rows_from_instructions(
+        &instructions_with_logs,
+        &transaction_costs(&raw_message, &tx.transaction.meta),
+        tx.slot,
+        tx.block_time.unwrap() as u64,
+        token_tx_signature,
+    )
+}
+
+// Everything stored for a transaction
+#[derive(Debug, Default)]
+pub struct TransactionRows {
+    pub trades: Vec<TradeRow>,
+    pub unresolved_trades: Vec<TradeRow>,
+    pub token_events: Vec<TokenEventRow>,
+    pub pools: Vec<PoolRow>,
+    pub jupiter_routes: Vec<JupiterRouteRow>,
+    pub curve_params: Vec<CurveParamsRow>,
+}
+
+impl TransactionRows {
+    pub fn extend(&mut self, other: TransactionRows) {
+        self.trades.extend(other.trades);
+        self.unresolved_trades.extend(other.unresolved_trades);
+        self.token_events.extend(other.token_events);
+        self.pools.extend(other.pools);
+        self.jupiter_routes.extend(other.jupiter_routes);
+        self.curve_params.extend(other.curve_params);
+    }
+}
+
+pub fn rows_from_instructions(
+    instructions_with_logs: &[InstructionWithLogs],
+    costs: &TransactionCosts,
+    slot: u64,
+    block_time: u64,
+    token_tx_signature: &str,
+) -> Result<TransactionRows, TradeCrawlError> {
+    let mut trades =
+        trades_from_instructions(instructions_with_logs, slot, block_time, token_tx_signature)?;
+    let mut unresolved_trades = unresolved_trades_from_instructions(
+        instructions_with_logs,
+        slot,
+        block_time,
+        token_tx_signature,
+    );
+
+    // legs are linked before they are split between stored and waiting for their pool
+    let jupiter_routes = jupiter_routes_from_instructions(instructions_with_logs);
+    link_route_legs(
+        &jupiter_routes,
+        trades.iter_mut().chain(unresolved_trades.iter_mut()),
+    );
+
+    for trade in trades.iter_mut().chain(unresolved_trades.iter_mut()) {
+        trade.costs = costs.clone();
+    }
+    number_trades(trades.iter_mut().chain(unresolved_trades.iter_mut()));
+
+    Ok(TransactionRows {
+        trades,
+        unresolved_trades,
+        token_events: token_events_from_instructions(
+            instructions_with_logs,
+            slot,
+            block_time,
+            token_tx_signature,
+        )?,
+        pools: raydium_pools_from_instructions(instructions_with_logs)
+            .iter()
+            .map(|pool| PoolRow::from_pool(pool, TradeVenue::RaydiumAmm))
+            .chain(
+                pump_swap_pools_from_instructions(instructions_with_logs)
+                    .iter()
+                    .map(|pool| PoolRow::from_pool(pool, TradeVenue::PumpSwap)),
+            )
+            .collect(),
+        jupiter_routes: jupiter_routes
+            .iter()
+            .filter_map(|jupiter_route| {
+                JupiterRouteRow::from_route(jupiter_route, slot, block_time, token_tx_signature)
+            })
+            .collect(),
+        curve_params: set_params_events(instructions_with_logs)
+            .map(|set_params_event| {
+                CurveParamsRow::from_event(set_params_event, slot, block_time, token_tx_signature)
+            })
+            .collect(),
+    })
+}
+
+// Trades sharing an instruction, coin, pool and direction are numbered in the order they were
+// parsed, so a reparse gives each of them the same sorting key again. Unresolved trades have
+// no coin yet, their pool stands in for it.
+pub fn number_trades<'a>(trades: impl Iterator<Item = &'a mut TradeRow>) {
+    let mut counts: HashMap<(u64, String, String, u8), u64> = HashMap::new();
+
+    for trade in trades {
+        let count = counts
+            .entry((
+                trade.instruction_index,
+                trade.coin_token_address.clone(),
+                trade.pool_address.clone(),
+                trade.direction as u8,
+            ))
+            .or_insert(0);
+
+        trade.trade_ordinal = *count;
+        *count += 1;
+    }
+}
+
+pub fn trades_from_instructions(
+    instructions_with_logs: &[InstructionWithLogs],
+    slot: u64,
+    block_time: u64,
+    token_tx_signature: &str,
+) -> Result<Vec<TradeRow>, TradeCrawlError> {
+    update_curve_params(instructions_with_logs, slot);
+
     let mut trades = Vec::new();
 
     for instruction_with_logs in instructions_with_logs.iter() {
@@ -69,8 +213,8 @@ fn parse_transaction(
                 let trade = trade_from_pump_fun_instruction(
                     &instruction,
                     &instruction_index,
-                    tx.slot,
-                    tx.block_time.unwrap() as u64,
+                    slot,
+                    block_time,
                     &instruction_with_logs.cpi_logs,
                     &token_tx_signature,
                 )?;
@@ -80,15 +224,81 @@ fn parse_transaction(
                     None => continue,
                 }
             }
-            Some(Instruction::Raydium(instruction_index, instruction)) => {
-                trade_from_raydium_instruction(
+            Some(Instruction::PumpSwap(instruction_index, instruction)) => {
+                let trade = trade_from_pump_swap_instruction(
+                    &instruction,
+                    &instruction_index,
+                    slot,
+                    block_time,
+                    &instruction_with_logs.cpi_logs,
+                    &token_tx_signature,
+                );
+
+                match trade {
+                    Some(trade) => trade,
+                    None => continue,
+                }
+            }
+            // raydium and whirlpool trades without mints are stored once their pool is resolved
+            Some(Instruction::Raydium(_, _)) => continue,
+            Some(Instruction::RaydiumClmm(_, RaydiumClmmInstruction::Swap(_))) => continue,
+            Some(Instruction::OrcaWhirlpool(_, OrcaWhirlpoolInstruction::Swap(_))) => continue,
+            Some(Instruction::RaydiumCpmm(instruction_index, instruction)) => {
+                let trade = trade_from_raydium_cpmm_instruction(
+                    &instruction,
+                    &instruction_index,
+                    slot,
+                    block_time,
+                    &instruction_with_logs.cpi_logs,
+                    &token_tx_signature,
+                );
+
+                match trade {
+                    Some(trade) => trade,
+                    None => continue,
+                }
+            }
+            Some(Instruction::RaydiumClmm(instruction_index, instruction)) => {
+                let trade = trade_from_raydium_clmm_instruction(
+                    &instruction,
+                    &instruction_index,
+                    slot,
+                    block_time,
+                    &instruction_with_logs.cpi_logs,
+                    &token_tx_signature,
+                );
+
+                match trade {
+                    Some(trade) => trade,
+                    None => continue,
+                }
+            }
+            Some(Instruction::OrcaWhirlpool(instruction_index, instruction)) => {
+                trade_from_orca_whirlpool_instruction(
                     &instruction,
                     &instruction_index,
-                    tx.slot,
-                    tx.block_time.unwrap() as u64,
+                    slot,
+                    block_time,
                     &token_tx_signature,
                 )
             }
+            Some(Instruction::MeteoraDlmm(instruction_index, instruction)) => {
+                let trade = trade_from_meteora_dlmm_instruction(
+                    &instruction,
+                    &instruction_index,
+                    slot,
+                    block_time,
+                    &instruction_with_logs.cpi_logs,
+                    &token_tx_signature,
+                );
+
+                match trade {
+                    Some(trade) => trade,
+                    None => continue,
+                }
+            }
+            // routes are stored on their own, their legs are the trades above
+            Some(Instruction::Jupiter(_, _)) => continue,
             None => continue,
         };
 
@@ -98,58 +308,77 @@ fn parse_transaction(
     Ok(trades)
 }
 
-pub fn token_trade_from_transaction(
+pub type TokenTradesResult = Result<TransactionRows, TradeCrawlError>;
+
+pub async fn token_trades_from_transactions(
     rpc_pool_manager: &RpcPoolManager,
     crawl_status_tx: &Sender<CrawlStatusOperation>,
     dragonfly_client: &Client,
     thread_index: usize,
-    token_tx_signature: &str,
+    token_tx_signatures: &[String],
     pump_fun_program_address: &Pubkey,
     raydium_amm_program_address: &Pubkey,
-) -> Result<Vec<TradeRow>, TradeCrawlError> {
+) -> Vec<(String, TokenTradesResult)> {
     let log_tag = format!(
         "{} token pump fun transactions #{} | ",
         log_time(),
         thread_index
     );
 
-    if let Ok(has_crawled) = has_crawled_signature(&dragonfly_client, &token_tx_signature) {
-        if has_crawled {
-            return Err(TradeCrawlError::AlreadyCrawled);
+    let mut results = Vec::with_capacity(token_tx_signatures.len());
+    let mut to_fetch = Vec::with_capacity(token_tx_signatures.len());
+
+    for token_tx_signature in token_tx_signatures {
+        // offline runs reparse from the cache, so crawled signatures are processed again
+        if rpc_pool_manager.is_offline() {
+            to_fetch.push(token_tx_signature.clone());
+            continue;
+        }
+
+        if let Ok(has_crawled) = has_crawled_signature(&dragonfly_client, &token_tx_signature) {
+            if has_crawled {
+                results.push((
+                    token_tx_signature.clone(),
+                    Err(TradeCrawlError::AlreadyCrawled),
+                ));
+                continue;
+            }
         }
+
+        to_fetch.push(token_tx_signature.clone());
     }
 
-    println!(
-        "{} Getting token transaction for {}",
-        log_tag, token_tx_signature
-    );
-    let tx = rpc_pool_manager.execute(
-        |client| {
-            client.get_transaction_with_config(
-                &Signature::from_str(&token_tx_signature).unwrap(),
-                TRANSACTION_CONFIG,
-            )
-        },
-        Some(thread_index as u64),
-    );
+    if to_fetch.is_empty() {
+        return results;
+    }
 
-    match tx {
-        Err(RpcError::ClientError(error)) => {
-            println!(
-                "{} Error getting token transaction.\n{:?}\nSkipping",
-                log_tag, error
-            );
-            return Err(TradeCrawlError::TransactionFetchFailed);
-        }
-        Ok(tx) => {
-            let trades = parse_transaction(
+    println!("{} Getting {} token transactions", log_tag, to_fetch.len());
+    let signatures = to_fetch
+        .iter()
+        .map(|signature| Signature::from_str(signature).unwrap())
+        .collect::<Vec<_>>();
+    let txs = get_transactions(rpc_pool_manager, &signatures, Some(thread_index as u64)).await;
+
+    for (token_tx_signature, tx) in to_fetch.into_iter().zip(txs) {
+        let result = match tx {
+            Err(error) => {
+                println!(
+                    "{} Error getting token transaction {}.\n{:?}\nSkipping",
+                    log_tag, token_tx_signature, error
+                );
+                Err(TradeCrawlError::TransactionFetchFailed(error))
+            }
+            Ok(tx) => parse_transaction(
                 &tx,
-                token_tx_signature,
+                &token_tx_signature,
                 crawl_status_tx,
                 pump_fun_program_address,
                 raydium_amm_program_address,
-            )?;
-            return Ok(trades);
-        }
+            ),
+        };
+
+        results.push((token_tx_signature, result));
     }
+
+    results
 }
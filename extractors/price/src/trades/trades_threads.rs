@@ -1,34 +1,42 @@
-use super::{errors::TradeCrawlError, trades::token_trade_from_transaction};
+use super::{errors::TradeCrawlError, trades::token_trades_from_transactions};
 use crate::{
     crawl_status::{
         channels::{mark_as_failed, mark_as_succeeded},
         table::CrawlStatusOperation,
     },
     dragonfly::client::dragonfly_client,
-    pump_fun::program::program::get_pump_fun_program_address,
+    jupiter::table::JupiterRouteRow,
+    pools::table::PoolRow,
+    pump_fun::{params::table::CurveParamsRow, program::program::get_pump_fun_program_address},
     raydium::amm::get_raydium_amm_program_address,
-    rpc::{clients::get_rpc_nodes_count, pool::RpcPoolManager},
+    rpc::pool::RpcPoolManager,
     termination::{is_terminated, terminate, terminate_on_error, TerminationFlag},
-    token::mint::signatures::TokenMintSignatures,
-    trades::db::table::TradeRow,
-    utils::log::log_time,
+    token::events::table::TokenEventRow,
+    trades::{db::table::TradeRow, signatures::TradeSignature},
+    utils::{blocking::blocking_call, channels::recv_batch, log::log_time},
 };
 use crossbeam::channel::{Receiver, Sender};
 use std::thread;
 
 pub fn token_trades_threads(
     trades_tx: &Sender<TradeRow>,
-    token_pump_fun_signatures_rx: &Receiver<TokenMintSignatures>,
+    unresolved_trades_tx: &Sender<TradeRow>,
+    token_events_tx: &Sender<TokenEventRow>,
+    pools_tx: &Sender<PoolRow>,
+    jupiter_routes_tx: &Sender<JupiterRouteRow>,
+    curve_params_tx: &Sender<CurveParamsRow>,
+    token_pump_fun_signatures_rx: &Receiver<TradeSignature>,
     rpc_pool_manager: &RpcPoolManager,
     crawl_status_tx: &Sender<CrawlStatusOperation>,
     termination_flag: &TerminationFlag,
 ) -> Vec<thread::JoinHandle<()>> {
-    let concurrency = get_rpc_nodes_count();
+    let concurrency = rpc_pool_manager.get_rpc_nodes_count();
 
     let mut handles = Vec::with_capacity(concurrency);
 
     let pump_fun_program_address = get_pump_fun_program_address();
     let raydium_amm_program_address = get_raydium_amm_program_address();
+    let batch_size = rpc_pool_manager.get_batch_size();
 
     for thread_index in 0..concurrency {
         let log_tag = format!(
@@ -38,6 +46,11 @@ pub fn token_trades_threads(
         );
 
         let trades_tx = trades_tx.clone();
+        let unresolved_trades_tx = unresolved_trades_tx.clone();
+        let token_events_tx = token_events_tx.clone();
+        let pools_tx = pools_tx.clone();
+        let jupiter_routes_tx = jupiter_routes_tx.clone();
+        let curve_params_tx = curve_params_tx.clone();
         let token_pump_fun_signatures_rx = token_pump_fun_signatures_rx.clone();
         let rpc_pool_manager = rpc_pool_manager.clone();
         let crawl_status_tx = crawl_status_tx.clone();
@@ -45,59 +58,116 @@ pub fn token_trades_threads(
         let termination_flag = termination_flag.clone();
 
         let handle = thread::spawn(move || {
-            while let Ok((_mint_address, token_tx_signature)) = token_pump_fun_signatures_rx.recv()
-            {
-                if is_terminated(&termination_flag) {
-                    println!("{} Termination flag set. Exiting", log_tag);
-                    break;
-                }
+            blocking_call(async move {
+                while let Some(batch) = recv_batch(&token_pump_fun_signatures_rx, batch_size) {
+                    if is_terminated(&termination_flag) {
+                        println!("{} Termination flag set. Exiting", log_tag);
+                        break;
+                    }
 
-                let result = token_trade_from_transaction(
-                    &rpc_pool_manager,
-                    &crawl_status_tx,
-                    &dragonfly_client,
-                    thread_index,
-                    &token_tx_signature,
-                    &pump_fun_program_address,
-                    &raydium_amm_program_address,
-                );
+                    let token_tx_signatures = batch
+                        .into_iter()
+                        .map(TradeSignature::into_signature)
+                        .collect::<Vec<_>>();
 
-                match result {
-                    Ok(trades) => {
-                        for trade in trades {
-                            terminate_on_error(&termination_flag, trades_tx.send(trade));
-                        }
+                    let results = token_trades_from_transactions(
+                        &rpc_pool_manager,
+                        &crawl_status_tx,
+                        &dragonfly_client,
+                        thread_index,
+                        &token_tx_signatures,
+                        &pump_fun_program_address,
+                        &raydium_amm_program_address,
+                    )
+                    .await;
 
-                        terminate_on_error(
-                            &termination_flag,
-                            mark_as_succeeded(&crawl_status_tx, &token_tx_signature),
-                        );
-                    }
-                    Err(TradeCrawlError::AlreadyCrawled) => {
-                        println!("{} Transaction already crawled. Skipping", log_tag);
-                        continue;
-                    }
-                    Err(TradeCrawlError::TransactionFailed) => {
-                        println!("{} Transaction failed. Skipping", log_tag);
-                        terminate_on_error(
-                            &termination_flag,
-                            mark_as_succeeded(&crawl_status_tx, &token_tx_signature),
-                        );
-                        continue;
-                    }
-                    Err(err @ TradeCrawlError::TransactionMessageParseFailed)
-                    | Err(err @ TradeCrawlError::TransactionFetchFailed)
-                    | Err(err @ TradeCrawlError::CrawlStatusSend(_))
-                    | Err(err @ TradeCrawlError::BlockTimeParseError(_)) => {
-                        println!("{} Token transaction could not be parsed", log_tag);
-                        terminate_on_error(
-                            &termination_flag,
-                            mark_as_failed(&crawl_status_tx, &token_tx_signature, &err.to_string()),
-                        );
-                        terminate(&termination_flag);
+                    for (token_tx_signature, result) in results {
+                        match result {
+                            Ok(rows) => {
+                                for trade in rows.trades {
+                                    terminate_on_error(&termination_flag, trades_tx.send(trade));
+                                }
+                                for trade in rows.unresolved_trades {
+                                    terminate_on_error(
+                                        &termination_flag,
+                                        unresolved_trades_tx.send(trade),
+                                    );
+                                }
+                                for token_event in rows.token_events {
+                                    terminate_on_error(
+                                        &termination_flag,
+                                        token_events_tx.send(token_event),
+                                    );
+                                }
+                                for pool in rows.pools {
+                                    terminate_on_error(&termination_flag, pools_tx.send(pool));
+                                }
+                                for jupiter_route in rows.jupiter_routes {
+                                    terminate_on_error(
+                                        &termination_flag,
+                                        jupiter_routes_tx.send(jupiter_route),
+                                    );
+                                }
+                                for curve_params in rows.curve_params {
+                                    terminate_on_error(
+                                        &termination_flag,
+                                        curve_params_tx.send(curve_params),
+                                    );
+                                }
+
+                                terminate_on_error(
+                                    &termination_flag,
+                                    mark_as_succeeded(&crawl_status_tx, &token_tx_signature),
+                                );
+                            }
+                            Err(TradeCrawlError::AlreadyCrawled) => {
+                                println!("{} Transaction already crawled. Skipping", log_tag);
+                                continue;
+                            }
+                            Err(TradeCrawlError::TransactionFailed) => {
+                                println!("{} Transaction failed. Skipping", log_tag);
+                                terminate_on_error(
+                                    &termination_flag,
+                                    mark_as_succeeded(&crawl_status_tx, &token_tx_signature),
+                                );
+                                continue;
+                            }
+                            Err(TradeCrawlError::TransactionFetchFailed(error))
+                                if !error.is_fatal() =>
+                            {
+                                println!(
+                                "{} Token transaction fetch failed after retries: {}. Continuing",
+                                log_tag, error
+                            );
+                                terminate_on_error(
+                                    &termination_flag,
+                                    mark_as_failed(
+                                        &crawl_status_tx,
+                                        &token_tx_signature,
+                                        &error.to_string(),
+                                    ),
+                                );
+                                continue;
+                            }
+                            Err(err @ TradeCrawlError::TransactionMessageParseFailed)
+                            | Err(err @ TradeCrawlError::TransactionFetchFailed(_))
+                            | Err(err @ TradeCrawlError::CrawlStatusSend(_))
+                            | Err(err @ TradeCrawlError::BlockTimeParseError(_)) => {
+                                println!("{} Token transaction could not be parsed", log_tag);
+                                terminate_on_error(
+                                    &termination_flag,
+                                    mark_as_failed(
+                                        &crawl_status_tx,
+                                        &token_tx_signature,
+                                        &err.to_string(),
+                                    ),
+                                );
+                                terminate(&termination_flag);
+                            }
+                        }
                     }
                 }
-            }
+            })
         });
 
         handles.push(handle);
@@ -0,0 +1,131 @@
+use super::parse::{flatten_instructions, full_account_keys};
+use crate::{
+    compute_budget::{
+        instructions::{parse_compute_budget_instruction, ComputeBudgetInstruction},
+        program::{
+            BUILTIN_PROGRAM_ADDRESSES, COMPUTE_BUDGET_PROGRAM_ADDRESS,
+            DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT, MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT,
+            MAX_COMPUTE_UNIT_LIMIT, MICRO_LAMPORTS_PER_LAMPORT,
+        },
+    },
+    pools::transfers::parse_inner_transfer_instruction,
+    system::program::SYSTEM_PROGRAM_ADDRESS,
+};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, UiRawMessage, UiTransactionStatusMeta,
+};
+
+// Jito's block engine only takes tips paid to one of these
+pub const JITO_TIP_ADDRESSES: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+// What landing a transaction cost whoever paid for it. Fees are in lamports, the compute unit
+// price in micro lamports.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionCosts {
+    pub fee_payer_address: String,
+    pub base_fee: u64,
+    pub priority_fee: u64,
+    pub compute_unit_price: u64,
+    pub compute_unit_limit: u64,
+    pub compute_units_consumed: Option<u64>,
+    pub jito_tip: u64,
+}
+
+// The priority fee is charged on the requested limit, not on what was consumed. Without a
+// requested limit the runtime gives every top level instruction the default limit, and
+// builtin ones, compute budget included, only the builtin allocation. Whatever else the fee
+// covers is signature fees.
+pub fn transaction_costs(
+    raw_message: &UiRawMessage,
+    meta: &Option<UiTransactionStatusMeta>,
+) -> TransactionCosts {
+    let account_keys = full_account_keys(raw_message, meta);
+    let instructions = flatten_instructions(raw_message, meta);
+
+    let mut compute_unit_limit = None;
+    let mut compute_unit_price = 0;
+    let mut default_compute_unit_limit = 0;
+    for instruction in instructions.iter() {
+        let program_address = account_keys
+            .get(instruction.program_id_index)
+            .map(String::as_str)
+            .unwrap_or_default();
+
+        default_compute_unit_limit += if BUILTIN_PROGRAM_ADDRESSES.contains(&program_address) {
+            MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT
+        } else {
+            DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT
+        };
+
+        if program_address != COMPUTE_BUDGET_PROGRAM_ADDRESS {
+            continue;
+        }
+
+        match parse_compute_budget_instruction(instruction) {
+            Some(ComputeBudgetInstruction::SetComputeUnitLimit(units)) => {
+                compute_unit_limit = Some(units)
+            }
+            Some(ComputeBudgetInstruction::SetComputeUnitPrice(price)) => {
+                compute_unit_price = price
+            }
+            None => {}
+        }
+    }
+
+    let compute_unit_limit = compute_unit_limit
+        .unwrap_or(default_compute_unit_limit)
+        .min(MAX_COMPUTE_UNIT_LIMIT);
+    let priority_fee = (compute_unit_price as u128 * compute_unit_limit as u128)
+        .div_ceil(MICRO_LAMPORTS_PER_LAMPORT as u128) as u64;
+
+    // tips can be paid from a top level instruction or from a program the transaction calls
+    let jito_tip = instructions
+        .iter()
+        .flat_map(|instruction| {
+            std::iter::once(instruction).chain(instruction.inner_instructions.iter().flatten())
+        })
+        .filter(|instruction| {
+            account_keys
+                .get(instruction.program_id_index)
+                .map(String::as_str)
+                == Some(SYSTEM_PROGRAM_ADDRESS)
+        })
+        .filter_map(|instruction| parse_inner_transfer_instruction(instruction, &account_keys))
+        .filter(|(_, _, destination)| JITO_TIP_ADDRESSES.contains(&destination.as_str()))
+        .map(|(lamports, _, _)| lamports)
+        .sum();
+
+    let (fee, compute_units_consumed) = match meta {
+        Some(meta) => (
+            meta.fee,
+            match meta.compute_units_consumed {
+                OptionSerializer::Some(units) => Some(units),
+                _ => None,
+            },
+        ),
+        None => (0, None),
+    };
+
+    TransactionCosts {
+        fee_payer_address: raw_message
+            .account_keys
+            .first()
+            .cloned()
+            .unwrap_or_default(),
+        base_fee: fee.saturating_sub(priority_fee),
+        priority_fee,
+        compute_unit_price,
+        compute_unit_limit,
+        compute_units_consumed,
+        jito_tip,
+    }
+}
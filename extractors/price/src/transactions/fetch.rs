@@ -0,0 +1,46 @@
+use super::config::TRANSACTION_CONFIG;
+use crate::rpc::{batch::execute_batched, errors::RpcError, pool::RpcPoolManager};
+use serde_json::json;
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+
+pub type TransactionFetchResult = Result<EncodedConfirmedTransactionWithStatusMeta, RpcError>;
+
+// Serves what it can from the local transaction cache and only sends the misses to rpc.
+pub async fn get_transactions(
+    rpc_pool_manager: &RpcPoolManager,
+    signatures: &[Signature],
+    pin: Option<u64>,
+) -> Vec<TransactionFetchResult> {
+    let mut results = signatures
+        .iter()
+        .map(|signature| rpc_pool_manager.get_cached_transaction(signature).map(Ok))
+        .collect::<Vec<Option<TransactionFetchResult>>>();
+
+    let misses = results
+        .iter()
+        .enumerate()
+        .filter_map(|(index, result)| result.is_none().then_some(index))
+        .collect::<Vec<_>>();
+
+    if !misses.is_empty() {
+        let params = misses
+            .iter()
+            .map(|index| json!([signatures[*index].to_string(), TRANSACTION_CONFIG]))
+            .collect();
+
+        let fetched: Vec<TransactionFetchResult> =
+            execute_batched(rpc_pool_manager, RpcRequest::GetTransaction, params, pin).await;
+
+        for (index, result) in misses.into_iter().zip(fetched) {
+            if let Ok(tx) = &result {
+                rpc_pool_manager.cache_transaction(&signatures[index], tx);
+            }
+
+            results[index] = Some(result);
+        }
+    }
+
+    results.into_iter().flatten().collect()
+}
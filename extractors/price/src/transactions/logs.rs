@@ -0,0 +1,171 @@
+// One program invocation as reconstructed from the transaction logs. Top-level
+// invocations line up with the message instructions, and the pre-order walk of an
+// invocation's descendants lines up with that instruction's inner instructions.
+// TODO: use logs, compute and return data, remove allow dead
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProgramInvocation {
+    pub program_id: String,
+    pub depth: usize,
+    pub logs: Vec<String>,
+    pub data: Vec<String>,
+    pub compute_units_consumed: Option<u64>,
+    pub return_data: Option<String>,
+    pub succeeded: Option<bool>,
+    pub invocations: Vec<ProgramInvocation>,
+}
+
+impl ProgramInvocation {
+    pub fn descendants(&self) -> Vec<&ProgramInvocation> {
+        let mut descendants = Vec::new();
+        for invocation in &self.invocations {
+            descendants.push(invocation);
+            descendants.extend(invocation.descendants());
+        }
+        descendants
+    }
+}
+
+enum LogLine<'a> {
+    Invoke(&'a str, usize),
+    Success(&'a str),
+    Failed(&'a str),
+    Consumed(&'a str, u64),
+    Log(&'a str),
+    Data(&'a str),
+    Return(&'a str, &'a str),
+    Other,
+}
+
+// Program output always comes after one of the `log:`, `data:` or `return:` prefixes,
+// so text a program logs can never pass for an invoke or a result.
+fn parse_log_line(line: &str) -> LogLine {
+    if let Some(message) = line.strip_prefix("Program log: ") {
+        return LogLine::Log(message);
+    }
+    if let Some(data) = line.strip_prefix("Program data: ") {
+        return LogLine::Data(data);
+    }
+    if let Some(return_data) = line.strip_prefix("Program return: ") {
+        return match return_data.split_once(' ') {
+            Some((program_id, data)) => LogLine::Return(program_id, data),
+            None => LogLine::Other,
+        };
+    }
+
+    let Some((program_id, event)) = line
+        .strip_prefix("Program ")
+        .and_then(|rest| rest.split_once(' '))
+    else {
+        return LogLine::Other;
+    };
+
+    if let Some(depth) = event
+        .strip_prefix("invoke [")
+        .and_then(|depth| depth.strip_suffix(']'))
+        .and_then(|depth| depth.parse().ok())
+    {
+        LogLine::Invoke(program_id, depth)
+    } else if event == "success" {
+        LogLine::Success(program_id)
+    } else if event.starts_with("failed") {
+        LogLine::Failed(program_id)
+    } else if let Some(units) = event
+        .strip_prefix("consumed ")
+        .and_then(|consumed| consumed.split_whitespace().next())
+        .and_then(|units| units.parse().ok())
+    {
+        LogLine::Consumed(program_id, units)
+    } else {
+        LogLine::Other
+    }
+}
+
+fn close_invocation(
+    stack: &mut Vec<ProgramInvocation>,
+    invocations: &mut Vec<ProgramInvocation>,
+    succeeded: Option<bool>,
+) {
+    if let Some(mut invocation) = stack.pop() {
+        invocation.succeeded = succeeded;
+        match stack.last_mut() {
+            Some(parent) => parent.invocations.push(invocation),
+            None => invocations.push(invocation),
+        }
+    }
+}
+
+fn close_program(
+    stack: &mut Vec<ProgramInvocation>,
+    invocations: &mut Vec<ProgramInvocation>,
+    program_id: &str,
+    succeeded: bool,
+) {
+    if stack
+        .last()
+        .is_some_and(|invocation| invocation.program_id == program_id)
+    {
+        close_invocation(stack, invocations, Some(succeeded));
+    }
+}
+
+pub fn parse_program_logs(logs: &[String]) -> Vec<ProgramInvocation> {
+    let mut invocations = Vec::new();
+    let mut stack: Vec<ProgramInvocation> = Vec::new();
+
+    for line in logs {
+        match parse_log_line(line) {
+            LogLine::Invoke(program_id, depth) => {
+                // a missing result line (truncated logs) leaves deeper invocations open
+                while stack.len() >= depth {
+                    close_invocation(&mut stack, &mut invocations, None);
+                }
+
+                stack.push(ProgramInvocation {
+                    program_id: program_id.to_string(),
+                    depth,
+                    ..Default::default()
+                });
+            }
+            LogLine::Success(program_id) => {
+                close_program(&mut stack, &mut invocations, program_id, true);
+            }
+            LogLine::Failed(program_id) => {
+                close_program(&mut stack, &mut invocations, program_id, false);
+            }
+            LogLine::Consumed(program_id, units) => {
+                if let Some(invocation) = stack
+                    .last_mut()
+                    .filter(|invocation| invocation.program_id == program_id)
+                {
+                    invocation.compute_units_consumed = Some(units);
+                }
+            }
+            LogLine::Log(message) => {
+                if let Some(invocation) = stack.last_mut() {
+                    invocation.logs.push(message.to_string());
+                }
+            }
+            LogLine::Data(data) => {
+                if let Some(invocation) = stack.last_mut() {
+                    invocation.data.push(data.to_string());
+                }
+            }
+            LogLine::Return(program_id, data) => {
+                if let Some(invocation) = stack
+                    .last_mut()
+                    .filter(|invocation| invocation.program_id == program_id)
+                {
+                    invocation.return_data = Some(data.to_string());
+                }
+            }
+            LogLine::Other => continue,
+        }
+    }
+
+    while !stack.is_empty() {
+        close_invocation(&mut stack, &mut invocations, None);
+    }
+
+    invocations
+}
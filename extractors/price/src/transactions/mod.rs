@@ -1,3 +1,6 @@
 pub mod config;
+pub mod costs;
+pub mod fetch;
+pub mod logs;
 pub mod parse;
 pub mod status;
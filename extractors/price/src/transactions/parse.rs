@@ -1,14 +1,54 @@
 use crate::{
     cpi::cpi::CpiLog,
-    instructions::parse::{parse_single_instruction, InstructionWithLogs, ParsableInstruction},
-    pump_fun::cpi::PumpFunCpiLog,
+    instructions::parse::{
+        invoked_instructions, parse_single_instruction, InstructionWithLogs, ParsableInstruction,
+    },
+    jupiter::{cpi::jupiter_cpi_log_from_event_instruction, program::JUPITER_ADDRESS},
+    meteora_dlmm::{
+        cpi::meteora_dlmm_cpi_log_from_event_instruction, program::METEORA_DLMM_ADDRESS,
+    },
+    pump_fun::cpi::pump_fun_cpi_log_from_event_data,
+    pump_swap::{cpi::pump_swap_cpi_log_from_event_instruction, program::PUMP_SWAP_ADDRESS},
+    raydium_clmm::{cpi::raydium_clmm_cpi_log_from_event_data, program::RAYDIUM_CLMM_ADDRESS},
+    raydium_cpmm::{cpi::raydium_cpmm_cpi_log_from_event_data, program::RAYDIUM_CPMM_ADDRESS},
+    transactions::logs::{parse_program_logs, ProgramInvocation},
 };
 use solana_sdk::pubkey::Pubkey;
 use solana_transaction_status::{
-    option_serializer::OptionSerializer, UiInstruction, UiRawMessage, UiTransactionStatusMeta,
+    option_serializer::OptionSerializer, EncodedTransaction, UiInstruction, UiMessage,
+    UiRawMessage, UiTransactionStatusMeta,
 };
 use std::collections::HashMap;
 
+// Signature and message of a json encoded transaction
+pub fn raw_message(transaction: &EncodedTransaction) -> Option<(&String, &UiRawMessage)> {
+    match transaction {
+        EncodedTransaction::Json(tx_json) => match &tx_json.message {
+            UiMessage::Raw(raw_message) => Some((tx_json.signatures.first()?, raw_message)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// v0 transactions index past the static keys into the addresses loaded from lookup
+// tables, writable ones first, then readonly.
+pub fn full_account_keys(
+    raw_message: &UiRawMessage,
+    meta: &Option<UiTransactionStatusMeta>,
+) -> Vec<String> {
+    let mut account_keys = raw_message.account_keys.clone();
+
+    if let Some(meta) = meta {
+        if let OptionSerializer::Some(loaded_addresses) = &meta.loaded_addresses {
+            account_keys.extend(loaded_addresses.writable.iter().cloned());
+            account_keys.extend(loaded_addresses.readonly.iter().cloned());
+        }
+    }
+
+    account_keys
+}
+
 pub fn parse_transaction_with_logs(
     meta: &Option<UiTransactionStatusMeta>,
     raw_message: &UiRawMessage,
@@ -17,66 +57,71 @@ pub fn parse_transaction_with_logs(
 ) -> Vec<InstructionWithLogs> {
     let mut result = Vec::new();
 
-    let account_keys = &raw_message.account_keys;
+    let account_keys = &full_account_keys(raw_message, meta);
     let all_instructions = flatten_instructions(raw_message, meta);
 
-    if let Some(meta) = meta {
-        if let OptionSerializer::Some(logs) = &meta.log_messages {
-            let mut current_instruction_index = 0;
-            let mut current_cpi_logs = Vec::new();
-
-            let mut stack_depth = 0;
-
-            for log in logs {
-                if log.contains("invoke") {
-                    stack_depth += 1;
-                } else if log.contains("success") || log.contains("failed") {
-                    stack_depth -= 1;
-
-                    if stack_depth == 0 {
-                        let parsable_ix = &all_instructions[current_instruction_index];
-                        let instruction = if current_instruction_index < all_instructions.len() {
-                            parse_single_instruction(
-                                &parsable_ix,
-                                account_keys,
-                                current_instruction_index as u64,
-                                pump_fun_program_id,
-                                raydium_amm_program_id,
-                                &all_instructions,
-                            )
-                        } else {
-                            None
-                        };
-
-                        result.push(InstructionWithLogs {
-                            instruction,
-                            cpi_logs: current_cpi_logs.clone(),
-                        });
-
-                        if let Some(inner_instructions) = &parsable_ix.inner_instructions {
-                            for inner_ix in inner_instructions {
-                                let instruction = parse_single_instruction(
-                                    &inner_ix,
-                                    account_keys,
-                                    inner_ix.instruction_index as u64,
-                                    pump_fun_program_id,
-                                    raydium_amm_program_id,
-                                    &inner_instructions,
-                                );
-
-                                result.push(InstructionWithLogs {
-                                    instruction,
-                                    cpi_logs: current_cpi_logs.clone(),
-                                });
-                            }
-                        }
-
-                        current_cpi_logs.clear();
-                        current_instruction_index += 1;
-                    }
-                } else if let Some(cpi_log) = PumpFunCpiLog::from_encoded_log(log) {
-                    current_cpi_logs.push(CpiLog::PumpFun(cpi_log));
-                }
+    let invocations = match meta {
+        Some(UiTransactionStatusMeta {
+            log_messages: OptionSerializer::Some(logs),
+            ..
+        }) => parse_program_logs(logs),
+        _ => return result,
+    };
+
+    // truncated logs can end before the last instructions, those are left out
+    for (instruction_index, (parsable_ix, invocation)) in
+        all_instructions.iter().zip(&invocations).enumerate()
+    {
+        let instruction = parse_single_instruction(
+            parsable_ix,
+            account_keys,
+            instruction_index as u64,
+            pump_fun_program_id,
+            raydium_amm_program_id,
+            &all_instructions,
+        );
+
+        let mut cpi_logs = cpi_logs_from_invocation(invocation, pump_fun_program_id);
+        cpi_logs.extend(cpi_logs_from_event_instructions(
+            parsable_ix,
+            &all_instructions,
+            account_keys,
+        ));
+
+        result.push(InstructionWithLogs {
+            instruction,
+            cpi_logs,
+        });
+
+        if let Some(inner_instructions) = &parsable_ix.inner_instructions {
+            let inner_invocations = invocation.descendants();
+
+            for (inner_position, inner_ix) in inner_instructions.iter().enumerate() {
+                let instruction = parse_single_instruction(
+                    inner_ix,
+                    account_keys,
+                    inner_ix.instruction_index as u64,
+                    pump_fun_program_id,
+                    raydium_amm_program_id,
+                    inner_instructions,
+                );
+
+                let mut cpi_logs = inner_invocations
+                    .get(inner_position)
+                    .map(|inner_invocation| {
+                        cpi_logs_from_invocation(inner_invocation, pump_fun_program_id)
+                    })
+                    .unwrap_or_default();
+                cpi_logs.extend(cpi_logs_from_event_instructions(
+                    inner_ix,
+                    inner_instructions,
+                    account_keys,
+                ));
+
+                result.push(InstructionWithLogs {
+                    instruction,
+                    cpi_logs,
+                });
             }
         }
     }
@@ -84,7 +129,57 @@ pub fn parse_transaction_with_logs(
     result
 }
 
-fn flatten_instructions(
+// Events are attributed to the invocation that logged them, not to its callers.
+fn cpi_logs_from_invocation(
+    invocation: &ProgramInvocation,
+    pump_fun_program_id: &Pubkey,
+) -> Vec<CpiLog> {
+    let cpi_log_from_event_data: fn(&str) -> Option<CpiLog> =
+        if invocation.program_id == pump_fun_program_id.to_string() {
+            pump_fun_cpi_log_from_event_data
+        } else if invocation.program_id == RAYDIUM_CPMM_ADDRESS {
+            raydium_cpmm_cpi_log_from_event_data
+        } else if invocation.program_id == RAYDIUM_CLMM_ADDRESS {
+            raydium_clmm_cpi_log_from_event_data
+        } else {
+            return Vec::new();
+        };
+
+    invocation
+        .data
+        .iter()
+        .filter_map(|data| cpi_log_from_event_data(data))
+        .collect()
+}
+
+// emit_cpi! events are not logged but passed to a call the program makes into itself, they
+// belong to the instruction that made it.
+fn cpi_logs_from_event_instructions(
+    parsable: &ParsableInstruction,
+    inner_instructions: &Vec<ParsableInstruction>,
+    account_keys: &[String],
+) -> Vec<CpiLog> {
+    let program_id = match account_keys.get(parsable.program_id_index) {
+        Some(program_id) => program_id.as_str(),
+        None => return Vec::new(),
+    };
+
+    let cpi_log_from_event_instruction: fn(&[u8]) -> Option<CpiLog> = match program_id {
+        PUMP_SWAP_ADDRESS => pump_swap_cpi_log_from_event_instruction,
+        METEORA_DLMM_ADDRESS => meteora_dlmm_cpi_log_from_event_instruction,
+        JUPITER_ADDRESS => jupiter_cpi_log_from_event_instruction,
+        _ => return Vec::new(),
+    };
+
+    invoked_instructions(parsable, inner_instructions)
+        .iter()
+        .filter(|ix| ix.program_id_index == parsable.program_id_index)
+        .filter_map(|ix| bs58::decode(&ix.data).into_vec().ok())
+        .filter_map(|data| cpi_log_from_event_instruction(&data))
+        .collect()
+}
+
+pub fn flatten_instructions(
     raw_message: &UiRawMessage,
     meta: &Option<UiTransactionStatusMeta>,
 ) -> Vec<ParsableInstruction> {
@@ -107,6 +202,7 @@ fn flatten_instructions(
                                 inner_instructions: None,
                                 instruction_index: inner_ix_set.index as usize,
                                 inner_instruction_index: Some(inner_instruction_index),
+                                stack_height: ix.stack_height,
                             }),
                             _ => None,
                         })
@@ -137,6 +233,7 @@ fn flatten_instructions(
                     inner_instructions: None,
                     instruction_index,
                     inner_instruction_index: Some(inner_instruction_index),
+                    stack_height: iix.stack_height,
                 })
                 .collect::<Vec<_>>();
 
@@ -147,6 +244,7 @@ fn flatten_instructions(
                 inner_instructions: Some(new_inner_instructions.clone()),
                 instruction_index,
                 inner_instruction_index: None,
+                stack_height: ix.stack_height,
             };
 
             parsable
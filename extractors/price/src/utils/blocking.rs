@@ -1,10 +1,19 @@
-use std::future::Future;
+use std::{future::Future, sync::OnceLock};
 
 use tokio::runtime::Runtime;
 
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+// One runtime for the whole process so the rpc and db clients keep their
+// connection pools alive between calls. Worker threads enter it once and await their
+// rpc calls from there, a blocking call from inside it would panic.
+pub fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| Runtime::new().unwrap())
+}
+
 pub fn blocking_call<F, T>(f: F) -> T
 where
     F: Future<Output = T>,
 {
-    Runtime::new().unwrap().block_on(f)
+    runtime().block_on(f)
 }
@@ -0,0 +1,16 @@
+use crossbeam::channel::Receiver;
+
+// Blocks for the first message, then takes whatever else is already queued up to `max`.
+pub fn recv_batch<T>(rx: &Receiver<T>, max: usize) -> Option<Vec<T>> {
+    let first = rx.recv().ok()?;
+
+    let mut batch = vec![first];
+    while batch.len() < max {
+        match rx.try_recv() {
+            Ok(message) => batch.push(message),
+            Err(_) => break,
+        }
+    }
+
+    Some(batch)
+}
@@ -1,2 +1,3 @@
 pub mod blocking;
+pub mod channels;
 pub mod log;
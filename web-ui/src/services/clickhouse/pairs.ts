@@ -5,6 +5,7 @@ export const TRADES_TABLE_NAME = 'trades';
 export const TOKENS_TABLE_NAME = 'tokens';
 export const CRAWL_STATUS_TABLE_NAME = 'crawl_status';
 export const CURRENT_PRICES_TABLE_NAME = 'trades_current_prices';
+export const JUPITER_ROUTES_TABLE_NAME = 'jupiter_routes';
 
 const pairSchema = z
   .object({
@@ -1,6 +1,6 @@
 import { z } from 'zod';
 import { client } from '../client';
-import { TRADES_TABLE_NAME, Pair } from '../pairs';
+import { TRADES_TABLE_NAME, JUPITER_ROUTES_TABLE_NAME, Pair } from '../pairs';
 
 export type ClickhouseInterval = 'second' | 'minute' | 'hour' | 'day';
 
@@ -82,7 +82,12 @@ const getCandles = async (
   resolution: CandleResolution,
   pair: Pick<Pair, 'coinTokenAddress' | 'priceCoinTokenAddress'>,
 ): Promise<Candle[]> => {
-  const whereClause = `WHERE coin_token_address = '${pair.coinTokenAddress}' AND price_coin_token_address = '${pair.priceCoinTokenAddress}'`;
+  const whereClause = `WHERE tra.coin_token_address = '${pair.coinTokenAddress}' AND tra.price_coin_token_address = '${pair.priceCoinTokenAddress}'`;
+
+  // A route leg whose coin is neither what the user paid nor what they got is bought and
+  // sold again within the route, so it only adds to volume and not to buy or sell pressure.
+  // Pump.fun trades are priced at their curve after the trade rather than at the trade's
+  // average, both in the raw units of the pair.
 
   const result = await client.query({
     query: `
@@ -90,19 +95,29 @@ SELECT
   tra.coin_token_address as coin_token_address,
   tra.price_coin_token_address as price_coin_token_address,
   toStartOfInterval(toDateTime64(tra.block_time, 0), INTERVAL ${resolution.value} ${resolution.unit.toUpperCase()}) as time_bucket,
-  coalesce(min(tra.price_coin_token_amount/nullIf(tra.coin_token_amount, 0)), 0) as low_price,
-  coalesce(max(tra.price_coin_token_amount/nullIf(tra.coin_token_amount, 0)), 0) as high_price,
-  coalesce(argMin(tra.price_coin_token_amount/nullIf(tra.coin_token_amount, 0), (tra.block_time, tra.slot, tra.instruction_index)), 0) as open_price,
-  coalesce(argMax(tra.price_coin_token_amount/nullIf(tra.coin_token_amount, 0), (tra.block_time, tra.slot, tra.instruction_index)), 0) as close_price,
-  sum(if(tra.direction = 'buy', tra.coin_token_amount, 0)) as buy_volume,
-  sum(if(tra.direction = 'sell', tra.coin_token_amount, 0)) as sell_volume,
+  coalesce(min(tra.price), 0) as low_price,
+  coalesce(max(tra.price), 0) as high_price,
+  coalesce(argMin(tra.price, (tra.block_time, tra.slot, tra.instruction_index)), 0) as open_price,
+  coalesce(argMax(tra.price, (tra.block_time, tra.slot, tra.instruction_index)), 0) as close_price,
+  sum(if(tra.direction = 'buy' AND NOT tra.is_pass_through, tra.coin_token_amount, 0)) as buy_volume,
+  sum(if(tra.direction = 'sell' AND NOT tra.is_pass_through, tra.coin_token_amount, 0)) as sell_volume,
   sum(tra.coin_token_amount) as volume,
   count() as trade_count
 FROM (
    SELECT DISTINCT
-       coin_token_address, price_coin_token_address, block_time, slot, instruction_index, direction, 
-       price_coin_token_amount, coin_token_amount, transaction_signature
+       tra.coin_token_address as coin_token_address, tra.price_coin_token_address as price_coin_token_address,
+       tra.block_time as block_time, tra.slot as slot, tra.instruction_index as instruction_index,
+       tra.direction as direction, tra.price_coin_token_amount as price_coin_token_amount,
+       tra.coin_token_amount as coin_token_amount, tra.transaction_signature as transaction_signature,
+       if(tra.virtual_token_reserves > 0,
+         tra.virtual_sol_reserves/tra.virtual_token_reserves,
+         tra.price_coin_token_amount/nullIf(tra.coin_token_amount, 0)) as price,
+       tra.route_id IS NOT NULL
+         AND rou.transaction_signature != ''
+         AND tra.coin_token_address NOT IN (rou.input_mint_address, rou.output_mint_address) as is_pass_through
    FROM ${TRADES_TABLE_NAME} as tra
+   LEFT JOIN ${JUPITER_ROUTES_TABLE_NAME} as rou
+     ON rou.transaction_signature = tra.transaction_signature AND rou.route_id = tra.route_id
    ${whereClause}
 ) as tra
 GROUP BY tra.coin_token_address, tra.price_coin_token_address, time_bucket